@@ -7,7 +7,9 @@ use crate::configure::{PdfVersion, ValidationError};
 use crate::error::KrillaResult;
 use crate::interchange::metadata::{pdf_date, Metadata};
 use crate::metadata::PageLayout;
+use crate::resource::Resource;
 use crate::serialize::SerializeContext;
+use crate::text::{CIDIdentifier, FontIdentifier, WritingMode};
 use crate::util::{stable_hash_base64, Deferred};
 
 type DChunk = Deferred<Chunk>;
@@ -41,6 +43,7 @@ pub(crate) struct NonStreamChunks {
     pub(crate) outline: Option<(Ref, Chunk)>,
     pub(crate) page_label_tree: Option<(Ref, Chunk)>,
     pub(crate) destination_profiles: Option<(Ref, Chunk)>,
+    pub(crate) output_intents: Chunk,
     pub(crate) struct_tree_root: Option<(Ref, Chunk)>,
     pub(crate) struct_elements: Option<Chunk>,
     pub(crate) page_labels: Chunk,
@@ -79,6 +82,7 @@ impl ChunkContainer {
                 outline: None,
                 page_label_tree: None,
                 destination_profiles: None,
+                output_intents: sc.new_chunk(),
                 struct_tree_root: None,
                 struct_elements: None,
                 page_labels: sc.new_chunk(),
@@ -197,6 +201,7 @@ impl ChunkContainer {
 
         let named_destinations = sc.global_objects.named_destinations.take();
         let embedded_files = sc.global_objects.embedded_files.take();
+        let acro_form = sc.global_objects.acro_form.take();
 
         // We only write a catalog if a page tree exists. Every valid PDF must have one
         // and krilla ensures that there always is one, but for snapshot tests, it can be
@@ -341,6 +346,19 @@ impl ChunkContainer {
                 }
             }
 
+            let form_fonts = acro_form
+                .fonts()
+                .map(|(name, font)| {
+                    let font_ref = sc
+                        .register_font_identifier(FontIdentifier::Cid(CIDIdentifier(
+                            font.clone(),
+                            WritingMode::Horizontal,
+                        )))
+                        .get_ref();
+                    (name, remapper[&font_ref])
+                })
+                .collect::<Vec<_>>();
+
             // we create a placeholder for Contents and ByteRange here
             // then we will post-processing (after write to PDF binary) later.
             // post-processing includes
@@ -350,7 +368,7 @@ impl ChunkContainer {
             //   2.2 after '<BEEFFACE00..00>' to EOF
             // *Note*: 'BEEFFACE' and '88888888' just hex text for seeking position only
             // *NOTE*: please use the same Contents length in post-processing function
-            if let (Some(sig), Some(date_pdf), Some(pt)) = (
+            let signature = if let (Some(sig), Some(date_pdf), Some(pt)) = (
                 sc.signer.as_ref(),
                 self.metadata.as_ref().and_then(|meta| meta.creation_date),
                 &self.non_stream.page_tree,
@@ -358,6 +376,53 @@ impl ChunkContainer {
                 let widget_id = remapped_ref.bump();
                 let sig_id = remapped_ref.bump();
 
+                catalog
+                    .insert(Name(b"Perms"))
+                    .dict()
+                    .pair(Name(b"DocMDP"), sig_id);
+
+                Some((sig, date_pdf, pt, widget_id, sig_id))
+            } else {
+                None
+            };
+
+            if !acro_form.is_empty() || signature.is_some() {
+                let mut acro_form_dict = catalog.insert(Name(b"AcroForm")).dict();
+
+                if signature.is_some() {
+                    acro_form_dict.pair(Name(b"SigFlags"), 3);
+                }
+
+                let mut fields = acro_form_dict.insert(Name(b"Fields")).array();
+
+                for field in acro_form.fields() {
+                    fields.item(remapper[field]);
+                }
+
+                if let Some((.., widget_id, _)) = signature {
+                    fields.item(widget_id);
+                }
+
+                fields.finish();
+
+                if !form_fonts.is_empty() {
+                    let mut resources = acro_form_dict.insert(Name(b"DR")).dict();
+                    let mut font_dict = resources.insert(Name(b"Font")).dict();
+
+                    for (name, font_ref) in &form_fonts {
+                        font_dict.pair(Name(name.as_bytes()), *font_ref);
+                    }
+
+                    font_dict.finish();
+                    resources.finish();
+                }
+
+                acro_form_dict.finish();
+            }
+
+            catalog.finish();
+
+            if let Some((sig, date_pdf, pt, widget_id, sig_id)) = signature {
                 // we need signature Contents from [cryptographic_message_syntax](https://github.com/indygreg/cryptography-rs)
                 // to overwrite 'BEEFFACE00..00' later
                 // cryptographic_message_syntax::signing::SignedDataBuilder::build_der() will return Vec<u8>
@@ -371,20 +436,6 @@ impl ChunkContainer {
                 sig_contents[2] = 250; // FA
                 sig_contents[3] = 206; // CE
 
-                catalog
-                    .insert(Name(b"Perms"))
-                    .dict()
-                    .pair(Name(b"DocMDP"), sig_id);
-
-                let mut acro_form = catalog.insert(Name(b"AcroForm")).dict();
-                acro_form
-                    .pair(Name(b"SigFlags"), 3)
-                    .insert(Name(b"Fields"))
-                    .array()
-                    .item(widget_id);
-                acro_form.finish();
-                catalog.finish();
-
                 pdf.indirect(widget_id)
                     .dict()
                     .pair(Name(b"F"), 130)
@@ -425,8 +476,6 @@ impl ChunkContainer {
                     .pair(Name(b"Type"), Name(b"TransformParams"))
                     .pair(Name(b"V"), Name(b"1.2"))
                     .pair(Name(b"P"), 1);
-            } else {
-                catalog.finish();
             }
         }
 
@@ -508,6 +557,7 @@ impl Visit for NonStreamChunks {
         self.page_tree.visit(sc, f)?;
         self.outline.visit(sc, f)?;
         self.page_label_tree.visit(sc, f)?;
+        self.output_intents.visit(sc, f)?;
         self.destination_profiles.visit(sc, f)?;
         self.struct_tree_root.visit(sc, f)?;
         self.struct_elements.visit(sc, f)?;
@@ -3,7 +3,9 @@
 pub mod validate;
 mod version;
 
-pub use validate::{Accessibility, Archival, ValidationError, Validator, Validators};
+pub use validate::{
+    Accessibility, Archival, PrintExchange, ValidationError, Validator, Validators,
+};
 pub use version::PdfVersion;
 
 use crate::configure::validate::ValidatorsBuilder;
@@ -64,6 +66,12 @@ impl ConfigurationBuilder {
         self
     }
 
+    /// Set the PDF/X print exchange validator, overwriting the current one if already set.
+    pub fn with_print_exchange_validator(mut self, x: PrintExchange) -> Self {
+        self.validators = self.validators.with_print_exchange_validator(x);
+        self
+    }
+
     /// Build the [`Configuration`], returning an error if the validators and version are incompatible.
     pub fn finish(self) -> Result<Configuration, ConfigurationError> {
         let validators = self
@@ -109,7 +117,7 @@ pub enum ConfigurationError {
 mod tests {
     use crate::configure::{
         Accessibility, Archival, Configuration, ConfigurationBuilder, ConfigurationError,
-        PdfVersion,
+        PdfVersion, PrintExchange,
     };
 
     #[test]
@@ -156,6 +164,37 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn invalid_combination_4() {
+        // X1a max is PDF 1.4; explicit PDF 1.7 is out of range.
+        assert!(matches!(
+            ConfigurationBuilder::new()
+                .with_print_exchange_validator(PrintExchange::X1a)
+                .with_version(PdfVersion::Pdf17)
+                .finish(),
+            Err(ConfigurationError::VersionDoesNotMatchValidatorsRange(
+                PdfVersion::Pdf17,
+                _
+            ))
+        ));
+    }
+
+    #[test]
+    fn multi_validator_pdf_a2b_pdf_x4() {
+        // A2_B allows up to PDF 1.7 and X4 up to PDF 1.6.
+        let config = ConfigurationBuilder::new()
+            .with_archival_validator(Archival::A2_B)
+            .with_print_exchange_validator(PrintExchange::X4)
+            .finish()
+            .unwrap();
+        assert_eq!(config.validators().len(), 2);
+        assert_eq!(
+            config.validators().print_exchange(),
+            Some(PrintExchange::X4)
+        );
+        assert_eq!(config.version(), PdfVersion::Pdf16);
+    }
+
     #[test]
     fn multi_validator_pdf_a3b_pdf_ua1() {
         let config = ConfigurationBuilder::new()
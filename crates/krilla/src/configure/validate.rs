@@ -26,14 +26,16 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
 
-use pdf_writer::types::OutputIntentSubtype;
+use pdf_writer::writers::DocumentInfo;
+use pdf_writer::{Name, TextStr};
 use xmp_writer::pdfa::PdfAExtSchemasWriter;
-use xmp_writer::XmpWriter;
+use xmp_writer::{Namespace, XmpWriter};
 
 use crate::color::separation::SeparationColorant;
 use crate::color::separation::SeparationSpace;
 use crate::color::RegularColor;
 use crate::configure::PdfVersion;
+use crate::icc::{GenericICCProfile, OutputIntentSubtype};
 use crate::interchange::embed::EmbedError;
 use crate::surface::Location;
 use crate::text::Font;
@@ -74,7 +76,12 @@ pub enum ValidationError {
     TooHighQNestingLevel,
     /// The PDF contains PostScript code, which is forbidden by some export formats.
     ///
-    /// Occurs if a gradient with spread method `Repeat`/`Reflect` or a sweep gradient was used.
+    /// Occurs if a linear gradient with spread method `Repeat`/`Reflect` was used. Sweep gradients
+    /// are automatically written as Coons patch meshes instead if PostScript is forbidden. Can be
+    /// avoided by setting `gradient_samples` in the serialize settings.
+    ///
+    /// Also occurs if a DeviceN color space with more than 12 colorants was used, since its tint
+    /// transform is then written as a PostScript function.
     ContainsPostScript(Option<Location>),
     /// No CMYK ICC profile was provided, even though one is necessary.
     ///
@@ -85,6 +92,10 @@ pub enum ValidationError {
     ///
     /// Occurs if the user specified multiple Separation color spaces with the same colorant but a different fallback color.
     InconsistentSeparationFallback(SeparationColorant),
+    /// There was a DeviceN color space with more colorants than allowed in PDF 1.4 (8).
+    ///
+    /// Only forbidden by export formats that are based on PDF 1.4.
+    TooManyColorants,
     /// The `.notdef` glyph was used, which is forbidden by some export formats.
     ///
     /// Can occur if a glyph could not be found in the font for a corresponding codepoint
@@ -123,6 +134,17 @@ pub enum ValidationError {
     MissingDocumentOutline,
     /// An annotation is missing an alt text.
     MissingAnnotationAltText(Option<Location>),
+    /// The widget of a form field is missing a tooltip.
+    MissingFieldTooltip(Option<Location>),
+    /// The widget of a form field is not part of the tag tree, i.e. it was not
+    /// added with [`Page::add_tagged_annotation`], or it is not a direct child
+    /// of a `Form` structure element.
+    ///
+    /// [`Page::add_tagged_annotation`]: crate::page::Page::add_tagged_annotation
+    UntaggedFormField(Option<Location>),
+    /// An annotation is missing an appearance stream, which is required by some
+    /// standards (e.g. PDF/A-2) for all annotations except links.
+    MissingAnnotationAppearance(Option<Location>),
     /// The date of the document is missing.
     // We need this because for some standards we need to add the
     // xmp:History attribute.
@@ -140,6 +162,53 @@ pub enum ValidationError {
     /// This is currently forbidden in validated export because we cannot manually verify
     /// whether the file actually fulfills all the criteria for the export mode.
     EmbeddedPDF(Option<Location>),
+    /// The PDF contains multimedia content, like screen annotations or rendition actions.
+    Multimedia(Option<Location>),
+    /// The PDF contains a reset-form action.
+    ResetFormAction(Option<Location>),
+    /// Overprinting was enabled with the nonzero overprint mode.
+    ///
+    /// PDF/A forbids this in combination with ICC-based CMYK colors, which all CMYK
+    /// colors are in PDF/A. Since krilla cannot determine which colors are affected
+    /// by a graphics state, it forbids this combination entirely.
+    NonzeroOverprintMode(Option<Location>),
+    /// No output intent with the subtype required by the standard was provided.
+    ///
+    /// Occurs for PDF/X if none of the output intents in the serialize settings
+    /// can be used for PDF/X, since krilla cannot choose a printing condition on
+    /// your behalf.
+    MissingOutputIntent,
+    /// The output intent required by the standard doesn't contain an ICC profile,
+    /// even though one is required by some standards (e.g. PDF/X-4).
+    MissingOutputIntentProfile,
+    /// The ICC profile of an output intent is not supported by the PDF version
+    /// of the document, so the output intent had to be written without it.
+    UnsupportedOutputIntentProfile,
+    /// The ICC profile of an RGB or luma color is not supported by the PDF version
+    /// of the document, so the color had to be written in the default color space
+    /// instead.
+    UnsupportedColorProfile(Option<Location>),
+    /// Output intents with different ICC profiles were used.
+    ///
+    /// PDF/A requires all output intents that contain a destination profile
+    /// to use the same one.
+    InconsistentOutputIntentProfiles,
+    /// An annotation overlaps the bleed box of a page (or the trim or art box if
+    /// there is no bleed box), which is forbidden by some standards (e.g. PDF/X).
+    ///
+    /// Can for example occur if a link annotation or a form field is placed on the
+    /// printed area of the page.
+    AnnotationInBleedBox(Option<Location>),
+    /// A page has neither a trim box nor an art box, even though one of them is
+    /// required by some standards (e.g. PDF/X).
+    ///
+    /// The argument contains the index of the page.
+    MissingTrimBox(usize),
+    /// A color space other than DeviceCMYK, DeviceGray, Separation or DeviceN was used,
+    /// which is forbidden by some standards (e.g. PDF/X-1a).
+    ///
+    /// Can for example occur if an RGB color or an image with an ICC profile was used.
+    NonCmykColorSpace(Option<Location>),
     /// A feature only available in a later PDF version was required.
     RequiresNewerPdfVersion(VersionedFeature, Option<Location>),
 }
@@ -153,6 +222,10 @@ pub enum VersionedFeature {
     HeaderFooterArtifactSubtypes,
     /// Scope attribute for table header cells.
     TableHeaderScope,
+    /// Output intents for individual pages.
+    PageOutputIntents,
+    /// Watermark annotations.
+    WatermarkAnnotations,
 }
 
 impl VersionedFeature {
@@ -162,6 +235,8 @@ impl VersionedFeature {
             VersionedFeature::StructureOrderTabbing => PdfVersion::Pdf15,
             VersionedFeature::HeaderFooterArtifactSubtypes => PdfVersion::Pdf17,
             VersionedFeature::TableHeaderScope => PdfVersion::Pdf15,
+            VersionedFeature::PageOutputIntents => PdfVersion::Pdf20,
+            VersionedFeature::WatermarkAnnotations => PdfVersion::Pdf16,
         }
     }
 }
@@ -171,6 +246,7 @@ impl VersionedFeature {
 pub struct Validators {
     a: Option<Archival>,
     ua: Option<Accessibility>,
+    x: Option<PrintExchange>,
 }
 
 impl Validators {
@@ -179,19 +255,22 @@ impl Validators {
     pub fn prohibits(self, error: &ValidationError) -> Option<Self> {
         let a = self.a.filter(|v| v.prohibits(error));
         let ua = self.ua.filter(|v| v.prohibits(error));
+        let x = self.x.filter(|v| v.prohibits(error));
 
-        let any = a.is_some() || ua.is_some();
-        any.then_some(Self { a, ua })
+        let any = a.is_some() || ua.is_some() || x.is_some();
+        any.then_some(Self { a, ua, x })
     }
 
     /// Returns `true` if no validators are set.
     pub fn is_empty(self) -> bool {
-        self.a.is_none() && self.ua.is_none()
+        self.a.is_none() && self.ua.is_none() && self.x.is_none()
     }
 
     /// Returns the number of set validators.
     pub fn len(self) -> usize {
-        (if self.a.is_some() { 1 } else { 0 }) + (if self.ua.is_some() { 1 } else { 0 })
+        (if self.a.is_some() { 1 } else { 0 })
+            + (if self.ua.is_some() { 1 } else { 0 })
+            + (if self.x.is_some() { 1 } else { 0 })
     }
 
     /// Returns the PDF/A validator, if set.
@@ -204,6 +283,11 @@ impl Validators {
         self.ua
     }
 
+    /// Returns the PDF/X print exchange validator, if set.
+    pub fn print_exchange(self) -> Option<PrintExchange> {
+        self.x
+    }
+
     /// Whether the font must supply valid Unicode code points for each of the
     /// drawn glyphs.
     pub(crate) fn requires_codepoint_mappings(self) -> bool {
@@ -221,6 +305,11 @@ impl Validators {
         self.a.is_some_and(Archival::requires_no_device_cs)
     }
 
+    /// Force an sRGB profile for the `DeviceRgb` colorspace.
+    pub(crate) fn requires_no_device_rgb(self) -> bool {
+        self.x.is_some_and(PrintExchange::requires_no_device_rgb)
+    }
+
     /// Force the `Print` flag set and the `Hidden`, `Invisible`,
     /// `ToggleNoView`, and `NoView` flags unset.
     pub(crate) fn requires_annotation_flags(self) -> bool {
@@ -282,8 +371,25 @@ impl Validators {
         self.a.is_some_and(Archival::specifies_associated_files)
     }
 
-    pub(crate) fn output_intent(self) -> Option<OutputIntentSubtype<'static>> {
-        self.a.map(Archival::output_intent)
+    /// The subtypes of the output intents required by these standards.
+    pub(crate) fn output_intents(self) -> impl Iterator<Item = OutputIntentSubtype> {
+        [
+            self.a.map(Archival::output_intent),
+            self.x.map(PrintExchange::output_intent),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Whether the document information dictionary must be written.
+    pub(crate) fn requires_info_dict(self) -> bool {
+        self.x.is_some()
+    }
+
+    pub(crate) fn write_document_info(self, document_info: &mut DocumentInfo) {
+        if let Some(x) = self.x {
+            x.write_document_info(document_info);
+        }
     }
 
     pub(crate) fn write_xmp(self, xmp: &mut XmpWriter) {
@@ -304,6 +410,10 @@ impl Validators {
         if let Some(ua) = self.ua {
             ua.write_xmp(xmp);
         }
+
+        if let Some(x) = self.x {
+            x.write_xmp(xmp);
+        }
     }
 
     /// Returns the maximum PDF version allowed by all active validators.
@@ -311,6 +421,7 @@ impl Validators {
         self.a
             .map_or(PdfVersion::MAX, |v| v.max())
             .min(self.ua.map_or(PdfVersion::MAX, |v| v.max()))
+            .min(self.x.map_or(PdfVersion::MAX, |v| v.max()))
     }
 
     /// Returns the minimum PDF version required by all active validators, if any.
@@ -318,17 +429,22 @@ impl Validators {
         self.a
             .and_then(|v| v.min())
             .max(self.ua.and_then(|v| v.min()))
+            .max(self.x.and_then(|v| v.min()))
     }
 }
 
 impl IntoIterator for Validators {
     type Item = Validator;
-    type IntoIter = std::iter::Flatten<std::array::IntoIter<Option<Validator>, 2>>;
+    type IntoIter = std::iter::Flatten<std::array::IntoIter<Option<Validator>, 3>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        [self.a.map(Validator::A), self.ua.map(Validator::Ua)]
-            .into_iter()
-            .flatten()
+        [
+            self.a.map(Validator::A),
+            self.ua.map(Validator::Ua),
+            self.x.map(Validator::X),
+        ]
+        .into_iter()
+        .flatten()
     }
 }
 
@@ -342,6 +458,7 @@ impl ValidatorsBuilder {
         match validator {
             Validator::A(a) => self.with_archival_validator(a),
             Validator::Ua(ua) => self.with_accessibility_validator(ua),
+            Validator::X(x) => self.with_print_exchange_validator(x),
         }
     }
 
@@ -357,6 +474,12 @@ impl ValidatorsBuilder {
         self
     }
 
+    /// Set the PDF/X print exchange validator, overwriting the current one if already set.
+    pub fn with_print_exchange_validator(mut self, print_exchange: PrintExchange) -> Self {
+        self.0.x = Some(print_exchange);
+        self
+    }
+
     pub(crate) fn finish(self) -> Result<Validators, Validators> {
         let min = self.0.min().unwrap_or(PdfVersion::MIN);
         let max = self.0.max();
@@ -376,6 +499,8 @@ pub enum Validator {
     A(Archival),
     /// A PDF/UA accessibility validator.
     Ua(Accessibility),
+    /// A PDF/X print exchange validator.
+    X(PrintExchange),
 }
 
 impl Validator {
@@ -383,6 +508,7 @@ impl Validator {
         match self {
             Self::A(a) => a.requires_codepoint_mappings(),
             Self::Ua(ua) => ua.requires_codepoint_mappings(),
+            Self::X(x) => x.requires_codepoint_mappings(),
         }
     }
 
@@ -390,6 +516,7 @@ impl Validator {
         match self {
             Self::A(a) => a.requires_tagging(),
             Self::Ua(ua) => ua.requires_tagging(),
+            Self::X(x) => x.requires_tagging(),
         }
     }
 
@@ -397,6 +524,7 @@ impl Validator {
         match self {
             Self::A(a) => a.requires_xmp_metadata(),
             Self::Ua(ua) => ua.requires_xmp_metadata(),
+            Self::X(x) => x.requires_xmp_metadata(),
         }
     }
 
@@ -405,6 +533,7 @@ impl Validator {
         match self {
             Self::A(a) => a.min(),
             Self::Ua(ua) => ua.min(),
+            Self::X(x) => x.min(),
         }
     }
 
@@ -413,6 +542,7 @@ impl Validator {
         match self {
             Self::A(a) => a.max(),
             Self::Ua(ua) => ua.max(),
+            Self::X(x) => x.max(),
         }
     }
 
@@ -421,6 +551,7 @@ impl Validator {
         match self {
             Self::A(a) => a.as_str(),
             Self::Ua(ua) => ua.as_str(),
+            Self::X(x) => x.as_str(),
         }
     }
 }
@@ -437,6 +568,12 @@ impl From<Accessibility> for Validator {
     }
 }
 
+impl From<PrintExchange> for Validator {
+    fn from(x: PrintExchange) -> Self {
+        Self::X(x)
+    }
+}
+
 /// A PDF/A conformance level.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[allow(non_camel_case_types)]
@@ -541,6 +678,7 @@ impl Archival {
                 | ValidationError::TooLargeFloat
                 | ValidationError::TooManyIndirectObjects
                 | ValidationError::TooHighQNestingLevel
+                | ValidationError::TooManyColorants
                 | ValidationError::ContainsPostScript(_)
                 | ValidationError::MissingCMYKProfile
                 | ValidationError::RestrictedLicense(_)
@@ -548,7 +686,17 @@ impl Archival {
                 | ValidationError::Transparency(_)
                 | ValidationError::ImageInterpolation(_)
                 | ValidationError::EmbeddedFile(EmbedError::Existence, _)
-                | ValidationError::EmbeddedPDF(_),
+                | ValidationError::EmbeddedPDF(_)
+                | ValidationError::Multimedia(_)
+                | ValidationError::ResetFormAction(_)
+                | ValidationError::NonzeroOverprintMode(_)
+                | ValidationError::UnsupportedOutputIntentProfile
+                | ValidationError::UnsupportedColorProfile(_)
+                | ValidationError::InconsistentOutputIntentProfiles
+                | ValidationError::RequiresNewerPdfVersion(
+                    VersionedFeature::PageOutputIntents | VersionedFeature::WatermarkAnnotations,
+                    _,
+                ),
             ) => true,
             // Allowed under all PDF/A-1 profiles.
             (
@@ -559,7 +707,14 @@ impl Archival {
                 | ValidationError::NoDocumentTitle
                 | ValidationError::MissingHeadingTitle
                 | ValidationError::MissingDocumentOutline
+                | ValidationError::MissingAnnotationAppearance(_)
+                | ValidationError::UntaggedFormField(_)
                 | ValidationError::EmbeddedFile(_, _)
+                | ValidationError::MissingOutputIntent
+                | ValidationError::MissingOutputIntentProfile
+                | ValidationError::AnnotationInBleedBox(_)
+                | ValidationError::MissingTrimBox(_)
+                | ValidationError::NonCmykColorSpace(_)
                 | ValidationError::RequiresNewerPdfVersion(
                     VersionedFeature::HeaderFooterArtifactSubtypes
                     | VersionedFeature::StructureOrderTabbing
@@ -575,6 +730,7 @@ impl Archival {
                 | ValidationError::NoDocumentLanguage
                 | ValidationError::MissingAltText(_)
                 | ValidationError::MissingAnnotationAltText(_)
+                | ValidationError::MissingFieldTooltip(_)
                 | ValidationError::MissingTagging,
             ) => self == Self::A1_A,
 
@@ -592,7 +748,18 @@ impl Archival {
                 | ValidationError::RestrictedLicense(_)
                 | ValidationError::MissingDocumentDate
                 | ValidationError::ImageInterpolation(_)
-                | ValidationError::EmbeddedPDF(_),
+                | ValidationError::MissingAnnotationAppearance(_)
+                | ValidationError::EmbeddedPDF(_)
+                | ValidationError::Multimedia(_)
+                | ValidationError::ResetFormAction(_)
+                | ValidationError::NonzeroOverprintMode(_)
+                | ValidationError::UnsupportedOutputIntentProfile
+                | ValidationError::UnsupportedColorProfile(_)
+                | ValidationError::InconsistentOutputIntentProfiles
+                | ValidationError::RequiresNewerPdfVersion(
+                    VersionedFeature::PageOutputIntents | VersionedFeature::WatermarkAnnotations,
+                    _,
+                ),
             ) => true,
             // Allowed under all PDF/A-2 and PDF/A-3 profiles.
             (
@@ -600,10 +767,17 @@ impl Archival {
                 ValidationError::TooLongArray
                 | ValidationError::TooLongDictionary
                 | ValidationError::TooLargeFloat
+                | ValidationError::TooManyColorants
                 | ValidationError::NoDocumentTitle
                 | ValidationError::Transparency(_)
                 | ValidationError::MissingHeadingTitle
                 | ValidationError::MissingDocumentOutline
+                | ValidationError::UntaggedFormField(_)
+                | ValidationError::MissingOutputIntent
+                | ValidationError::MissingOutputIntentProfile
+                | ValidationError::AnnotationInBleedBox(_)
+                | ValidationError::MissingTrimBox(_)
+                | ValidationError::NonCmykColorSpace(_)
                 | ValidationError::RequiresNewerPdfVersion(
                     VersionedFeature::HeaderFooterArtifactSubtypes
                     | VersionedFeature::StructureOrderTabbing
@@ -633,6 +807,7 @@ impl Archival {
                 | ValidationError::NoDocumentLanguage
                 | ValidationError::MissingAltText(_)
                 | ValidationError::MissingAnnotationAltText(_)
+                | ValidationError::MissingFieldTooltip(_)
                 | ValidationError::MissingTagging,
             ) => self == Self::A2_A || self == Self::A3_A,
             // Forbidden under PDF/A-2 and PDF/A-3 accessible and Unicode profiles.
@@ -656,7 +831,18 @@ impl Archival {
                 | ValidationError::RestrictedLicense(_)
                 | ValidationError::MissingDocumentDate
                 | ValidationError::ImageInterpolation(_)
-                | ValidationError::EmbeddedPDF(_),
+                | ValidationError::MissingAnnotationAppearance(_)
+                | ValidationError::EmbeddedPDF(_)
+                | ValidationError::Multimedia(_)
+                | ValidationError::ResetFormAction(_)
+                | ValidationError::NonzeroOverprintMode(_)
+                | ValidationError::UnsupportedOutputIntentProfile
+                | ValidationError::UnsupportedColorProfile(_)
+                | ValidationError::InconsistentOutputIntentProfiles
+                | ValidationError::RequiresNewerPdfVersion(
+                    VersionedFeature::PageOutputIntents | VersionedFeature::WatermarkAnnotations,
+                    _,
+                ),
             ) => true,
             // Allowed under all PDF/A-4 profiles.
             (
@@ -668,6 +854,7 @@ impl Archival {
                 | ValidationError::TooLargeFloat
                 | ValidationError::TooManyIndirectObjects
                 | ValidationError::TooHighQNestingLevel
+                | ValidationError::TooManyColorants
                 | ValidationError::ContainsPostScript(_)
                 | ValidationError::NoDocumentLanguage
                 | ValidationError::NoDocumentTitle
@@ -675,12 +862,19 @@ impl Archival {
                 | ValidationError::MissingHeadingTitle
                 | ValidationError::MissingDocumentOutline
                 | ValidationError::MissingAnnotationAltText(_)
+                | ValidationError::MissingFieldTooltip(_)
+                | ValidationError::UntaggedFormField(_)
                 | ValidationError::Transparency(_)
                 | ValidationError::EmbeddedFile(
                     EmbedError::MissingDate | EmbedError::MissingMimeType,
                     _,
                 )
                 | ValidationError::MissingTagging
+                | ValidationError::MissingOutputIntent
+                | ValidationError::MissingOutputIntentProfile
+                | ValidationError::AnnotationInBleedBox(_)
+                | ValidationError::MissingTrimBox(_)
+                | ValidationError::NonCmykColorSpace(_)
                 | ValidationError::RequiresNewerPdfVersion(
                     VersionedFeature::HeaderFooterArtifactSubtypes
                     | VersionedFeature::StructureOrderTabbing
@@ -891,7 +1085,7 @@ impl Archival {
         }
     }
 
-    fn output_intent(self) -> OutputIntentSubtype<'static> {
+    fn output_intent(self) -> OutputIntentSubtype {
         match self {
             Self::A1_A
             | Self::A1_B
@@ -903,7 +1097,7 @@ impl Archival {
             | Self::A3_U
             | Self::A4
             | Self::A4F
-            | Self::A4E => OutputIntentSubtype::PDFA,
+            | Self::A4E => OutputIntentSubtype::PdfA,
         }
     }
 
@@ -1104,13 +1298,17 @@ impl Accessibility {
                 | ValidationError::MissingHeadingTitle
                 | ValidationError::MissingDocumentOutline
                 | ValidationError::MissingAnnotationAltText(_)
+                | ValidationError::MissingFieldTooltip(_)
+                | ValidationError::UntaggedFormField(_)
                 | ValidationError::EmbeddedFile(EmbedError::MissingDescription, _)
                 | ValidationError::MissingTagging
                 | ValidationError::EmbeddedPDF(_)
                 | ValidationError::RequiresNewerPdfVersion(
                     VersionedFeature::HeaderFooterArtifactSubtypes
                     | VersionedFeature::StructureOrderTabbing
-                    | VersionedFeature::TableHeaderScope,
+                    | VersionedFeature::TableHeaderScope
+                    | VersionedFeature::PageOutputIntents
+                    | VersionedFeature::WatermarkAnnotations,
                     _,
                 ),
             ) => true,
@@ -1123,6 +1321,7 @@ impl Accessibility {
                 | ValidationError::TooLargeFloat
                 | ValidationError::TooManyIndirectObjects
                 | ValidationError::TooHighQNestingLevel
+                | ValidationError::TooManyColorants
                 | ValidationError::ContainsPostScript(_)
                 | ValidationError::MissingCMYKProfile
                 | ValidationError::InconsistentSeparationFallback(_)
@@ -1130,11 +1329,23 @@ impl Accessibility {
                 | ValidationError::NoDocumentLanguage
                 | ValidationError::Transparency(_)
                 | ValidationError::ImageInterpolation(_)
+                | ValidationError::MissingAnnotationAppearance(_)
                 | ValidationError::EmbeddedFile(
                     EmbedError::Existence | EmbedError::MissingDate | EmbedError::MissingMimeType,
                     _,
                 )
-                | ValidationError::MissingDocumentDate,
+                | ValidationError::MissingDocumentDate
+                | ValidationError::Multimedia(_)
+                | ValidationError::ResetFormAction(_)
+                | ValidationError::NonzeroOverprintMode(_)
+                | ValidationError::MissingOutputIntent
+                | ValidationError::MissingOutputIntentProfile
+                | ValidationError::UnsupportedOutputIntentProfile
+                | ValidationError::UnsupportedColorProfile(_)
+                | ValidationError::InconsistentOutputIntentProfiles
+                | ValidationError::AnnotationInBleedBox(_)
+                | ValidationError::MissingTrimBox(_)
+                | ValidationError::NonCmykColorSpace(_),
             ) => false,
         }
     }
@@ -1201,6 +1412,216 @@ impl Accessibility {
     }
 }
 
+/// A PDF/X conformance level.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PrintExchange {
+    /// The validator for the PDF/X-1a:2003 standard.
+    ///
+    /// **Requirements**:
+    /// - You need to provide an output intent that describes the CMYK printing
+    ///   condition, either with an ICC profile or with the identifier of a registered
+    ///   printing condition.
+    /// - Only CMYK, grayscale and spot colors can be used. Since krilla writes CMYK
+    ///   colors as device colors in this mode, they need to be specified for the printing
+    ///   condition of the output intent.
+    /// - Pages must not have both a trim box and an art box, and they must lie within
+    ///   the bleed box and the media box.
+    /// - Annotations, including form fields, must not be placed on the printed area
+    ///   of a page.
+    X1a,
+    /// The validator for the PDF/X-3:2003 standard.
+    ///
+    /// **Requirements**:
+    /// - All requirements of PDF/X-1a, except that colors may also be
+    ///   device-independent.
+    X3,
+    /// The validator for the PDF/X-4 standard.
+    ///
+    /// **Requirements**:
+    /// - All requirements of PDF/X-3.
+    /// - The output intent must contain an ICC profile.
+    X4,
+}
+
+impl PrintExchange {
+    fn prohibits(self, error: &ValidationError) -> bool {
+        match (self, error) {
+            // Forbidden under all PDF/X profiles.
+            (
+                Self::X1a | Self::X3 | Self::X4,
+                ValidationError::TooLongString
+                | ValidationError::TooLongName
+                | ValidationError::TooManyIndirectObjects
+                | ValidationError::TooHighQNestingLevel
+                | ValidationError::MissingCMYKProfile
+                | ValidationError::InconsistentSeparationFallback(_)
+                | ValidationError::NoDocumentTitle
+                | ValidationError::MissingDocumentDate
+                | ValidationError::ImageInterpolation(_)
+                | ValidationError::MissingAnnotationAppearance(_)
+                | ValidationError::EmbeddedFile(EmbedError::Existence, _)
+                | ValidationError::EmbeddedPDF(_)
+                | ValidationError::Multimedia(_)
+                | ValidationError::MissingOutputIntent
+                | ValidationError::UnsupportedOutputIntentProfile
+                | ValidationError::UnsupportedColorProfile(_)
+                | ValidationError::AnnotationInBleedBox(_)
+                | ValidationError::MissingTrimBox(_)
+                | ValidationError::RequiresNewerPdfVersion(
+                    VersionedFeature::PageOutputIntents | VersionedFeature::WatermarkAnnotations,
+                    _,
+                ),
+            ) => true,
+            // Allowed under all PDF/X profiles.
+            (
+                Self::X1a | Self::X3 | Self::X4,
+                ValidationError::TooLongArray
+                | ValidationError::TooLongDictionary
+                | ValidationError::TooLargeFloat
+                | ValidationError::ContainsPostScript(_)
+                | ValidationError::ContainsNotDefGlyph(_, _, _)
+                | ValidationError::NoCodepointMapping(_, _, _)
+                | ValidationError::InvalidCodepointMapping(_, _, _, _)
+                | ValidationError::UnicodePrivateArea(_, _, _, _)
+                | ValidationError::RestrictedLicense(_)
+                | ValidationError::NoDocumentLanguage
+                | ValidationError::MissingAltText(_)
+                | ValidationError::MissingHeadingTitle
+                | ValidationError::MissingDocumentOutline
+                | ValidationError::MissingAnnotationAltText(_)
+                | ValidationError::MissingFieldTooltip(_)
+                | ValidationError::UntaggedFormField(_)
+                | ValidationError::EmbeddedFile(
+                    EmbedError::MissingDate
+                    | EmbedError::MissingDescription
+                    | EmbedError::MissingMimeType,
+                    _,
+                )
+                | ValidationError::MissingTagging
+                | ValidationError::ResetFormAction(_)
+                | ValidationError::NonzeroOverprintMode(_)
+                | ValidationError::InconsistentOutputIntentProfiles
+                | ValidationError::RequiresNewerPdfVersion(
+                    VersionedFeature::HeaderFooterArtifactSubtypes
+                    | VersionedFeature::StructureOrderTabbing
+                    | VersionedFeature::TableHeaderScope,
+                    _,
+                ),
+            ) => false,
+            // Forbidden under PDF/X-1a and PDF/X-3, which are based on PDF 1.4,
+            // but allowed under PDF/X-4.
+            (
+                Self::X1a | Self::X3 | Self::X4,
+                ValidationError::TooManyColorants | ValidationError::Transparency(_),
+            ) => self != Self::X4,
+            // Forbidden under PDF/X-4, which requires the output intent to contain
+            // an ICC profile, but allowed under other PDF/X profiles.
+            (Self::X1a | Self::X3 | Self::X4, ValidationError::MissingOutputIntentProfile) => {
+                self == Self::X4
+            }
+            // Forbidden under PDF/X-1a but allowed under other PDF/X profiles.
+            (Self::X1a | Self::X3 | Self::X4, ValidationError::NonCmykColorSpace(_)) => {
+                self == Self::X1a
+            }
+        }
+    }
+
+    fn requires_codepoint_mappings(self) -> bool {
+        match self {
+            Self::X1a | Self::X3 | Self::X4 => false,
+        }
+    }
+
+    fn requires_tagging(self) -> bool {
+        match self {
+            Self::X1a | Self::X3 | Self::X4 => false,
+        }
+    }
+
+    fn requires_xmp_metadata(self) -> bool {
+        match self {
+            Self::X1a | Self::X3 => false,
+            Self::X4 => true,
+        }
+    }
+
+    /// Whether RGB colors must be device-independent. PDF/X only allows `DeviceRGB`
+    /// if the output intent is an RGB one, which krilla cannot guarantee.
+    fn requires_no_device_rgb(self) -> bool {
+        match self {
+            // RGB colors are forbidden altogether.
+            Self::X1a => false,
+            Self::X3 | Self::X4 => true,
+        }
+    }
+
+    fn output_intent(self) -> OutputIntentSubtype {
+        match self {
+            Self::X1a | Self::X3 | Self::X4 => OutputIntentSubtype::PdfX,
+        }
+    }
+
+    fn version(self) -> &'static str {
+        match self {
+            Self::X1a => "PDF/X-1:2003",
+            Self::X3 => "PDF/X-3:2003",
+            Self::X4 => "PDF/X-4",
+        }
+    }
+
+    fn conformance(self) -> Option<&'static str> {
+        match self {
+            Self::X1a => Some("PDF/X-1a:2003"),
+            Self::X3 | Self::X4 => None,
+        }
+    }
+
+    fn write_document_info(self, document_info: &mut DocumentInfo) {
+        document_info.pair(Name(b"GTS_PDFXVersion"), TextStr(self.version()));
+
+        if let Some(conformance) = self.conformance() {
+            document_info.pair(Name(b"GTS_PDFXConformance"), TextStr(conformance));
+        }
+
+        // PDF/X requires the trapping status to be known. Since krilla never
+        // applies trapping, the document is always untrapped.
+        document_info.pair(Name(b"Trapped"), Name(b"False"));
+    }
+
+    fn write_xmp(self, xmp: &mut XmpWriter) {
+        xmp.pdfx_version(self.version());
+
+        if let Some(conformance) = self.conformance() {
+            xmp.element("GTS_PDFXConformance", Namespace::PdfXId)
+                .value(conformance);
+        }
+    }
+
+    /// Returns a human-readable string representation of the conformance level.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::X1a => "PDF/X-1a",
+            Self::X3 => "PDF/X-3",
+            Self::X4 => "PDF/X-4",
+        }
+    }
+
+    /// Minimum PDF version required to use this standard, if any.
+    pub const fn min(self) -> Option<PdfVersion> {
+        match self {
+            Self::X1a | Self::X3 | Self::X4 => Some(PdfVersion::Pdf14),
+        }
+    }
+
+    /// Maximum PDF version this standard can be used with.
+    pub const fn max(self) -> PdfVersion {
+        match self {
+            Self::X1a | Self::X3 => PdfVersion::Pdf14,
+            Self::X4 => PdfVersion::Pdf16,
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq)]
 pub(crate) struct ValidationStore {
     /// Maps from the name of a Separation colorant to a hash of its fallback
@@ -1208,6 +1629,9 @@ pub(crate) struct ValidationStore {
     /// fallback color. Since Krilla manages the `tintTransform` functions,
     /// those are always equivalent.
     separation_fallback_map: HashMap<SeparationColorant, RegularColor>,
+    /// The first destination profile of an output intent that was written.
+    /// Used to track that all output intents share the same profile.
+    dest_output_profile: Option<GenericICCProfile>,
 }
 
 impl ValidationStore {
@@ -1234,4 +1658,21 @@ impl ValidationStore {
             ))
         }
     }
+
+    /// Register the destination profile of an output intent and raise an error
+    /// if a different one was registered before.
+    pub(crate) fn validate_dest_output_profile(
+        &mut self,
+        profile: &GenericICCProfile,
+    ) -> Result<(), ValidationError> {
+        if self
+            .dest_output_profile
+            .get_or_insert_with(|| profile.clone())
+            == profile
+        {
+            Ok(())
+        } else {
+            Err(ValidationError::InconsistentOutputIntentProfiles)
+        }
+    }
 }
@@ -2,7 +2,6 @@ use pdf_writer::Pdf;
 use std::sync::LazyLock;
 use xmp_writer::XmpWriter;
 
-#[cfg(feature = "raster-images")]
 use crate::graphics::icc::ICCMetadata;
 use crate::graphics::icc::ICCProfile;
 #[cfg(feature = "raster-images")]
@@ -71,21 +70,25 @@ impl PdfVersion {
         }
     }
 
-    #[cfg(feature = "raster-images")]
     pub(crate) fn supports_icc(&self, metadata: &ICCMetadata) -> bool {
+        let version = (metadata.major, metadata.minor);
+
         match self {
-            PdfVersion::Pdf14 => metadata.major <= 2 && metadata.minor <= 2,
-            PdfVersion::Pdf15 => metadata.major <= 4,
-            PdfVersion::Pdf16 => metadata.major <= 4 && metadata.minor <= 1,
-            PdfVersion::Pdf17 => metadata.major <= 4 && metadata.minor <= 2,
-            PdfVersion::Pdf20 => metadata.major <= 4 && metadata.minor <= 2,
+            PdfVersion::Pdf14 => version <= (2, 2),
+            PdfVersion::Pdf15 => version.0 <= 4,
+            PdfVersion::Pdf16 => version <= (4, 1),
+            PdfVersion::Pdf17 => version <= (4, 2),
+            PdfVersion::Pdf20 => version <= (4, 2),
         }
     }
 
     #[cfg(feature = "raster-images")]
     pub(crate) fn supports_bit_depth(&self, bits_per_component: BitsPerComponent) -> bool {
         match bits_per_component {
-            BitsPerComponent::Eight => true,
+            BitsPerComponent::One
+            | BitsPerComponent::Two
+            | BitsPerComponent::Four
+            | BitsPerComponent::Eight => true,
             BitsPerComponent::Sixteen => *self >= PdfVersion::Pdf15,
         }
     }
@@ -16,17 +16,20 @@ use crate::configure::ValidationError;
 #[cfg(feature = "raster-images")]
 use crate::geom::Size;
 use crate::geom::{Point, Rect, Transform};
-use crate::graphics::color::{Color, ColorSpace};
+use crate::graphics::blend::{OverprintMode, TransparencyGroup};
+use crate::graphics::color::{Color, ColorSpace, RenderingIntent};
 use crate::graphics::graphics_state::{ExtGState, GraphicsStates};
 #[cfg(feature = "raster-images")]
-use crate::graphics::image::Image;
+use crate::graphics::image::{Image, StencilMask};
 use crate::graphics::mask::Mask;
-use crate::graphics::paint::{Fill, FillRule, InnerPaint, LineCap, LineJoin, Paint, Stroke};
+use crate::graphics::paint::{
+    Fill, FillRule, InnerPaint, LineCap, LineJoin, Paint, Stroke, UncoloredPattern,
+};
 use crate::graphics::shading_function::{
     GradientProperties, GradientPropertiesExt, ShadingFunction,
 };
 use crate::graphics::shading_pattern::ShadingPattern;
-use crate::graphics::tiling_pattern::TilingPattern;
+use crate::graphics::tiling_pattern::{PatternColorSpace, TilingPattern};
 use crate::graphics::xobject::XObject;
 use crate::interchange::tagging::ContentTag;
 use crate::num::NormalizedF32;
@@ -34,9 +37,12 @@ use crate::resource;
 use crate::resource::{Resource, ResourceDictionaryBuilder};
 use crate::serialize::{MaybeDeviceColorSpace, SerializeContext};
 use crate::stream::Stream;
+use crate::text::cid::vertical_metrics;
 use crate::text::group::{use_text_spanner, GlyphGroup, GlyphGrouper, GlyphSpan, GlyphSpanner};
 use crate::text::type3::ColoredGlyph;
-use crate::text::{Font, FontContainer, FontIdentifier, PdfFont, PDF_UNITS_PER_EM};
+use crate::text::{
+    Font, FontContainer, FontIdentifier, PdfFont, TextState, WritingMode, PDF_UNITS_PER_EM,
+};
 use crate::text::{Glyph, GlyphId};
 use crate::util::{calculate_stroke_bbox, NameExt};
 
@@ -48,6 +54,10 @@ pub(crate) struct ContentBuilder {
     graphics_states: GraphicsStates,
     bbox: Option<Rect>,
     uses_mask: bool,
+    /// Whether colors are left out, see [`StreamBuilder::uncolored_surface`].
+    ///
+    /// [`StreamBuilder::uncolored_surface`]: crate::stream::StreamBuilder::uncolored_surface
+    uncolored: bool,
     // Calculating the bbox of text is expensive, so we should avoid doing it if not needed.
     // The only time we really need it is if we are currently inside of an XObject, where we
     // need to provide a bbox of all its contents. If we are on the main page stream, we only
@@ -55,6 +65,10 @@ pub(crate) struct ContentBuilder {
     bbox_important: bool,
     /// A temporary buffer that's reused across the builder.
     scratch: Vec<u8>,
+    /// The text state of the text object that is currently being written.
+    text_state: TextState,
+    /// The writing mode of the text object that is currently being written.
+    writing_mode: WritingMode,
     pub(crate) active_marked_content: bool,
 }
 
@@ -66,6 +80,14 @@ enum ContentColorSpace {
     Named(String),
 }
 
+/// The color an uncolored pattern is painted with, consisting of the name of its
+/// `[/Pattern base]` color space in the current resource dictionary and the
+/// components in the base color space.
+struct PatternTint {
+    color_space: String,
+    components: Vec<f32>,
+}
+
 impl ContentBuilder {
     pub(crate) fn new(
         root_transform: Transform,
@@ -78,14 +100,27 @@ impl ContentBuilder {
             content: sc.new_content(),
             root_transform,
             uses_mask: false,
+            uncolored: false,
             bbox_important,
             graphics_states: GraphicsStates::new(),
             bbox: None,
             scratch: Vec::new(),
+            text_state: TextState::default(),
+            writing_mode: WritingMode::default(),
             active_marked_content: false,
         }
     }
 
+    /// Leave out all colors, images and shadings.
+    pub(crate) fn uncolored(mut self, uncolored: bool) -> Self {
+        self.uncolored = uncolored;
+        self
+    }
+
+    pub(crate) fn is_uncolored(&self) -> bool {
+        self.uncolored
+    }
+
     pub(crate) fn content_save_state(&mut self) {
         self.content.save_state();
 
@@ -106,6 +141,7 @@ impl ContentBuilder {
             self.validation_errors.into_iter().collect(),
             self.rd_builder.finish(),
             self.uses_mask,
+            self.uncolored,
         )
     }
 
@@ -183,6 +219,16 @@ impl ContentBuilder {
         }
     }
 
+    pub(crate) fn set_overprint(&mut self, fill: bool, stroke: bool, mode: OverprintMode) {
+        let state = ExtGState::new().overprint(fill, stroke, mode);
+        self.graphics_states.combine(&state);
+    }
+
+    pub(crate) fn set_rendering_intent(&mut self, rendering_intent: RenderingIntent) {
+        let state = ExtGState::new().rendering_intent(rendering_intent);
+        self.graphics_states.combine(&state);
+    }
+
     pub(crate) fn expand_bbox(&mut self, new_bbox: Rect) {
         let new_bbox = self.graphics_states.transform_bbox(new_bbox);
         if let Some(bbox) = &mut self.bbox {
@@ -239,7 +285,10 @@ impl ContentBuilder {
         };
 
         let fill_prep = |sb: &mut ContentBuilder, fill: &Fill| {
-            let has_pattern = matches!(fill.paint.0, InnerPaint::Pattern(_));
+            let has_pattern = matches!(
+                fill.paint.0,
+                InnerPaint::Pattern(_) | InnerPaint::UncoloredPattern(_)
+            );
             let fill_opacity = fill.opacity;
             sb.expand_bbox(Rect::from_tsp(path.bounds()));
 
@@ -251,7 +300,10 @@ impl ContentBuilder {
         };
 
         let stroke_prep = |sb: &mut ContentBuilder, stroke: &Stroke, stroke_bbox: Rect| {
-            let is_pattern = matches!(stroke.paint.0, InnerPaint::Pattern(_));
+            let is_pattern = matches!(
+                stroke.paint.0,
+                InnerPaint::Pattern(_) | InnerPaint::UncoloredPattern(_)
+            );
             let stroke_opacity = stroke.opacity;
             sb.expand_bbox(stroke_bbox);
 
@@ -375,6 +427,144 @@ impl ContentBuilder {
         self.content.end_path();
     }
 
+    /// Push a clip path consisting of the given glyphs, while also filling and/or stroking
+    /// them if a fill or stroke is provided.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn push_text_clip(
+        &mut self,
+        start: Point,
+        sc: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+        fill: Option<&Fill>,
+        stroke: Option<&Stroke>,
+        context_color: rgb::Color,
+        glyphs: &[impl Glyph],
+        font: Font,
+        text: &str,
+        font_size: f32,
+        text_state: TextState,
+        writing_mode: WritingMode,
+    ) {
+        self.content_save_state();
+
+        let transform = self.cur_transform_with_root_transform();
+        let Some(inverse) = transform.invert() else {
+            // The glyphs don't cover any area, so clip everything.
+            self.content.rect(0.0, 0.0, 0.0, 0.0);
+            self.content.clip_nonzero();
+            self.content.end_path();
+            return;
+        };
+
+        let (x, y) = (start.x, start.y);
+        let bbox = get_glyphs_bbox(
+            glyphs,
+            x,
+            y,
+            font_size,
+            font.clone(),
+            text_state,
+            writing_mode,
+            text,
+        );
+        self.expand_bbox(bbox);
+
+        // Unlike in other operations, the graphics state can't be restored after
+        // painting, since that would also remove the clip path. So instead, we undo all
+        // changes manually afterwards.
+        if transform != Transform::identity() {
+            self.content.transform(transform.to_pdf_transform());
+        }
+
+        self.graphics_states.save_state();
+
+        if let Some(fill) = fill {
+            if !matches!(
+                &fill.paint.0,
+                &InnerPaint::Pattern(_) | &InnerPaint::UncoloredPattern(_)
+            ) {
+                self.set_fill_opacity(fill.opacity);
+            }
+        }
+
+        if let Some(stroke) = stroke {
+            if !matches!(
+                &stroke.paint.0,
+                &InnerPaint::Pattern(_) | &InnerPaint::UncoloredPattern(_)
+            ) {
+                self.set_stroke_opacity(stroke.opacity);
+            }
+        }
+
+        let state = self.graphics_states.cur().ext_g_state().clone();
+
+        if !state.empty() {
+            let ext = self.rd_builder.register_resource::<resource::ExtGState>(
+                sc.register_resourceable(chunk_container, state.clone()),
+            );
+            self.content.set_parameters(ext.to_pdf_name());
+        }
+
+        if let Some(fill) = fill {
+            self.content_set_fill_properties(bbox, fill, sc, chunk_container);
+        }
+
+        if let Some(stroke) = stroke {
+            self.content_set_stroke_properties(bbox, stroke, sc, chunk_container);
+        }
+
+        let render_mode = match (fill, stroke) {
+            (Some(_), Some(_)) => TextRenderingMode::FillStrokeClip,
+            (Some(_), None) => TextRenderingMode::FillClip,
+            (None, Some(_)) => TextRenderingMode::StrokeClip,
+            (None, None) => TextRenderingMode::Clip,
+        };
+
+        self.encode_text_object(
+            x,
+            y,
+            sc,
+            render_mode,
+            glyphs,
+            font,
+            context_color,
+            text,
+            font_size,
+            text_state,
+            writing_mode,
+        );
+
+        // Gradients with transparent stops set their own soft mask.
+        let has_gradient = |paint: &Paint| {
+            !matches!(
+                &paint.0,
+                InnerPaint::Color(_) | InnerPaint::Pattern(_) | InnerPaint::UncoloredPattern(_)
+            )
+        };
+        let reset_mask = fill.is_some_and(|f| has_gradient(&f.paint))
+            || stroke.is_some_and(|s| has_gradient(&s.paint));
+        let neutral = state.neutral(reset_mask);
+
+        if !neutral.empty() {
+            let ext = self.rd_builder.register_resource::<resource::ExtGState>(
+                sc.register_resourceable(chunk_container, neutral),
+            );
+            self.content.set_parameters(ext.to_pdf_name());
+        }
+
+        if let Some(stroke) = stroke {
+            self.content_reset_stroke_properties(stroke);
+        }
+
+        self.content_reset_text_state();
+
+        if transform != Transform::identity() {
+            self.content.transform(inverse.to_pdf_transform());
+        }
+
+        self.graphics_states.restore_state();
+    }
+
     pub(crate) fn pop_clip_path(&mut self) {
         self.content.restore_state();
     }
@@ -392,6 +582,8 @@ impl ContentBuilder {
         font: Font,
         text: &str,
         font_size: f32,
+        text_state: TextState,
+        writing_mode: WritingMode,
     ) {
         if fill.is_none() && stroke.is_none() {
             return;
@@ -410,7 +602,16 @@ impl ContentBuilder {
                            chunk_container: &mut ChunkContainer,
                            fill: &Fill| {
             let bbox = if calculate_bbox(matches!(&fill.paint.0, InnerPaint::Color(_))) {
-                let bbox = get_glyphs_bbox(glyphs, x, y, font_size, font.clone());
+                let bbox = get_glyphs_bbox(
+                    glyphs,
+                    x,
+                    y,
+                    font_size,
+                    font.clone(),
+                    text_state,
+                    writing_mode,
+                    text,
+                );
                 sb.expand_bbox(bbox);
                 bbox
             } else {
@@ -426,7 +627,16 @@ impl ContentBuilder {
                              stroke: &Stroke| {
             let bbox = if calculate_bbox(matches!(&stroke.paint.0, InnerPaint::Color(_))) {
                 // TODO: Bbox should also account for stroke.
-                let bbox = get_glyphs_bbox(glyphs, x, y, font_size, font.clone());
+                let bbox = get_glyphs_bbox(
+                    glyphs,
+                    x,
+                    y,
+                    font_size,
+                    font.clone(),
+                    text_state,
+                    writing_mode,
+                    text,
+                );
                 sb.expand_bbox(bbox);
                 bbox
             } else {
@@ -439,7 +649,10 @@ impl ContentBuilder {
         let set_fill_opacity = |sb: &mut ContentBuilder, fill: &Fill| {
             // PDF viewers don't show patterns with fill/stroke opacities consistently.
             // Because of this, the opacity is accounted for in the pattern itself.
-            if !matches!(&fill.paint.0, &InnerPaint::Pattern(_)) {
+            if !matches!(
+                &fill.paint.0,
+                &InnerPaint::Pattern(_) | &InnerPaint::UncoloredPattern(_)
+            ) {
                 sb.set_fill_opacity(fill.opacity);
             }
         };
@@ -447,7 +660,10 @@ impl ContentBuilder {
         let set_stroke_opacity = |sb: &mut ContentBuilder, stroke: &Stroke| {
             // PDF viewers don't show patterns with fill/stroke opacities consistently.
             // Because of this, the opacity is accounted for in the pattern itself.
-            if !matches!(&stroke.paint.0, &InnerPaint::Pattern(_)) {
+            if !matches!(
+                &stroke.paint.0,
+                &InnerPaint::Pattern(_) | &InnerPaint::UncoloredPattern(_)
+            ) {
                 // PDF viewers don't show patterns with fill/stroke opacities consistently.
                 // Because of this, the opacity is accounted for in the pattern itself.
                 sb.set_stroke_opacity(stroke.opacity);
@@ -477,6 +693,8 @@ impl ContentBuilder {
                     context_color,
                     text,
                     font_size,
+                    text_state,
+                    writing_mode,
                 );
             }
             (Some(f), None) => {
@@ -496,6 +714,8 @@ impl ContentBuilder {
                     context_color,
                     text,
                     font_size,
+                    text_state,
+                    writing_mode,
                 );
             }
             (None, Some(s)) => {
@@ -515,6 +735,8 @@ impl ContentBuilder {
                     context_color,
                     text,
                     font_size,
+                    text_state,
+                    writing_mode,
                 );
             }
             (None, None) => unreachable!(),
@@ -523,6 +745,58 @@ impl ContentBuilder {
         self.graphics_states.restore_state();
     }
 
+    /// Draw glyphs with the invisible text rendering mode, so that they can still be
+    /// selected and extracted, but are not painted.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn draw_invisible_glyphs(
+        &mut self,
+        start: Point,
+        sc: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+        context_color: rgb::Color,
+        glyphs: &[impl Glyph],
+        font: Font,
+        text: &str,
+        font_size: f32,
+        text_state: TextState,
+        writing_mode: WritingMode,
+    ) {
+        let (x, y) = (start.x, start.y);
+        self.graphics_states.save_state();
+
+        if self.bbox_important {
+            let bbox = get_glyphs_bbox(
+                glyphs,
+                x,
+                y,
+                font_size,
+                font.clone(),
+                text_state,
+                writing_mode,
+                text,
+            );
+            self.expand_bbox(bbox);
+        }
+
+        self.fill_stroke_glyph_run(
+            x,
+            y,
+            sc,
+            chunk_container,
+            TextRenderingMode::Invisible,
+            |_, _, _| {},
+            glyphs,
+            font,
+            context_color,
+            text,
+            font_size,
+            text_state,
+            writing_mode,
+        );
+
+        self.graphics_states.restore_state();
+    }
+
     /// Encode a successive sequence of glyphs that share the same properties and
     /// can be encoded with one text showing operator.
     #[allow(clippy::too_many_arguments)]
@@ -538,14 +812,58 @@ impl ContentBuilder {
         context_color: rgb::Color,
         glyphs: &[impl Glyph],
         text: &str,
+    ) {
+        self.begin_glyph_run(
+            sc,
+            *cur_x,
+            cur_y,
+            font_identifier,
+            pdf_font,
+            size,
+            glyphs,
+            text,
+        );
+
+        // If it's just a single glyph and without an x_offset, there is no need for individual
+        // positioning, so we can use `Tj` instead of `TJ`. The reason we're doing this is because
+        // Acrobat's copy-paste is a bit buggy if `/ActualText` is used at the end of a line, but
+        // only if `TJ` is used. With `Tj`, it's fine.
+        if let [glyph] = glyphs {
+            if approx_eq!(f32, glyph.x_offset(1.0), 0.0, epsilon = 0.001) {
+                self.encode_single_glyph(cur_x, pdf_font, size, context_color, glyph, text);
+                return;
+            }
+        }
+
+        self.encode_glyphs_with_individual_positioning(
+            cur_x,
+            pdf_font,
+            size,
+            context_color,
+            glyphs,
+            text,
+        );
+    }
+
+    /// Select the font and position of a new glyph run.
+    #[allow(clippy::too_many_arguments)]
+    fn begin_glyph_run(
+        &mut self,
+        sc: &mut SerializeContext,
+        x: f32,
+        y: f32,
+        font_identifier: FontIdentifier,
+        pdf_font: &dyn PdfFont,
+        size: f32,
+        glyphs: &[impl Glyph],
+        text: &str,
     ) {
         let font_name = self
             .rd_builder
             .register_resource(sc.register_font_identifier(font_identifier));
         self.content.set_font(font_name.to_pdf_name(), size);
-        self.content.set_text_matrix(
-            Transform::from_row(1.0, 0.0, 0.0, -1.0, *cur_x, cur_y).to_pdf_transform(),
-        );
+        self.content
+            .set_text_matrix(Transform::from_row(1.0, 0.0, 0.0, -1.0, x, y).to_pdf_transform());
 
         for glyph in glyphs {
             if glyph.glyph_id() == GlyphId::new(0)
@@ -558,25 +876,77 @@ impl ContentBuilder {
                 ));
             }
         }
+    }
 
-        // If it's just a single glyph and without an x_offset, there is no need for individual
-        // positioning, so we can use `Tj` instead of `TJ`. The reason we're doing this is because
-        // Acrobat's copy-paste is a bit buggy if `/ActualText` is used at the end of a line, but
-        // only if `TJ` is used. With `Tj`, it's fine.
-        if let [glyph] = glyphs {
-            if approx_eq!(f32, glyph.x_offset(1.0), 0.0, epsilon = 0.001) {
-                self.encode_single_glyph(cur_x, pdf_font, size, context_color, glyph);
-                return;
+    /// Encode a successive sequence of glyphs of a vertical font that share the same
+    /// x coordinate of their vertical origin.
+    ///
+    /// In vertical writing mode, each glyph is placed with its vertical origin at the
+    /// current point, while glyph offsets are relative to the horizontal origin. So
+    /// in addition to the difference in advances, we need to account for the distance
+    /// between the two origins in the adjustments.
+    #[allow(clippy::too_many_arguments)]
+    fn encode_vertical_glyph_run(
+        &mut self,
+        sc: &mut SerializeContext,
+        x: f32,
+        cur_y: &mut f32,
+        font_identifier: FontIdentifier,
+        pdf_font: &dyn PdfFont,
+        size: f32,
+        context_color: rgb::Color,
+        glyphs: &[impl Glyph],
+        text: &str,
+    ) {
+        self.begin_glyph_run(sc, x, *cur_y, font_identifier, pdf_font, size, glyphs, text);
+
+        let font = pdf_font.font();
+        self.scratch.clear();
+        let encoded = &mut self.scratch;
+
+        let mut adjustment = 0.0;
+        let mut positioned = self.content.show_positioned();
+        let mut items = positioned.items();
+
+        for glyph in glyphs {
+            let pdf_glyph = pdf_font
+                .get_gid(ColoredGlyph::new(glyph.glyph_id(), context_color))
+                .unwrap();
+
+            let scale = |val| val * pdf_font.units_per_em();
+            let (advance_height, _, origin_y) = vertical_metrics(&font, glyph.glyph_id());
+
+            // Positive values move upwards, just like in the text space.
+            let y_advance = scale(glyph.y_advance(1.0));
+            let font_advance = -scale(advance_height / font.units_per_em());
+            let y_offset = scale(glyph.y_offset(1.0) + origin_y / font.units_per_em());
+
+            adjustment += y_offset;
+
+            // Make sure we don't write miniscule adjustments
+            if !approx_eq!(f32, adjustment, 0.0, epsilon = 0.001) {
+                if !encoded.is_empty() {
+                    items.show(Str(encoded));
+                    encoded.clear();
+                }
+
+                items.adjust(-(adjustment / pdf_font.units_per_em() * PDF_UNITS_PER_EM));
+                adjustment = 0.0;
             }
+
+            pdf_glyph.encode_into(encoded);
+
+            adjustment += y_advance - font_advance;
+            adjustment -= y_offset;
+            *cur_y -= glyph.y_advance(size);
         }
 
-        self.encode_glyphs_with_individual_positioning(
-            cur_x,
-            pdf_font,
-            size,
-            context_color,
-            glyphs,
-        );
+        if !encoded.is_empty() {
+            items.show(Str(encoded));
+        }
+
+        items.finish();
+        positioned.finish();
     }
 
     fn encode_single_glyph(
@@ -586,6 +956,7 @@ impl ContentBuilder {
         size: f32,
         context_color: rgb::Color,
         glyph: &impl Glyph,
+        text: &str,
     ) {
         let pdf_glyph = pdf_font
             .get_gid(ColoredGlyph::new(glyph.glyph_id(), context_color))
@@ -594,7 +965,7 @@ impl ContentBuilder {
         let encoded = &mut self.scratch;
         pdf_glyph.encode_into(encoded);
         self.content.show(Str(encoded));
-        *cur_x += glyph.x_advance(size);
+        *cur_x += self.text_state.advance(glyph, text, size);
     }
 
     fn encode_glyphs_with_individual_positioning(
@@ -604,7 +975,9 @@ impl ContentBuilder {
         size: f32,
         context_color: rgb::Color,
         glyphs: &[impl Glyph],
+        text: &str,
     ) {
+        let text_state = self.text_state;
         self.scratch.clear();
         let encoded = &mut self.scratch;
 
@@ -646,9 +1019,12 @@ impl ContentBuilder {
                 adjustment += x_advance - font_advance;
             }
 
+            // Word spacing is scaled horizontally just like the adjustment, so it only
+            // needs to be converted into font units.
+            adjustment += scale(text_state.word_spacing_after(glyph, text) / size);
             adjustment -= x_offset;
             // cur_x/cur_y and glyph metrics are in user space units.
-            *cur_x += glyph.x_advance(size);
+            *cur_x += text_state.advance(glyph, text, size);
         }
 
         if !encoded.is_empty() {
@@ -673,6 +1049,8 @@ impl ContentBuilder {
         context_color: rgb::Color,
         text: &str,
         font_size: f32,
+        text_state: TextState,
+        writing_mode: WritingMode,
     ) {
         if glyphs.is_empty() {
             return;
@@ -681,80 +1059,115 @@ impl ContentBuilder {
         self.apply_isolated_op(
             |_, _, _| {},
             |sb, sc, chunk_container| {
-                let mut cur_x = x;
-                let mut cur_y = ys;
-
-                // When the glyph ranges are monotonically decreasing, the glyphs are in reverse
-                // order and we should write `/ReversedChars` marked content.
-                let reversed = glyphs.len() > 1
-                    && glyphs
-                        .windows(2)
-                        .all(|w| w[0].text_range().start >= w[1].text_range().end);
-
                 action(sb, sc, chunk_container);
-                sb.content.begin_text();
-
-                let font_container = sc.register_font_container(font.clone());
-                let do_text_span = reversed
-                    || use_text_spanner(
-                        glyphs,
-                        text,
-                        context_color,
-                        &mut font_container.borrow_mut(),
-                    );
-
-                if do_text_span {
-                    // Separate into distinct glyph runs that either are encoded using actual text, or are
-                    // not.
-                    let spanned = GlyphSpanner::new(
-                        glyphs,
-                        text,
-                        reversed,
-                        sc.serialize_settings()
-                            .validators()
-                            .requires_codepoint_mappings(),
-                        context_color,
-                        font_container.clone(),
-                    );
-
-                    for fragment in spanned {
-                        sb.fill_stroke_glyph_span(
-                            &mut cur_x,
-                            &mut cur_y,
-                            fragment,
-                            sc,
-                            fill_render_mode,
-                            font_container.clone(),
-                            context_color,
-                            text,
-                            font_size,
-                            reversed,
-                        )
-                    }
-                } else {
-                    let glyph_span = GlyphSpan::Unspanned(glyphs);
-
-                    sb.fill_stroke_glyph_span(
-                        &mut cur_x,
-                        &mut cur_y,
-                        glyph_span,
-                        sc,
-                        fill_render_mode,
-                        font_container.clone(),
-                        context_color,
-                        text,
-                        font_size,
-                        reversed,
-                    )
-                }
-
-                sb.content.end_text();
+                sb.encode_text_object(
+                    x,
+                    ys,
+                    sc,
+                    fill_render_mode,
+                    glyphs,
+                    font,
+                    context_color,
+                    text,
+                    font_size,
+                    text_state,
+                    writing_mode,
+                );
             },
             sc,
             chunk_container,
         )
     }
 
+    /// Encode the glyphs into a single text object using the given text rendering mode.
+    #[allow(clippy::too_many_arguments)]
+    fn encode_text_object(
+        &mut self,
+        x: f32,
+        ys: f32,
+        sc: &mut SerializeContext,
+        fill_render_mode: TextRenderingMode,
+        glyphs: &[impl Glyph],
+        font: Font,
+        context_color: rgb::Color,
+        text: &str,
+        font_size: f32,
+        text_state: TextState,
+        writing_mode: WritingMode,
+    ) {
+        let mut cur_x = x;
+        let mut cur_y = ys;
+
+        // When the glyph ranges are monotonically decreasing, the glyphs are in reverse
+        // order and we should write `/ReversedChars` marked content.
+        let reversed = glyphs.len() > 1
+            && glyphs
+                .windows(2)
+                .all(|w| w[0].text_range().start >= w[1].text_range().end);
+
+        self.content.begin_text();
+        self.content_set_text_state(text_state);
+        self.writing_mode = writing_mode;
+
+        let font_container = sc.register_font_container(font.clone());
+        let do_text_span = reversed
+            || use_text_spanner(
+                glyphs,
+                text,
+                context_color,
+                writing_mode,
+                &mut font_container.borrow_mut(),
+            );
+
+        if do_text_span {
+            // Separate into distinct glyph runs that either are encoded using actual text, or are
+            // not.
+            let spanned = GlyphSpanner::new(
+                glyphs,
+                text,
+                reversed,
+                sc.serialize_settings()
+                    .validators()
+                    .requires_codepoint_mappings(),
+                context_color,
+                font_container.clone(),
+                writing_mode,
+            );
+
+            for fragment in spanned {
+                self.fill_stroke_glyph_span(
+                    &mut cur_x,
+                    &mut cur_y,
+                    fragment,
+                    sc,
+                    fill_render_mode,
+                    font_container.clone(),
+                    context_color,
+                    text,
+                    font_size,
+                    reversed,
+                )
+            }
+        } else {
+            let glyph_span = GlyphSpan::Unspanned(glyphs);
+
+            self.fill_stroke_glyph_span(
+                &mut cur_x,
+                &mut cur_y,
+                glyph_span,
+                sc,
+                fill_render_mode,
+                font_container.clone(),
+                context_color,
+                text,
+                font_size,
+                reversed,
+            )
+        }
+
+        self.content.end_text();
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn fill_stroke_glyph_span(
         &mut self,
@@ -782,7 +1195,12 @@ impl ContentBuilder {
 
         // Segment into glyph runs that can be encoded in one go using a PDF
         // text showing operator (i.e. no y shift, same Type3 font, etc.)
-        let segmented = GlyphGrouper::new(font_container.clone(), context_color, fragment.glyphs());
+        let segmented = GlyphGrouper::new(
+            font_container.clone(),
+            context_color,
+            self.writing_mode,
+            fragment.glyphs(),
+        );
 
         for glyph_group in segmented {
             self.fill_stroke_glyph_group(
@@ -825,15 +1243,36 @@ impl ContentBuilder {
             .get_from_identifier(glyph_group.font_identifier.clone())
             .unwrap();
 
-        if fill_render_mode == TextRenderingMode::Fill || pdf_font.force_fill() {
-            self.content
-                .set_text_rendering_mode(TextRenderingMode::Fill);
-        } else if fill_render_mode == TextRenderingMode::FillStroke {
-            self.content
-                .set_text_rendering_mode(TextRenderingMode::FillStroke);
+        // Glyphs of fonts that need to be filled are never stroked, but the
+        // invisible and clipping modes still apply to them.
+        let render_mode = if pdf_font.force_fill() {
+            match fill_render_mode {
+                TextRenderingMode::Invisible | TextRenderingMode::Clip => fill_render_mode,
+                TextRenderingMode::FillClip
+                | TextRenderingMode::StrokeClip
+                | TextRenderingMode::FillStrokeClip => TextRenderingMode::FillClip,
+                _ => TextRenderingMode::Fill,
+            }
         } else {
-            self.content
-                .set_text_rendering_mode(TextRenderingMode::Stroke);
+            fill_render_mode
+        };
+        self.content.set_text_rendering_mode(render_mode);
+
+        if glyph_group.font_identifier.is_vertical() {
+            self.encode_vertical_glyph_run(
+                sc,
+                *cur_x + glyph_group.x_offset * font_size,
+                cur_y,
+                glyph_group.font_identifier,
+                pdf_font,
+                font_size,
+                context_color,
+                glyph_group.glyphs,
+                text,
+            );
+
+            *cur_x += glyph_group.x_advance * font_size;
+            return;
         }
 
         self.encode_consecutive_glyph_run(
@@ -937,6 +1376,18 @@ impl ContentBuilder {
         self.draw_xobject(sc, chunk_container, x_object, &state);
     }
 
+    pub(crate) fn draw_transparency_group(
+        &mut self,
+        sc: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+        group: TransparencyGroup,
+        stream: Stream,
+    ) {
+        let state = ExtGState::new();
+        let x_object = XObject::new_transparency_group(stream, group);
+        self.draw_xobject(sc, chunk_container, x_object, &state);
+    }
+
     #[cfg(feature = "raster-images")]
     pub(crate) fn draw_image(
         &mut self,
@@ -945,8 +1396,19 @@ impl ContentBuilder {
         sc: &mut SerializeContext,
         chunk_container: &mut ChunkContainer,
     ) {
+        // Images are not allowed in uncolored content.
+        if self.uncolored {
+            return;
+        }
+
+        let (image, rendering_intent) = image.split_rendering_intent();
+
         self.apply_isolated_op(
             |sb, _, _| {
+                if let Some(rendering_intent) = rendering_intent {
+                    sb.set_rendering_intent(rendering_intent);
+                }
+
                 // Scale the image from 1x1 to the actual dimensions.
                 let transform =
                     Transform::from_row(size.width(), 0.0, 0.0, -size.height(), 0.0, size.height());
@@ -965,12 +1427,61 @@ impl ContentBuilder {
         );
     }
 
+    #[cfg(feature = "raster-images")]
+    pub(crate) fn draw_stencil_mask(
+        &mut self,
+        mask: StencilMask,
+        size: Size,
+        fill: &Fill,
+        sc: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+    ) {
+        let bbox = Rect::from_xywh(0.0, 0.0, size.width(), size.height()).unwrap();
+
+        self.apply_isolated_op(
+            |sb, _, _| {
+                sb.expand_bbox(bbox);
+
+                // PDF viewers don't show patterns with fill/stroke opacities consistently.
+                // Because of this, the opacity is accounted for in the pattern itself.
+                if !matches!(
+                    &fill.paint.0,
+                    &InnerPaint::Pattern(_) | &InnerPaint::UncoloredPattern(_)
+                ) {
+                    sb.set_fill_opacity(fill.opacity);
+                }
+            },
+            move |sb, sc, chunk_container| {
+                // The fill needs to be set before scaling, so that gradients and patterns
+                // are positioned in the same way as for paths.
+                sb.content_set_fill_properties(bbox, fill, sc, chunk_container);
+
+                let mask_name = sb.rd_builder.register_resource(resource::XObject::new(
+                    sc.register_stencil_mask(chunk_container, mask),
+                ));
+
+                // Scale the mask from 1x1 to the actual dimensions.
+                sb.content.transform(
+                    Transform::from_row(size.width(), 0.0, 0.0, -size.height(), 0.0, size.height())
+                        .to_pdf_transform(),
+                );
+                sb.content.x_object(mask_name.to_pdf_name());
+            },
+            sc,
+            chunk_container,
+        );
+    }
+
     pub(crate) fn draw_shading(
         &mut self,
         shading: &ShadingFunction,
         sc: &mut SerializeContext,
         chunk_container: &mut ChunkContainer,
     ) {
+        if self.uncolored {
+            return;
+        }
+
         self.apply_isolated_op(
             |_, _, _| {},
             move |sb, sc, chunk_container| {
@@ -1039,9 +1550,15 @@ impl ContentBuilder {
         opacity: NormalizedF32,
         sc: &mut SerializeContext,
         chunk_container: &mut ChunkContainer,
-        mut set_pattern_fn: impl FnMut(&mut Content, String),
+        mut set_pattern_fn: impl FnMut(&mut Content, String, Option<PatternTint>),
         mut set_solid_fn: impl FnMut(&mut Content, ContentColorSpace, &Color),
     ) {
+        // In uncolored content, the color is determined by the use site, so
+        // the paint of the fill or stroke is ignored.
+        if self.uncolored {
+            return;
+        }
+
         let pattern_transform = |transform: Transform| -> Transform {
             transform.post_concat(self.cur_transform_with_root_transform())
         };
@@ -1092,7 +1609,7 @@ impl ContentBuilder {
                         content_builder.content.set_parameters(ext.to_pdf_name());
                     }
 
-                    set_pattern_fn(&mut content_builder.content, color_space);
+                    set_pattern_fn(&mut content_builder.content, color_space, None);
                 }
             };
 
@@ -1114,6 +1631,10 @@ impl ContentBuilder {
                 let (gradient_props, transform) = sg.clone().gradient_properties(bounds);
                 write_gradient(gradient_props, sc, chunk_container, transform, self);
             }
+            InnerPaint::MeshGradient(mg) => {
+                let (gradient_props, transform) = mg.clone().gradient_properties(bounds);
+                write_gradient(gradient_props, sc, chunk_container, transform, self);
+            }
             InnerPaint::Pattern(pat) => {
                 let mut pat = Arc::unwrap_or_clone(pat.clone());
                 pat.transform = pattern_transform(pat.transform);
@@ -1124,6 +1645,7 @@ impl ContentBuilder {
                     opacity,
                     pat.width,
                     pat.height,
+                    false,
                     sc,
                     chunk_container,
                 );
@@ -1131,7 +1653,37 @@ impl ContentBuilder {
                 let color_space = self.rd_builder.register_resource::<resource::Pattern>(
                     sc.register_resourceable(chunk_container, tiling_pattern),
                 );
-                set_pattern_fn(&mut self.content, color_space);
+                set_pattern_fn(&mut self.content, color_space, None);
+            }
+            InnerPaint::UncoloredPattern(pat) => {
+                let UncoloredPattern { mut pattern, color } = Arc::unwrap_or_clone(pat.clone());
+                pattern.transform = pattern_transform(pattern.transform);
+
+                let tiling_pattern = TilingPattern::new(
+                    pattern.stream,
+                    pattern.transform,
+                    opacity,
+                    pattern.width,
+                    pattern.height,
+                    true,
+                    sc,
+                    chunk_container,
+                );
+
+                let pattern = self.rd_builder.register_resource::<resource::Pattern>(
+                    sc.register_resourceable(chunk_container, tiling_pattern),
+                );
+                // The pattern itself doesn't depend on the color, so the same pattern object
+                // is shared by all colors and only the color space is specific to it.
+                let pattern_cs = PatternColorSpace::new(color.color_space(sc));
+                let color_space = self.rd_builder.register_resource::<resource::ColorSpace>(
+                    sc.register_resourceable(chunk_container, pattern_cs),
+                );
+                let tint = PatternTint {
+                    color_space,
+                    components: color.to_pdf_color(),
+                };
+                set_pattern_fn(&mut self.content, pattern, Some(tint));
             }
         }
     }
@@ -1159,9 +1711,17 @@ impl ContentBuilder {
         serializer_context: &mut SerializeContext,
         chunk_container: &mut ChunkContainer,
     ) {
-        fn set_pattern_fn(content: &mut Content, color_space: String) {
-            content.set_fill_color_space(pdf_writer::types::ColorSpaceOperand::Pattern);
-            content.set_fill_pattern(None, color_space.to_pdf_name());
+        fn set_pattern_fn(content: &mut Content, pattern: String, tint: Option<PatternTint>) {
+            match tint {
+                Some(tint) => {
+                    content.set_fill_color_space(tint.color_space.to_pdf_name());
+                    content.set_fill_pattern(tint.components, pattern.to_pdf_name());
+                }
+                None => {
+                    content.set_fill_color_space(pdf_writer::types::ColorSpaceOperand::Pattern);
+                    content.set_fill_pattern(None, pattern.to_pdf_name());
+                }
+            }
         }
 
         fn set_solid_fn(content: &mut Content, color_space: ContentColorSpace, color: &Color) {
@@ -1178,6 +1738,18 @@ impl ContentBuilder {
                         let comps = c.to_pdf_color();
                         content.set_fill_cmyk(comps[0], comps[1], comps[2], comps[3]);
                     }
+                    Color::Regular(
+                        crate::color::RegularColor::Lab(_)
+                        | crate::color::RegularColor::CalRgb(_)
+                        | crate::color::RegularColor::CalGray(_),
+                    ) => {
+                        panic!("Device color space cannot be used with CIE-based colors")
+                    }
+                    Color::ICCBased(c) => {
+                        // The profile is not supported, so we fall back to the color
+                        // without it.
+                        set_solid_fn(content, color_space, &c.to_regular().into())
+                    }
                     Color::Special(_) => {
                         panic!("Device color space cannot be used with special colors")
                     }
@@ -1207,9 +1779,17 @@ impl ContentBuilder {
         serializer_context: &mut SerializeContext,
         chunk_container: &mut ChunkContainer,
     ) {
-        fn set_pattern_fn(content: &mut Content, color_space: String) {
-            content.set_stroke_color_space(pdf_writer::types::ColorSpaceOperand::Pattern);
-            content.set_stroke_pattern(None, color_space.to_pdf_name());
+        fn set_pattern_fn(content: &mut Content, pattern: String, tint: Option<PatternTint>) {
+            match tint {
+                Some(tint) => {
+                    content.set_stroke_color_space(tint.color_space.to_pdf_name());
+                    content.set_stroke_pattern(tint.components, pattern.to_pdf_name());
+                }
+                None => {
+                    content.set_stroke_color_space(pdf_writer::types::ColorSpaceOperand::Pattern);
+                    content.set_stroke_pattern(None, pattern.to_pdf_name());
+                }
+            }
         }
 
         fn set_solid_fn(content: &mut Content, color_space: ContentColorSpace, color: &Color) {
@@ -1226,6 +1806,18 @@ impl ContentBuilder {
                         let comps = c.to_pdf_color();
                         content.set_stroke_cmyk(comps[0], comps[1], comps[2], comps[3]);
                     }
+                    Color::Regular(
+                        crate::color::RegularColor::Lab(_)
+                        | crate::color::RegularColor::CalRgb(_)
+                        | crate::color::RegularColor::CalGray(_),
+                    ) => {
+                        panic!("Device color space cannot be used with CIE-based colors")
+                    }
+                    Color::ICCBased(c) => {
+                        // The profile is not supported, so we fall back to the color
+                        // without it.
+                        set_solid_fn(content, color_space, &c.to_regular().into())
+                    }
                     Color::Special(_) => {
                         panic!("Device color space cannot be used with special colors")
                     }
@@ -1272,6 +1864,69 @@ impl ContentBuilder {
         }
     }
 
+    fn content_set_text_state(&mut self, text_state: TextState) {
+        self.text_state = text_state;
+
+        // Only write if they don't correspond to the default values as defined in the
+        // PDF specification. The word spacing is not written, since it only applies to
+        // single-byte character codes, and is instead accounted for when positioning
+        // the glyphs.
+        if text_state.character_spacing != 0.0 {
+            self.content.set_char_spacing(text_state.character_spacing);
+        }
+
+        if text_state.horizontal_scaling != 1.0 {
+            self.content
+                .set_horizontal_scaling(text_state.horizontal_scaling * 100.0);
+        }
+
+        if text_state.rise != 0.0 {
+            self.content.set_rise(text_state.rise);
+        }
+    }
+
+    /// Reset the text state written by `content_set_text_state` to the default values.
+    fn content_reset_text_state(&mut self) {
+        let text_state = std::mem::take(&mut self.text_state);
+
+        if text_state.character_spacing != 0.0 {
+            self.content.set_char_spacing(0.0);
+        }
+
+        if text_state.horizontal_scaling != 1.0 {
+            self.content.set_horizontal_scaling(100.0);
+        }
+
+        if text_state.rise != 0.0 {
+            self.content.set_rise(0.0);
+        }
+    }
+
+    /// Reset the stroke properties written by `content_set_stroke_properties` to
+    /// their default values.
+    fn content_reset_stroke_properties(&mut self, stroke: &Stroke) {
+        if stroke.width != 1.0 {
+            self.content.set_line_width(1.0);
+        }
+
+        if stroke.miter_limit != 10.0 {
+            self.content.set_miter_limit(10.0);
+        }
+
+        if stroke.line_cap != LineCap::Butt {
+            self.content.set_line_cap(LineCap::Butt.to_pdf_line_cap());
+        }
+
+        if stroke.line_join != LineJoin::Miter {
+            self.content
+                .set_line_join(LineJoin::Miter.to_pdf_line_join());
+        }
+
+        if stroke.dash.is_some() {
+            self.content.set_dash_pattern(std::iter::empty(), 0.0);
+        }
+    }
+
     fn content_draw_path(&mut self, path_data: impl Iterator<Item = PathSegment>) {
         // Taken from resvg
         fn calc(n1: f32, n2: f32) -> f32 {
@@ -1319,11 +1974,24 @@ impl ContentBuilder {
 // Note that this isn't a 100% accurate calculation, it can overestimate (and in a few cases
 // even underestimate), but it should be good enough for the majority of the cases.
 // TODO: Improve this so that `zalgo_text` test case shows up fully in the reference image.
-fn get_glyphs_bbox(glyphs: &[impl Glyph], x: f32, y: f32, size: f32, font: Font) -> Rect {
+#[allow(clippy::too_many_arguments)]
+fn get_glyphs_bbox(
+    glyphs: &[impl Glyph],
+    x: f32,
+    y: f32,
+    size: f32,
+    font: Font,
+    text_state: TextState,
+    writing_mode: WritingMode,
+    text: &str,
+) -> Rect {
+    let scaling = text_state.horizontal_scaling;
+    let y = y - text_state.rise;
+
     let font_bbox = font.bbox();
     let (mut bl, mut bt, mut br, mut bb) = font_bbox
         .transform(Transform::from_scale(
-            size / font.units_per_em(),
+            scaling * size / font.units_per_em(),
             -size / font.units_per_em(),
         ))
         .and_then(|b| b.transform(Transform::from_translate(x, y)))
@@ -1334,8 +2002,8 @@ fn get_glyphs_bbox(glyphs: &[impl Glyph], x: f32, y: f32, size: f32, font: Font)
     let mut y = y;
 
     for glyph in glyphs {
-        let xo = glyph.x_offset(size);
-        let xa = glyph.x_advance(size);
+        let xo = glyph.x_offset(size) * scaling;
+        let xa = text_state.advance(glyph, text, size);
         let yo = glyph.y_offset(size);
         let ya = glyph.y_advance(size);
 
@@ -1348,5 +2016,22 @@ fn get_glyphs_bbox(glyphs: &[impl Glyph], x: f32, y: f32, size: f32, font: Font)
         bb = bb.max(y - yo);
     }
 
+    // Vertical glyphs extend to the right of their horizontal origin, so also
+    // account for the last glyph.
+    if writing_mode == WritingMode::Vertical {
+        if let Some(b) = font_bbox
+            .transform(Transform::from_scale(
+                size / font.units_per_em(),
+                -size / font.units_per_em(),
+            ))
+            .and_then(|b| b.transform(Transform::from_translate(x, y)))
+        {
+            bl = bl.min(b.left());
+            bt = bt.min(b.top());
+            br = br.max(b.right());
+            bb = bb.max(b.bottom());
+        }
+    }
+
     Rect::from_ltrb(bl, bt, br, bb).unwrap()
 }
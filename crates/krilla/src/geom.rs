@@ -116,6 +116,14 @@ impl Rect {
         Self(rect)
     }
 
+    /// Whether the two rects overlap. Rects that only share an edge don't overlap.
+    pub(crate) fn intersects(&self, other: &Rect) -> bool {
+        self.left() < other.right()
+            && other.left() < self.right()
+            && self.top() < other.bottom()
+            && other.top() < self.bottom()
+    }
+
     pub(crate) fn expand(&mut self, other: &Rect) {
         let left = self.left().min(other.left());
         let top = self.top().min(other.top());
@@ -1,4 +1,7 @@
-//! Blending.
+//! Blending, transparency groups and overprinting.
+
+use crate::graphics::color::{cmyk, luma, rgb, ColorSpace, RegularColor};
+use crate::serialize::SerializeContext;
 
 /// How to blend source and backdrop.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
@@ -44,3 +47,83 @@ impl BlendMode {
         }
     }
 }
+
+/// How overprinting affects the colorants of CMYK colors.
+///
+/// Only relevant when painting CMYK colors on a device that produces separations.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum OverprintMode {
+    /// Each colorant of the painted color erases the corresponding colorant
+    /// of the backdrop, even if its value is zero.
+    #[default]
+    Zero,
+    /// Colorants with a value of zero leave the corresponding colorant of the
+    /// backdrop unchanged. This is for example used to let black text overprint
+    /// the background without knocking it out.
+    Nonzero,
+}
+
+impl OverprintMode {
+    pub(crate) fn to_pdf(self) -> i32 {
+        match self {
+            OverprintMode::Zero => 0,
+            OverprintMode::Nonzero => 1,
+        }
+    }
+}
+
+/// The properties of a transparency group.
+///
+/// The contents of a transparency group are composited with each other first, and the
+/// result is then composited with the backdrop as a whole.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct TransparencyGroup {
+    /// Whether the group is isolated, i.e. its contents are composited onto a
+    /// fully transparent backdrop instead of the backdrop of the group.
+    pub isolated: bool,
+    /// Whether the group is a knockout group, i.e. each element of the group is
+    /// composited with the initial backdrop of the group instead of the elements
+    /// painted before it.
+    pub knockout: bool,
+    /// The color space in which the contents of the group are blended.
+    ///
+    /// Note that this only has an effect for isolated groups.
+    pub color_space: GroupColorSpace,
+}
+
+impl Default for TransparencyGroup {
+    fn default() -> Self {
+        Self {
+            isolated: true,
+            knockout: false,
+            color_space: GroupColorSpace::default(),
+        }
+    }
+}
+
+/// The blending color space of a transparency group.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum GroupColorSpace {
+    /// The RGB color space.
+    #[default]
+    Rgb,
+    /// The luma color space.
+    Luma,
+    /// The CMYK color space.
+    ///
+    /// Note that if you use this color space with a validator that requires
+    /// device-independent colors, you need to provide a CMYK profile.
+    Cmyk,
+}
+
+impl GroupColorSpace {
+    pub(crate) fn color_space(self, sc: &mut SerializeContext) -> ColorSpace {
+        let color: RegularColor = match self {
+            GroupColorSpace::Rgb => rgb::Color::black().into(),
+            GroupColorSpace::Luma => luma::Color::black().into(),
+            GroupColorSpace::Cmyk => cmyk::Color::new(0, 0, 0, 255).into(),
+        };
+
+        color.color_space(sc).into()
+    }
+}
@@ -0,0 +1,107 @@
+use pdf_writer::{Dict, Finish, Name, Ref};
+
+use crate::chunk_container::ChunkContainer;
+use crate::color::cal_gray::CalGraySpace;
+use crate::color::cal_rgb::CalRgbSpace;
+use crate::color::lab::LabSpace;
+use crate::resource::{self, Resourceable};
+use crate::serialize::{Cacheable, SerializeContext};
+
+fn write_calibrated_space(
+    chunk_container: &mut ChunkContainer,
+    root_ref: Ref,
+    name: &[u8],
+    white_point: [f32; 3],
+    black_point: [f32; 3],
+    write_params: impl FnOnce(&mut Dict),
+) {
+    let chunk = &mut chunk_container.non_stream.color_spaces;
+
+    let mut array = chunk.indirect(root_ref).array();
+    array.item(Name(name));
+
+    let mut dict = array.push().dict();
+    dict.insert(Name(b"WhitePoint")).array().items(white_point);
+
+    if black_point != [0.0; 3] {
+        dict.insert(Name(b"BlackPoint")).array().items(black_point);
+    }
+
+    write_params(&mut dict);
+    dict.finish();
+
+    array.finish();
+}
+
+impl Cacheable for LabSpace {
+    fn serialize(
+        self,
+        _: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+        root_ref: Ref,
+    ) {
+        write_calibrated_space(
+            chunk_container,
+            root_ref,
+            b"Lab",
+            self.white_point,
+            self.black_point,
+            |dict| {
+                dict.insert(Name(b"Range")).array().items(self.range);
+            },
+        );
+    }
+}
+
+impl Resourceable for LabSpace {
+    type Resource = resource::ColorSpace;
+}
+
+impl Cacheable for CalRgbSpace {
+    fn serialize(
+        self,
+        _: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+        root_ref: Ref,
+    ) {
+        write_calibrated_space(
+            chunk_container,
+            root_ref,
+            b"CalRGB",
+            self.white_point,
+            self.black_point,
+            |dict| {
+                dict.insert(Name(b"Gamma")).array().items(self.gamma);
+                dict.insert(Name(b"Matrix")).array().items(self.matrix);
+            },
+        );
+    }
+}
+
+impl Resourceable for CalRgbSpace {
+    type Resource = resource::ColorSpace;
+}
+
+impl Cacheable for CalGraySpace {
+    fn serialize(
+        self,
+        _: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+        root_ref: Ref,
+    ) {
+        write_calibrated_space(
+            chunk_container,
+            root_ref,
+            b"CalGray",
+            self.white_point,
+            self.black_point,
+            |dict| {
+                dict.pair(Name(b"Gamma"), self.gamma);
+            },
+        );
+    }
+}
+
+impl Resourceable for CalGraySpace {
+    type Resource = resource::ColorSpace;
+}
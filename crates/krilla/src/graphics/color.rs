@@ -2,11 +2,13 @@
 //!
 //! # Color spaces
 //!
-//! krilla currently supports four color models:
+//! krilla currently supports the following color models:
 //! - RGB
 //! - Luma
 //! - CMYK
+//! - CIE L*a*b*, calibrated RGB and calibrated gray
 //! - Separation (also known as Spot)
+//! - DeviceN (multiple colorants, including NChannel)
 //!
 //! Each color space is associated with its specific color type, which you can use to create new
 //! instances of a specific color in that color space.
@@ -39,6 +41,16 @@
 //! was provided to the serialize settings, this will be used for CMYK colors. Otherwise,
 //! it will fall back to device CMYK.
 //!
+//! You can also specify RGB and Luma colors in a color space defined by an arbitrary ICC
+//! profile, such as Display P3 or Adobe RGB, using [`rgb::Color::new_with_profile`] and
+//! [`luma::Color::new_with_profile`]. These colors are always device-independent. Colors
+//! using the same profile share a single color space in the PDF. If the profile is not
+//! supported by the PDF version of the document, krilla falls back to the default color
+//! space and registers a [`ValidationError::UnsupportedColorProfile`].
+//!
+//! Colors in the CIE-based [`lab`], [`cal_rgb`] and [`cal_gray`] color spaces are always
+//! device-independent, since they are defined relative to a white point.
+//!
 //! # Separations
 //!
 //! An alternative way to achieve exact color reproduction in print are Separation colors.
@@ -72,11 +84,18 @@ pub(crate) const DEVICE_GRAY: &str = "DeviceGray";
 /// The PDF name for the device CMYK color space.
 pub(crate) const DEVICE_CMYK: &str = "DeviceCMYK";
 
+/// The CIE XYZ coordinates of the D50 standard illuminant.
+pub const WHITE_POINT_D50: [f32; 3] = [0.9642, 1.0, 0.8249];
+/// The CIE XYZ coordinates of the D65 standard illuminant.
+pub const WHITE_POINT_D65: [f32; 3] = [0.9505, 1.0, 1.089];
+
 /// A wrapper for storing colors from different color spaces.
 #[derive(Debug, Hash, Eq, PartialEq, Clone)]
 pub enum Color {
     /// A device or CIE-based color.
     Regular(RegularColor),
+    /// A color in the color space of an ICC profile.
+    ICCBased(ICCBasedColor),
     /// A special color space.
     Special(SpecialColor),
 }
@@ -90,6 +109,21 @@ pub enum RegularColor {
     Luma(luma::Color),
     /// A device CMYK color.
     Cmyk(cmyk::Color),
+    /// A CIE L*a*b* color.
+    Lab(lab::Color),
+    /// A calibrated RGB color.
+    CalRgb(cal_rgb::Color),
+    /// A calibrated gray-scale color.
+    CalGray(cal_gray::Color),
+}
+
+/// A color in the color space of an ICC profile.
+#[derive(Debug, Hash, Eq, PartialEq, Clone)]
+pub enum ICCBasedColor {
+    /// An RGB color with an ICC profile.
+    Rgb(rgb::ICCColor),
+    /// A luma color with an ICC profile.
+    Luma(luma::ICCColor),
 }
 
 /// A special color space color.
@@ -97,6 +131,8 @@ pub enum RegularColor {
 pub enum SpecialColor {
     /// A separation color.
     Separation(separation::Color),
+    /// A DeviceN color.
+    DeviceN(devicen::Color),
 }
 
 impl Color {
@@ -105,23 +141,62 @@ impl Color {
             Color::Regular(RegularColor::Rgb(rgb)) => rgb.to_pdf_color().to_vec(),
             Color::Regular(RegularColor::Luma(l)) => vec![l.to_pdf_color()],
             Color::Regular(RegularColor::Cmyk(cmyk)) => cmyk.to_pdf_color().to_vec(),
+            Color::Regular(RegularColor::Lab(lab)) => lab.to_pdf_color().to_vec(),
+            Color::Regular(RegularColor::CalRgb(c)) => c.to_pdf_color().to_vec(),
+            Color::Regular(RegularColor::CalGray(c)) => vec![c.to_pdf_color()],
+            Color::ICCBased(c) => Color::Regular(c.to_regular()).to_pdf_color(),
             Color::Special(SpecialColor::Separation(spot)) => vec![spot.to_pdf_color()],
+            Color::Special(SpecialColor::DeviceN(c)) => c.to_pdf_color(),
         }
     }
 
     pub(crate) fn color_space(&self, sc: &mut SerializeContext) -> ColorSpace {
         match self {
             Color::Regular(c) => c.color_space(sc).into(),
+            Color::ICCBased(c) => c.color_space(sc).into(),
             Color::Special(c) => c.color_space().into(),
         }
     }
 
+    /// Whether both colors are in the same color space.
+    pub(crate) fn is_same_space(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Color::Regular(a), Color::Regular(b)) => a.is_same_space(*b),
+            (Color::ICCBased(ICCBasedColor::Rgb(a)), Color::ICCBased(ICCBasedColor::Rgb(b))) => {
+                a.1 == b.1
+            }
+            (Color::ICCBased(ICCBasedColor::Luma(a)), Color::ICCBased(ICCBasedColor::Luma(b))) => {
+                a.1 == b.1
+            }
+            (
+                Color::Special(SpecialColor::Separation(a)),
+                Color::Special(SpecialColor::Separation(b)),
+            ) => a.space == b.space,
+            (
+                Color::Special(SpecialColor::DeviceN(a)),
+                Color::Special(SpecialColor::DeviceN(b)),
+            ) => a.space == b.space,
+            _ => false,
+        }
+    }
+
+    /// The range of each component of the color, as a flat list of minimum
+    /// and maximum values.
+    pub(crate) fn pdf_range(&self) -> Vec<f32> {
+        match self {
+            Color::Regular(RegularColor::Lab(lab)) => lab.pdf_range().to_vec(),
+            _ => [0.0, 1.0].repeat(self.to_pdf_color().len()),
+        }
+    }
+
     /// Convert a color to a regular color for use with constructs like tags or
     /// annotations that don't support special color spaces
     pub(crate) fn to_regular(&self) -> RegularColor {
         match self {
             Color::Regular(c) => *c,
+            Color::ICCBased(c) => c.to_regular(),
             Color::Special(SpecialColor::Separation(c)) => c.space.fallback,
+            Color::Special(SpecialColor::DeviceN(c)) => c.to_regular(),
         }
     }
 }
@@ -129,7 +204,7 @@ impl Color {
 impl RegularColor {
     pub(crate) fn color_space(&self, sc: &mut SerializeContext) -> RegularColorSpace {
         match self {
-            Self::Rgb(r) => r.color_space(sc.serialize_settings().no_device_cs),
+            Self::Rgb(_) => rgb::color_space(&sc.serialize_settings()),
             Self::Luma(_) => luma::color_space(sc.serialize_settings().no_device_cs),
             Self::Cmyk(_) => match cmyk::color_space(&sc.serialize_settings()) {
                 None => {
@@ -138,6 +213,9 @@ impl RegularColor {
                 }
                 Some(cs) => cs,
             },
+            Self::Lab(l) => l.color_space(),
+            Self::CalRgb(c) => c.color_space(),
+            Self::CalGray(c) => c.color_space(),
         }
     }
 
@@ -148,13 +226,80 @@ impl RegularColor {
             Self::Rgb(r) => r,
             Self::Luma(l) => rgb::Color::new(l.0, l.0, l.0),
             Self::Cmyk(_) => return None,
+            Self::Lab(l) => l.to_rgb(),
+            Self::CalRgb(c) => c.to_rgb(),
+            Self::CalGray(c) => c.to_rgb(),
         })
     }
 
-    /// Returns true if this is a subtractive color space (CMYK), false otherwise (RGB, Luma).
+    /// Returns a color in the same color space as this one, with the given components.
+    pub(crate) fn with_pdf_color(self, components: &[f32]) -> Self {
+        let c = |i: usize| components.get(i).copied().unwrap_or(0.0);
+        let u = |i: usize| (c(i).clamp(0.0, 1.0) * 255.0).round() as u8;
+
+        match self {
+            Self::Rgb(_) => rgb::Color::new(u(0), u(1), u(2)).into(),
+            Self::Luma(_) => luma::Color::new(u(0)).into(),
+            Self::Cmyk(_) => cmyk::Color::new(u(0), u(1), u(2), u(3)).into(),
+            Self::Lab(l) => lab::Color::new_with_space(c(0), c(1), c(2), l.space).into(),
+            Self::CalRgb(r) => cal_rgb::Color::new_with_space(c(0), c(1), c(2), r.space).into(),
+            Self::CalGray(g) => cal_gray::Color::new_with_space(c(0), g.space).into(),
+        }
+    }
+
+    /// Returns true if both colors are in the same color space.
+    pub(crate) fn is_same_space(self, other: Self) -> bool {
+        match (self, other) {
+            (Self::Rgb(_), Self::Rgb(_))
+            | (Self::Luma(_), Self::Luma(_))
+            | (Self::Cmyk(_), Self::Cmyk(_)) => true,
+            (Self::Lab(a), Self::Lab(b)) => a.space == b.space,
+            (Self::CalRgb(a), Self::CalRgb(b)) => a.space == b.space,
+            (Self::CalGray(a), Self::CalGray(b)) => a.space == b.space,
+            _ => false,
+        }
+    }
+
+    /// Returns the components of white (i.e. no ink) in the color space of this color.
     /// Used for determining the correct tint transform behavior in Separation color spaces.
-    pub(crate) fn is_subtractive(self) -> bool {
-        matches!(self, Self::Cmyk(_))
+    pub(crate) fn white_pdf_color(self) -> Vec<f32> {
+        match self {
+            Self::Rgb(_) | Self::CalRgb(_) => vec![1.0; 3],
+            Self::Luma(_) | Self::CalGray(_) => vec![1.0],
+            Self::Cmyk(_) => vec![0.0; 4],
+            Self::Lab(_) => vec![100.0, 0.0, 0.0],
+        }
+    }
+}
+
+impl ICCBasedColor {
+    /// Return the color space of the profile, or the default color space if the
+    /// profile is not supported by the PDF version of the document.
+    pub(crate) fn color_space(&self, sc: &mut SerializeContext) -> RegularColorSpace {
+        let pdf_version = sc.serialize_settings().pdf_version();
+
+        let supported = match self {
+            Self::Rgb(r) => pdf_version.supports_icc(r.1.metadata()),
+            Self::Luma(l) => pdf_version.supports_icc(l.1.metadata()),
+        };
+
+        if !supported {
+            sc.register_validation_error(ValidationError::UnsupportedColorProfile(sc.location));
+            return self.to_regular().color_space(sc);
+        }
+
+        match self {
+            Self::Rgb(r) => CieBasedColorSpace::Rgb(ICCBasedColorSpace(r.1.clone())).into(),
+            Self::Luma(l) => CieBasedColorSpace::Gray(ICCBasedColorSpace(l.1.clone())).into(),
+        }
+    }
+
+    /// Return the color without its profile.
+    pub(crate) fn to_regular(&self) -> RegularColor {
+        match self {
+            Self::Rgb(r) => r.0.into(),
+            Self::Luma(l) => l.0.into(),
+        }
     }
 }
 
@@ -162,6 +307,40 @@ impl SpecialColor {
     pub(crate) fn color_space(&self) -> SpecialColorSpace {
         match self {
             Self::Separation(spot) => spot.color_space().into(),
+            Self::DeviceN(c) => c.color_space().into(),
+        }
+    }
+}
+
+/// How colors should be mapped when they are converted from their color space
+/// to the color space of the output device.
+///
+/// All four variants are standard rendering intents, so they can be used in PDF/A
+/// documents.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum RenderingIntent {
+    /// Colors are reproduced exactly, without adjusting for the white point of
+    /// the output device.
+    AbsoluteColorimetric,
+    /// Colors within the gamut of the output device are reproduced exactly, with
+    /// the white point mapped to the white point of the output device.
+    #[default]
+    RelativeColorimetric,
+    /// Saturation is preserved at the expense of accuracy. Useful for business
+    /// graphics.
+    Saturation,
+    /// The whole gamut is compressed so that relations between colors are
+    /// preserved. Useful for photographs.
+    Perceptual,
+}
+
+impl RenderingIntent {
+    pub(crate) fn to_pdf(self) -> pdf_writer::types::RenderingIntent {
+        match self {
+            Self::AbsoluteColorimetric => pdf_writer::types::RenderingIntent::AbsoluteColorimetric,
+            Self::RelativeColorimetric => pdf_writer::types::RenderingIntent::RelativeColorimetric,
+            Self::Saturation => pdf_writer::types::RenderingIntent::Saturation,
+            Self::Perceptual => pdf_writer::types::RenderingIntent::Perceptual,
         }
     }
 }
@@ -169,17 +348,29 @@ impl SpecialColor {
 /// Gray-scale colors.
 pub mod luma {
     use crate::color::{CieBasedColorSpace, DeviceColorSpace, RegularColor, RegularColorSpace};
+    use crate::graphics::icc::ICCProfile;
 
     /// A luma color.
     #[derive(Debug, Hash, Eq, PartialEq, Clone, Copy)]
     pub struct Color(pub(crate) u8);
 
+    /// A luma color in the color space of an ICC profile.
+    #[derive(Debug, Hash, Eq, PartialEq, Clone)]
+    pub struct ICCColor(pub(crate) Color, pub(crate) ICCProfile<1>);
+
     impl Color {
         /// Create a new luma color.
         pub fn new(lightness: u8) -> Color {
             Color(lightness)
         }
 
+        /// Create a new luma color in the color space of the given ICC profile.
+        ///
+        /// See the [module-level documentation](super) for more information.
+        pub fn new_with_profile(lightness: u8, profile: ICCProfile<1>) -> ICCColor {
+            ICCColor(Color(lightness), profile)
+        }
+
         /// Create a black luma color.
         pub fn black() -> Self {
             Self::new(0)
@@ -201,6 +392,12 @@ pub mod luma {
         }
     }
 
+    impl From<ICCColor> for super::Color {
+        fn from(val: ICCColor) -> Self {
+            super::Color::ICCBased(super::ICCBasedColor::Luma(val))
+        }
+    }
+
     impl From<Color> for super::Color {
         fn from(val: Color) -> Self {
             RegularColor::from(val).into()
@@ -292,11 +489,17 @@ pub mod cmyk {
 /// RGB colors.
 pub mod rgb {
     use crate::color::{CieBasedColorSpace, DeviceColorSpace, RegularColorSpace};
+    use crate::graphics::icc::ICCProfile;
+    use crate::SerializeSettings;
 
     /// An RGB color.
     #[derive(Debug, Hash, Eq, PartialEq, Clone, Copy)]
     pub struct Color(pub(crate) u8, pub(crate) u8, pub(crate) u8);
 
+    /// An RGB color in the color space of an ICC profile.
+    #[derive(Debug, Hash, Eq, PartialEq, Clone)]
+    pub struct ICCColor(pub(crate) Color, pub(crate) ICCProfile<3>);
+
     impl Default for Color {
         fn default() -> Self {
             Color::black()
@@ -314,6 +517,14 @@ pub mod rgb {
             Color(red, green, blue)
         }
 
+        /// Create a new RGB color in the color space of the given ICC profile,
+        /// for example Display P3 or Adobe RGB.
+        ///
+        /// See the [module-level documentation](super) for more information.
+        pub fn new_with_profile(red: u8, green: u8, blue: u8, profile: ICCProfile<3>) -> ICCColor {
+            ICCColor(Color(red, green, blue), profile)
+        }
+
         /// Create a black RGB color.
         pub fn black() -> Self {
             Self::new(0, 0, 0)
@@ -346,10 +557,6 @@ pub mod rgb {
                 self.2 as f32 / 255.0,
             ]
         }
-
-        pub(super) fn color_space(&self, no_device_cs: bool) -> RegularColorSpace {
-            color_space(no_device_cs)
-        }
     }
 
     impl From<Color> for super::RegularColor {
@@ -358,14 +565,20 @@ pub mod rgb {
         }
     }
 
+    impl From<ICCColor> for super::Color {
+        fn from(val: ICCColor) -> Self {
+            super::Color::ICCBased(super::ICCBasedColor::Rgb(val))
+        }
+    }
+
     impl From<Color> for super::Color {
         fn from(val: Color) -> Self {
             super::RegularColor::from(val).into()
         }
     }
 
-    pub(crate) fn color_space(no_device_cs: bool) -> RegularColorSpace {
-        if no_device_cs {
+    pub(crate) fn color_space(ss: &SerializeSettings) -> RegularColorSpace {
+        if ss.no_device_cs || ss.validators().requires_no_device_rgb() {
             CieBasedColorSpace::Srgb.into()
         } else {
             DeviceColorSpace::Rgb.into()
@@ -373,6 +586,471 @@ pub mod rgb {
     }
 }
 
+/// CIE L*a*b* colors.
+pub mod lab {
+    use std::hash::{Hash, Hasher};
+
+    use crate::color::{CieBasedColorSpace, RegularColorSpace, WHITE_POINT_D50};
+
+    /// A CIE L*a*b* color space.
+    ///
+    /// The space is defined by the white point it is relative to and the
+    /// range of its `a*` and `b*` components. The lightness `L*` always
+    /// ranges from 0 to 100.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct LabSpace {
+        pub(crate) white_point: [f32; 3],
+        pub(crate) black_point: [f32; 3],
+        pub(crate) range: [f32; 4],
+    }
+
+    impl LabSpace {
+        /// Create a new Lab space with the given diffuse white point in CIE
+        /// XYZ coordinates. The `Y` coordinate of the white point must be 1.
+        pub fn new(white_point: [f32; 3]) -> Self {
+            Self {
+                white_point,
+                black_point: [0.0; 3],
+                range: [-100.0, 100.0, -100.0, 100.0],
+            }
+        }
+
+        /// Set the diffuse black point in CIE XYZ coordinates.
+        ///
+        /// Defaults to `[0.0, 0.0, 0.0]`.
+        pub fn with_black_point(mut self, black_point: [f32; 3]) -> Self {
+            self.black_point = black_point;
+            self
+        }
+
+        /// Set the range of the `a*` and `b*` components as
+        /// `[a_min, a_max, b_min, b_max]`.
+        ///
+        /// Defaults to `[-100.0, 100.0, -100.0, 100.0]`.
+        pub fn with_range(mut self, range: [f32; 4]) -> Self {
+            self.range = range;
+            self
+        }
+    }
+
+    impl Default for LabSpace {
+        fn default() -> Self {
+            Self::new(WHITE_POINT_D50)
+        }
+    }
+
+    impl Eq for LabSpace {}
+
+    impl Hash for LabSpace {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            for f in self
+                .white_point
+                .iter()
+                .chain(self.black_point.iter())
+                .chain(self.range.iter())
+            {
+                f.to_bits().hash(state);
+            }
+        }
+    }
+
+    /// A CIE L*a*b* color.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct Color {
+        pub(crate) l: f32,
+        pub(crate) a: f32,
+        pub(crate) b: f32,
+        pub(crate) space: LabSpace,
+    }
+
+    impl Color {
+        /// Create a new Lab color in the default space, relative to the D50
+        /// white point.
+        ///
+        /// `l` ranges from 0 to 100, while `a` and `b` are clamped to the
+        /// range of the space.
+        pub fn new(l: f32, a: f32, b: f32) -> Self {
+            Self::new_with_space(l, a, b, LabSpace::default())
+        }
+
+        /// Create a new Lab color in a specific Lab space.
+        pub fn new_with_space(l: f32, a: f32, b: f32, space: LabSpace) -> Self {
+            Self { l, a, b, space }
+        }
+
+        pub(crate) fn to_pdf_color(self) -> [f32; 3] {
+            let [a_min, a_max, b_min, b_max] = self.space.range;
+
+            [
+                self.l.clamp(0.0, 100.0),
+                self.a.max(a_min).min(a_max),
+                self.b.max(b_min).min(b_max),
+            ]
+        }
+
+        pub(crate) fn pdf_range(&self) -> [f32; 6] {
+            let [a_min, a_max, b_min, b_max] = self.space.range;
+
+            [0.0, 100.0, a_min, a_max, b_min, b_max]
+        }
+
+        pub(crate) fn to_rgb(self) -> super::rgb::Color {
+            let [l, a, b] = self.to_pdf_color();
+            let f = |t: f32| {
+                const DELTA: f32 = 6.0 / 29.0;
+
+                if t > DELTA {
+                    t.powi(3)
+                } else {
+                    3.0 * DELTA * DELTA * (t - 4.0 / 29.0)
+                }
+            };
+
+            let fy = (l + 16.0) / 116.0;
+            let [wx, wy, wz] = self.space.white_point;
+            let xyz = [wx * f(fy + a / 500.0), wy * f(fy), wz * f(fy - b / 200.0)];
+
+            super::xyz_to_rgb(xyz, self.space.white_point)
+        }
+
+        pub(super) fn color_space(&self) -> RegularColorSpace {
+            CieBasedColorSpace::Lab(self.space).into()
+        }
+    }
+
+    impl Eq for Color {}
+
+    impl Hash for Color {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.l.to_bits().hash(state);
+            self.a.to_bits().hash(state);
+            self.b.to_bits().hash(state);
+            self.space.hash(state);
+        }
+    }
+
+    impl Default for Color {
+        fn default() -> Self {
+            Color::new(0.0, 0.0, 0.0)
+        }
+    }
+
+    impl From<Color> for super::RegularColor {
+        fn from(val: Color) -> Self {
+            super::RegularColor::Lab(val)
+        }
+    }
+
+    impl From<Color> for super::Color {
+        fn from(val: Color) -> Self {
+            super::RegularColor::from(val).into()
+        }
+    }
+}
+
+/// Calibrated RGB colors.
+pub mod cal_rgb {
+    use std::hash::{Hash, Hasher};
+
+    use crate::color::{CieBasedColorSpace, RegularColorSpace, WHITE_POINT_D65};
+
+    /// A calibrated RGB color space.
+    ///
+    /// The space is defined by its white point, the gamma of each component
+    /// and a matrix mapping the decoded components to CIE XYZ.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct CalRgbSpace {
+        pub(crate) white_point: [f32; 3],
+        pub(crate) black_point: [f32; 3],
+        pub(crate) gamma: [f32; 3],
+        pub(crate) matrix: [f32; 9],
+    }
+
+    impl CalRgbSpace {
+        /// Create a new calibrated RGB space with the given diffuse white point
+        /// in CIE XYZ coordinates. The `Y` coordinate of the white point must be 1.
+        ///
+        /// The gamma and matrix default to an approximation of sRGB.
+        pub fn new(white_point: [f32; 3]) -> Self {
+            Self {
+                white_point,
+                black_point: [0.0; 3],
+                gamma: [2.2; 3],
+                matrix: [
+                    0.4124, 0.2126, 0.0193, 0.3576, 0.7152, 0.1192, 0.1805, 0.0722, 0.9505,
+                ],
+            }
+        }
+
+        /// Set the diffuse black point in CIE XYZ coordinates.
+        ///
+        /// Defaults to `[0.0, 0.0, 0.0]`.
+        pub fn with_black_point(mut self, black_point: [f32; 3]) -> Self {
+            self.black_point = black_point;
+            self
+        }
+
+        /// Set the gamma of the red, green and blue components.
+        ///
+        /// Defaults to `[2.2, 2.2, 2.2]`.
+        pub fn with_gamma(mut self, gamma: [f32; 3]) -> Self {
+            self.gamma = gamma;
+            self
+        }
+
+        /// Set the matrix mapping the decoded components to CIE XYZ, given as
+        /// the `X`, `Y` and `Z` coordinates of the red, green and blue primaries,
+        /// in that order.
+        pub fn with_matrix(mut self, matrix: [f32; 9]) -> Self {
+            self.matrix = matrix;
+            self
+        }
+    }
+
+    impl Default for CalRgbSpace {
+        fn default() -> Self {
+            Self::new(WHITE_POINT_D65)
+        }
+    }
+
+    impl Eq for CalRgbSpace {}
+
+    impl Hash for CalRgbSpace {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            for f in self
+                .white_point
+                .iter()
+                .chain(self.black_point.iter())
+                .chain(self.gamma.iter())
+                .chain(self.matrix.iter())
+            {
+                f.to_bits().hash(state);
+            }
+        }
+    }
+
+    /// A calibrated RGB color.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct Color {
+        pub(crate) components: [f32; 3],
+        pub(crate) space: CalRgbSpace,
+    }
+
+    impl Color {
+        /// Create a new calibrated RGB color in the default space. The components
+        /// are clamped to the range 0 to 1.
+        pub fn new(red: f32, green: f32, blue: f32) -> Self {
+            Self::new_with_space(red, green, blue, CalRgbSpace::default())
+        }
+
+        /// Create a new calibrated RGB color in a specific space.
+        pub fn new_with_space(red: f32, green: f32, blue: f32, space: CalRgbSpace) -> Self {
+            Self {
+                components: [red, green, blue],
+                space,
+            }
+        }
+
+        pub(crate) fn to_pdf_color(self) -> [f32; 3] {
+            self.components.map(|c| c.clamp(0.0, 1.0))
+        }
+
+        pub(crate) fn to_rgb(self) -> super::rgb::Color {
+            let decoded = self
+                .to_pdf_color()
+                .into_iter()
+                .zip(self.space.gamma)
+                .map(|(c, g)| c.powf(g))
+                .collect::<Vec<_>>();
+            let m = self.space.matrix;
+            let xyz = [0, 1, 2]
+                .map(|i| decoded[0] * m[i] + decoded[1] * m[3 + i] + decoded[2] * m[6 + i]);
+
+            super::xyz_to_rgb(xyz, self.space.white_point)
+        }
+
+        pub(super) fn color_space(&self) -> RegularColorSpace {
+            CieBasedColorSpace::CalRgb(self.space).into()
+        }
+    }
+
+    impl Eq for Color {}
+
+    impl Hash for Color {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            for c in self.components {
+                c.to_bits().hash(state);
+            }
+            self.space.hash(state);
+        }
+    }
+
+    impl Default for Color {
+        fn default() -> Self {
+            Color::new(0.0, 0.0, 0.0)
+        }
+    }
+
+    impl From<Color> for super::RegularColor {
+        fn from(val: Color) -> Self {
+            super::RegularColor::CalRgb(val)
+        }
+    }
+
+    impl From<Color> for super::Color {
+        fn from(val: Color) -> Self {
+            super::RegularColor::from(val).into()
+        }
+    }
+}
+
+/// Calibrated gray-scale colors.
+pub mod cal_gray {
+    use std::hash::{Hash, Hasher};
+
+    use crate::color::{CieBasedColorSpace, RegularColorSpace, WHITE_POINT_D65};
+
+    /// A calibrated gray-scale color space.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct CalGraySpace {
+        pub(crate) white_point: [f32; 3],
+        pub(crate) black_point: [f32; 3],
+        pub(crate) gamma: f32,
+    }
+
+    impl CalGraySpace {
+        /// Create a new calibrated gray space with the given diffuse white point
+        /// in CIE XYZ coordinates. The `Y` coordinate of the white point must be 1.
+        pub fn new(white_point: [f32; 3]) -> Self {
+            Self {
+                white_point,
+                black_point: [0.0; 3],
+                gamma: 2.2,
+            }
+        }
+
+        /// Set the diffuse black point in CIE XYZ coordinates.
+        ///
+        /// Defaults to `[0.0, 0.0, 0.0]`.
+        pub fn with_black_point(mut self, black_point: [f32; 3]) -> Self {
+            self.black_point = black_point;
+            self
+        }
+
+        /// Set the gamma of the gray component.
+        ///
+        /// Defaults to `2.2`.
+        pub fn with_gamma(mut self, gamma: f32) -> Self {
+            self.gamma = gamma;
+            self
+        }
+    }
+
+    impl Default for CalGraySpace {
+        fn default() -> Self {
+            Self::new(WHITE_POINT_D65)
+        }
+    }
+
+    impl Eq for CalGraySpace {}
+
+    impl Hash for CalGraySpace {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            for f in self.white_point.iter().chain(self.black_point.iter()) {
+                f.to_bits().hash(state);
+            }
+            self.gamma.to_bits().hash(state);
+        }
+    }
+
+    /// A calibrated gray-scale color.
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub struct Color {
+        pub(crate) lightness: f32,
+        pub(crate) space: CalGraySpace,
+    }
+
+    impl Color {
+        /// Create a new calibrated gray color in the default space. The lightness
+        /// is clamped to the range 0 to 1.
+        pub fn new(lightness: f32) -> Self {
+            Self::new_with_space(lightness, CalGraySpace::default())
+        }
+
+        /// Create a new calibrated gray color in a specific space.
+        pub fn new_with_space(lightness: f32, space: CalGraySpace) -> Self {
+            Self { lightness, space }
+        }
+
+        pub(crate) fn to_pdf_color(self) -> f32 {
+            self.lightness.clamp(0.0, 1.0)
+        }
+
+        pub(crate) fn to_rgb(self) -> super::rgb::Color {
+            let y = self.to_pdf_color().powf(self.space.gamma);
+
+            super::xyz_to_rgb(
+                self.space.white_point.map(|w| w * y),
+                self.space.white_point,
+            )
+        }
+
+        pub(super) fn color_space(&self) -> RegularColorSpace {
+            CieBasedColorSpace::CalGray(self.space).into()
+        }
+    }
+
+    impl Eq for Color {}
+
+    impl Hash for Color {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.lightness.to_bits().hash(state);
+            self.space.hash(state);
+        }
+    }
+
+    impl Default for Color {
+        fn default() -> Self {
+            Color::new(0.0)
+        }
+    }
+
+    impl From<Color> for super::RegularColor {
+        fn from(val: Color) -> Self {
+            super::RegularColor::CalGray(val)
+        }
+    }
+
+    impl From<Color> for super::Color {
+        fn from(val: Color) -> Self {
+            super::RegularColor::from(val).into()
+        }
+    }
+}
+
+/// Convert a CIE XYZ color relative to the given white point to sRGB, using a simple
+/// von Kries adaptation to the D65 white point.
+fn xyz_to_rgb(xyz: [f32; 3], white_point: [f32; 3]) -> rgb::Color {
+    let [x, y, z] = [0, 1, 2].map(|i| xyz[i] * WHITE_POINT_D65[i] / white_point[i]);
+
+    let encode = |c: f32| {
+        let c = c.clamp(0.0, 1.0);
+        let c = if c <= 0.0031308 {
+            12.92 * c
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        };
+
+        (c * 255.0).round() as u8
+    };
+
+    rgb::Color::new(
+        encode(3.2406 * x - 1.5372 * y - 0.4986 * z),
+        encode(-0.9689 * x + 1.8758 * y + 0.0415 * z),
+        encode(0.0557 * x - 0.2040 * y + 1.0570 * z),
+    )
+}
+
 /// Separation (spot) colors.
 pub mod separation {
     use crate::color::RegularColor;
@@ -479,6 +1157,242 @@ pub mod separation {
     }
 }
 
+/// DeviceN (multi-colorant) colors.
+pub mod devicen {
+    use crate::color::separation::SeparationColorant;
+    use crate::color::RegularColor;
+    use crate::num::NormalizedF32;
+
+    /// The maximum number of colorants in a DeviceN color space.
+    pub(crate) const MAX_COLORANTS: usize = 32;
+
+    /// A DeviceN color, consisting of one tint for each colorant of its space.
+    #[derive(Debug, Hash, Eq, PartialEq, Clone)]
+    pub struct Color {
+        pub(crate) tints: Vec<u8>,
+        pub(crate) space: DeviceNSpace,
+    }
+
+    impl Color {
+        /// Create a new DeviceN color.
+        ///
+        /// The tints are given in the same order as the colorants of the space.
+        /// Missing tints default to 0, and superfluous tints are ignored.
+        pub fn new(tints: Vec<u8>, space: DeviceNSpace) -> Self {
+            Self { tints, space }
+        }
+
+        pub(crate) fn to_pdf_color(&self) -> Vec<f32> {
+            let mut tints = self
+                .tints
+                .iter()
+                .map(|t| *t as f32 / 255.0)
+                .collect::<Vec<_>>();
+            tints.resize(self.space.colorants.len(), 0.0);
+
+            tints
+        }
+
+        pub(crate) fn color_space(&self) -> DeviceNSpace {
+            self.space.clone()
+        }
+
+        /// The color in the alternate space that the tints are mapped to.
+        pub(crate) fn to_regular(&self) -> RegularColor {
+            let components = crate::graphics::devicen::mix(&self.space, &self.to_pdf_color());
+            self.space.fallbacks[0].with_pdf_color(&components)
+        }
+    }
+
+    impl From<Color> for super::SpecialColor {
+        fn from(val: Color) -> Self {
+            super::SpecialColor::DeviceN(val)
+        }
+    }
+
+    impl From<Color> for super::Color {
+        fn from(val: Color) -> Self {
+            super::SpecialColor::from(val).into()
+        }
+    }
+
+    /// A DeviceN color space, combining multiple colorants into a single color.
+    ///
+    /// Each colorant is associated with a fallback color that represents its
+    /// appearance at full tint. The fallback colors define the alternate space
+    /// of the DeviceN space, and krilla automatically creates a tint transform
+    /// that mixes them multiplicatively, similar to overlaid inks.
+    ///
+    /// Colorants named `Cyan`, `Magenta`, `Yellow` and `Black` refer to the process
+    /// colorants, while all other custom names are treated as spot colorants. Each
+    /// spot colorant is additionally written as a Separation color space with its
+    /// fallback color, so the same rules for consistent fallback colors as for
+    /// [`SeparationSpace`](super::separation::SeparationSpace) apply.
+    #[derive(Debug, Eq, PartialEq, Hash, Clone)]
+    pub struct DeviceNSpace {
+        pub(crate) colorants: Vec<SeparationColorant>,
+        pub(crate) fallbacks: Vec<RegularColor>,
+        pub(crate) nchannel: Option<NChannelAttributes>,
+    }
+
+    impl DeviceNSpace {
+        /// Create a new DeviceN space from a list of colorants and their fallback colors.
+        ///
+        /// Returns `None` if no or more than 32 colorants were given, if a colorant
+        /// is [`SeparationColorant::AllColorants`], if a colorant appears more than once,
+        /// or if the fallback colors are not all in the same color space.
+        ///
+        /// Note that for spaces with more than 12 colorants, the tint transform is
+        /// written as a PostScript function, which is forbidden by some export formats.
+        /// Spaces with more than 8 colorants are not supported by PDF 1.4.
+        pub fn new(colorants: Vec<(SeparationColorant, RegularColor)>) -> Option<Self> {
+            if colorants.is_empty() || colorants.len() > MAX_COLORANTS {
+                return None;
+            }
+
+            let first = colorants[0].1;
+
+            for (i, (colorant, fallback)) in colorants.iter().enumerate() {
+                if *colorant == SeparationColorant::AllColorants || !first.is_same_space(*fallback)
+                {
+                    return None;
+                }
+
+                if *colorant != SeparationColorant::NoColorant
+                    && colorants[..i].iter().any(|(c, _)| c == colorant)
+                {
+                    return None;
+                }
+            }
+
+            let (colorants, fallbacks) = colorants.into_iter().unzip();
+
+            Some(Self {
+                colorants,
+                fallbacks,
+                nchannel: None,
+            })
+        }
+
+        /// Write the space as an NChannel color space with the given attributes.
+        ///
+        /// NChannel color spaces require PDF 1.6. For earlier versions, the space
+        /// is written as a regular DeviceN color space.
+        ///
+        /// Solidities of colorants that are not part of the space are ignored. If the
+        /// printing order doesn't contain all colorants of the space, the order of the
+        /// colorants in the space is used instead.
+        pub fn with_nchannel(mut self, mut attributes: NChannelAttributes) -> Self {
+            let is_named = |c: &SeparationColorant| {
+                !matches!(
+                    c,
+                    SeparationColorant::AllColorants | SeparationColorant::NoColorant
+                )
+            };
+
+            attributes
+                .solidities
+                .retain(|(c, _)| is_named(c) && self.colorants.contains(c));
+            attributes.printing_order.retain(is_named);
+
+            if self
+                .colorants
+                .iter()
+                .filter(|c| is_named(c))
+                .any(|c| !attributes.printing_order.contains(c))
+            {
+                attributes.printing_order.clear();
+            }
+
+            self.nchannel = Some(attributes);
+            self
+        }
+    }
+
+    impl From<DeviceNSpace> for super::SpecialColorSpace {
+        fn from(value: DeviceNSpace) -> Self {
+            Self::DeviceN(value)
+        }
+    }
+
+    /// The attributes of an NChannel color space.
+    #[derive(Debug, Eq, PartialEq, Hash, Clone, Default)]
+    pub struct NChannelAttributes {
+        pub(crate) process: Option<ProcessColorants>,
+        pub(crate) solidities: Vec<(SeparationColorant, NormalizedF32)>,
+        pub(crate) printing_order: Vec<SeparationColorant>,
+    }
+
+    impl NChannelAttributes {
+        /// Create new, empty NChannel attributes.
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Set the process color model whose components are part of the colorants.
+        pub fn with_process(mut self, process: ProcessColorants) -> Self {
+            self.process = Some(process);
+            self
+        }
+
+        /// Add a mixing hint for the solidity of a colorant, where 1 means that the
+        /// colorant completely covers the colorants printed before it.
+        ///
+        /// If the solidity of a colorant is specified more than once, the last one
+        /// is used. Solidities of colorants that are not part of the color space
+        /// the attributes are used with are ignored.
+        pub fn with_solidity(
+            mut self,
+            colorant: SeparationColorant,
+            solidity: NormalizedF32,
+        ) -> Self {
+            self.solidities.retain(|(c, _)| *c != colorant);
+            self.solidities.push((colorant, solidity));
+            self
+        }
+
+        /// Set the order in which the colorants are printed.
+        ///
+        /// The printing order must contain all colorants of the color space the
+        /// attributes are used with, and may contain additional ones. Otherwise,
+        /// or if solidities were specified but no printing order was given, the
+        /// order of the colorants in the color space is used.
+        pub fn with_printing_order(mut self, printing_order: Vec<SeparationColorant>) -> Self {
+            self.printing_order = printing_order;
+            self
+        }
+    }
+
+    /// The process color model of an NChannel color space.
+    #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+    pub enum ProcessColorants {
+        /// The `Red`, `Green` and `Blue` colorants.
+        Rgb,
+        /// The `Gray` colorant.
+        Gray,
+        /// The `Cyan`, `Magenta`, `Yellow` and `Black` colorants.
+        Cmyk,
+    }
+
+    impl ProcessColorants {
+        pub(crate) fn components(self) -> &'static [&'static str] {
+            match self {
+                Self::Rgb => &["Red", "Green", "Blue"],
+                Self::Gray => &["Gray"],
+                Self::Cmyk => &["Cyan", "Magenta", "Yellow", "Black"],
+            }
+        }
+
+        pub(crate) fn color(self) -> RegularColor {
+            match self {
+                Self::Rgb => super::rgb::Color::default().into(),
+                Self::Gray => super::luma::Color::default().into(),
+                Self::Cmyk => super::cmyk::Color::default().into(),
+            }
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
 pub(crate) enum ColorSpace {
     Device(DeviceColorSpace),
@@ -525,6 +1439,11 @@ pub(crate) enum CieBasedColorSpace {
     Srgb,
     Luma,
     Cmyk(ICCBasedColorSpace<4>),
+    Rgb(ICCBasedColorSpace<3>),
+    Gray(ICCBasedColorSpace<1>),
+    Lab(lab::LabSpace),
+    CalRgb(cal_rgb::CalRgbSpace),
+    CalGray(cal_gray::CalGraySpace),
 }
 
 impl From<CieBasedColorSpace> for ColorSpace {
@@ -543,6 +1462,8 @@ impl From<CieBasedColorSpace> for RegularColorSpace {
 pub(crate) enum SpecialColorSpace {
     /// A Separation color space with its colorant and fallback.
     Separation(separation::SeparationSpace),
+    /// A DeviceN color space with its colorants and fallbacks.
+    DeviceN(devicen::DeviceNSpace),
 }
 
 impl From<SpecialColorSpace> for ColorSpace {
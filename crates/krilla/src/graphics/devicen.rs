@@ -0,0 +1,360 @@
+use std::ops::DerefMut;
+
+use pdf_writer::types::PostScriptOp;
+use pdf_writer::{Chunk, Finish, Name, Ref};
+
+use crate::chunk_container::ChunkContainer;
+use crate::color::devicen::DeviceNSpace;
+use crate::color::separation::{SeparationColorant, SeparationSpace};
+use crate::color::{Color, ColorSpace, SpecialColorSpace};
+use crate::configure::{PdfVersion, ValidationError};
+use crate::graphics::shading_function::serialize_sampled_function;
+use crate::resource::{self, Resourceable};
+use crate::serialize::{Cacheable, SerializeContext};
+use crate::stream::FilterStreamBuilder;
+use crate::util::{set_colorspace, write_colorspace};
+
+/// The maximum number of colorants in a DeviceN color space for PDF 1.4.
+const MAX_COLORANTS_PDF14: usize = 8;
+/// The maximum number of colorants for which the tint transform is written as a
+/// sampled function. Beyond that, the number of samples becomes too large and a
+/// PostScript function is used instead.
+const MAX_SAMPLED_COLORANTS: usize = 12;
+/// The names of the process colorants, which never get an entry in the
+/// `Colorants` dictionary.
+const PROCESS_COLORANTS: [&str; 4] = ["Cyan", "Magenta", "Yellow", "Black"];
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub(crate) struct DeviceNColorSpace {
+    space: DeviceNSpace,
+}
+
+impl DeviceNColorSpace {
+    pub fn new(space: DeviceNSpace) -> Self {
+        Self { space }
+    }
+}
+
+impl Cacheable for DeviceNColorSpace {
+    fn serialize(
+        self,
+        sc: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+        root_ref: Ref,
+    ) {
+        let space = self.space;
+
+        if space.colorants.len() > MAX_COLORANTS_PDF14 {
+            sc.register_validation_error(ValidationError::TooManyColorants);
+        }
+
+        let alternate_cs = space.fallbacks[0].color_space(sc);
+        let alternate_cs = sc.register_colorspace(chunk_container, alternate_cs.into());
+        let tint_transform = serialize_tint_transform(sc, chunk_container, &space);
+
+        let process = space
+            .nchannel
+            .as_ref()
+            .and_then(|n| n.process)
+            .filter(|_| sc.serialize_settings().pdf_version() >= PdfVersion::Pdf16);
+        let process_cs = process.map(|p| {
+            let cs = p.color().color_space(sc);
+            (p, sc.register_colorspace(chunk_container, cs.into()))
+        });
+
+        // Each spot colorant is described by a Separation color space with the
+        // same fallback, which also ensures that a colorant is always used with
+        // the same fallback color.
+        let spot_colorants = space
+            .colorants
+            .iter()
+            .zip(&space.fallbacks)
+            .filter(|(colorant, _)| match colorant {
+                SeparationColorant::Custom(name) => {
+                    !PROCESS_COLORANTS.contains(&name.as_str())
+                        && !process.is_some_and(|p| p.components().contains(&name.as_str()))
+                }
+                _ => false,
+            })
+            .map(|(colorant, fallback)| {
+                let separation = SeparationSpace::new(colorant.clone(), *fallback);
+                let cs = sc.register_colorspace(
+                    chunk_container,
+                    ColorSpace::Special(SpecialColorSpace::Separation(separation)),
+                );
+                (colorant, cs)
+            })
+            .collect::<Vec<_>>();
+
+        let nchannel = space
+            .nchannel
+            .as_ref()
+            .filter(|_| sc.serialize_settings().pdf_version() >= PdfVersion::Pdf16);
+
+        let chunk = &mut chunk_container.non_stream.color_spaces;
+
+        // Write DeviceN color space array: [/DeviceN names alternateSpace tintTransform attributes]
+        let mut array = chunk.indirect(root_ref).array();
+        array.item(Name(b"DeviceN"));
+        array
+            .push()
+            .array()
+            .items(space.colorants.iter().map(|c| c.to_pdf()));
+        write_colorspace(alternate_cs, array.push());
+        array.item(tint_transform);
+
+        if !spot_colorants.is_empty() || nchannel.is_some() {
+            let mut attributes = array.push().dict();
+
+            if nchannel.is_some() {
+                attributes.pair(Name(b"Subtype"), Name(b"NChannel"));
+            }
+
+            if !spot_colorants.is_empty() {
+                let mut colorants = attributes.insert(Name(b"Colorants")).dict();
+
+                for (colorant, cs) in spot_colorants {
+                    write_colorspace(cs, colorants.insert(colorant.to_pdf()));
+                }
+
+                colorants.finish();
+            }
+
+            if let Some((process, cs)) = process_cs {
+                let mut process_dict = attributes.insert(Name(b"Process")).dict();
+                set_colorspace(cs, &mut process_dict);
+                process_dict
+                    .insert(Name(b"Components"))
+                    .array()
+                    .items(process.components().iter().map(|c| Name(c.as_bytes())));
+                process_dict.finish();
+            }
+
+            if let Some(nchannel) = nchannel.filter(|n| !n.solidities.is_empty()) {
+                let mut hints = attributes.insert(Name(b"MixingHints")).dict();
+
+                let mut solidities = hints.insert(Name(b"Solidities")).dict();
+                for (colorant, solidity) in &nchannel.solidities {
+                    solidities.pair(colorant.to_pdf(), solidity.get());
+                }
+                solidities.finish();
+
+                let printing_order = if nchannel.printing_order.is_empty() {
+                    &space.colorants
+                } else {
+                    &nchannel.printing_order
+                };
+                hints.insert(Name(b"PrintingOrder")).array().items(
+                    printing_order
+                        .iter()
+                        .filter(|c| **c != SeparationColorant::NoColorant)
+                        .map(|c| c.to_pdf()),
+                );
+
+                hints.finish();
+            }
+
+            attributes.finish();
+        }
+
+        array.finish();
+    }
+}
+
+impl Resourceable for DeviceNColorSpace {
+    type Resource = resource::ColorSpace;
+}
+
+/// How the tints of the colorants are mixed in one component of the alternate space.
+enum Mixing {
+    /// The colorants cover the space between `white` and `black` multiplicatively,
+    /// like overlaid inks.
+    Multiply {
+        white: f32,
+        black: f32,
+        coverages: Vec<f32>,
+    },
+    /// The offsets of the colorants from `white` are added up. Used for components
+    /// where white is not at the boundary of the range, like `a*` and `b*` of Lab.
+    Add { white: f32, offsets: Vec<f32> },
+}
+
+impl Mixing {
+    fn apply(&self, tints: &[f32]) -> f32 {
+        match self {
+            Mixing::Multiply {
+                white,
+                black,
+                coverages,
+            } => {
+                let transmittance = coverages
+                    .iter()
+                    .zip(tints)
+                    .map(|(c, t)| 1.0 - t * c)
+                    .product::<f32>();
+
+                black + (white - black) * transmittance
+            }
+            Mixing::Add { white, offsets } => {
+                white + offsets.iter().zip(tints).map(|(o, t)| o * t).sum::<f32>()
+            }
+        }
+    }
+}
+
+fn mixing(space: &DeviceNSpace) -> Vec<Mixing> {
+    let first = space.fallbacks[0];
+    let range = Color::from(first).pdf_range();
+    let fallbacks = space
+        .fallbacks
+        .iter()
+        .map(|f| Color::from(*f).to_pdf_color())
+        .collect::<Vec<_>>();
+
+    first
+        .white_pdf_color()
+        .into_iter()
+        .enumerate()
+        .map(|(k, white)| {
+            let (min, max) = (range[2 * k], range[2 * k + 1]);
+
+            if white == min || white == max {
+                let black = if white == min { max } else { min };
+
+                Mixing::Multiply {
+                    white,
+                    black,
+                    coverages: fallbacks
+                        .iter()
+                        .map(|f| (f[k] - white) / (black - white))
+                        .collect(),
+                }
+            } else {
+                Mixing::Add {
+                    white,
+                    offsets: fallbacks.iter().map(|f| f[k] - white).collect(),
+                }
+            }
+        })
+        .collect()
+}
+
+/// Mix the fallback colors of the space with the given tints, yielding the
+/// components of the color in the alternate space.
+pub(crate) fn mix(space: &DeviceNSpace, tints: &[f32]) -> Vec<f32> {
+    mixing(space).iter().map(|m| m.apply(tints)).collect()
+}
+
+fn serialize_tint_transform(
+    sc: &mut SerializeContext,
+    chunk_container: &mut ChunkContainer,
+    space: &DeviceNSpace,
+) -> Ref {
+    let mixing = mixing(space);
+    let num_colorants = space.colorants.len();
+    let range = Color::from(space.fallbacks[0]).pdf_range();
+    let domain = [0.0, 1.0].repeat(num_colorants);
+
+    let mut chunk = sc.new_chunk();
+
+    // Since the mixing is linear in each tint, sampling each tint at 0 and 1
+    // and interpolating in between yields exactly the same result.
+    let root_ref = if num_colorants <= MAX_SAMPLED_COLORANTS {
+        let samples = (0..1usize << num_colorants).map(|index| {
+            let tints = (0..num_colorants)
+                .map(|i| ((index >> i) & 1) as f32)
+                .collect::<Vec<_>>();
+
+            mixing.iter().map(|m| m.apply(&tints)).collect()
+        });
+
+        serialize_sampled_function(
+            &mut chunk,
+            sc,
+            &vec![2; num_colorants],
+            &domain,
+            &range,
+            samples,
+        )
+    } else {
+        sc.register_validation_error(ValidationError::ContainsPostScript(sc.location));
+        serialize_postscript_tint_transform(&mut chunk, sc, &mixing, &domain, &range)
+    };
+
+    chunk_container.streams.shading_functions.push(chunk);
+
+    root_ref
+}
+
+fn serialize_postscript_tint_transform(
+    chunk: &mut Chunk,
+    sc: &mut SerializeContext,
+    mixing: &[Mixing],
+    domain: &[f32],
+    range: &[f32],
+) -> Ref {
+    use pdf_writer::types::PostScriptOp::*;
+
+    let root_ref = sc.new_ref();
+    let num_colorants = domain.len() / 2;
+    let mut code = vec![];
+
+    // Stack: t_1 ... t_n, followed by the components that were already computed.
+    for (k, mixing) in mixing.iter().enumerate() {
+        // Copies the i-th tint to the top of the stack, given that the
+        // accumulator is on top of the `k` computed components.
+        let tint = |i: usize| Integer((num_colorants - i + k) as i32);
+
+        match mixing {
+            Mixing::Multiply {
+                white,
+                black,
+                coverages,
+            } => {
+                code.push(Real(1.0));
+
+                for (i, coverage) in coverages.iter().enumerate() {
+                    code.extend([
+                        tint(i),
+                        Index,
+                        Real(*coverage),
+                        Mul,
+                        Neg,
+                        Real(1.0),
+                        Add,
+                        Mul,
+                    ]);
+                }
+
+                code.extend([Real(white - black), Mul, Real(*black), Add]);
+            }
+            Mixing::Add { white, offsets } => {
+                code.push(Real(*white));
+
+                for (i, offset) in offsets.iter().enumerate() {
+                    code.extend([tint(i), Index, Real(*offset), Mul, Add]);
+                }
+            }
+        }
+    }
+
+    // Move the components below the tints and remove the tints.
+    code.extend([
+        Integer((num_colorants + mixing.len()) as i32),
+        Integer(mixing.len() as i32),
+        Roll,
+    ]);
+    code.extend(std::iter::repeat_n(Pop, num_colorants));
+
+    let encoded = PostScriptOp::encode(&code);
+    sc.register_limits(encoded.limits());
+    let encoded = FilterStreamBuilder::new_from_content_stream(&encoded, &sc.serialize_settings())
+        .finish(&sc.serialize_settings());
+    let mut function = chunk.post_script_function(root_ref, encoded.encoded_data());
+    encoded.write_filters(function.deref_mut().deref_mut());
+    function.domain(domain.iter().copied());
+    function.range(range.iter().copied());
+    function.finish();
+
+    root_ref
+}
@@ -7,6 +7,8 @@ use pdf_writer::{Finish, Name, Ref};
 use crate::chunk_container::ChunkContainer;
 use crate::configure::ValidationError;
 use crate::geom::{Rect, Transform};
+use crate::graphics::blend::OverprintMode;
+use crate::graphics::color::RenderingIntent;
 use crate::graphics::mask::Mask;
 use crate::num::NormalizedF32;
 use crate::resource;
@@ -22,8 +24,16 @@ struct Repr {
     stroking_alpha: Option<NormalizedF32>,
     /// The blend mode.
     blend_mode: Option<BlendMode>,
-    /// An active mask.
-    mask: Option<Ref>,
+    /// An active mask, or `None` if the mask should be removed.
+    mask: Option<Option<Ref>>,
+    /// Whether overprinting is enabled for non-stroking operations.
+    non_stroking_overprint: Option<bool>,
+    /// Whether overprinting is enabled for stroking operations.
+    stroking_overprint: Option<bool>,
+    /// The overprint mode.
+    overprint_mode: Option<OverprintMode>,
+    /// The rendering intent.
+    rendering_intent: Option<RenderingIntent>,
 }
 
 /// A graphics state containing information about
@@ -31,6 +41,8 @@ struct Repr {
 /// - The current non-stroking alpha.
 /// - The current blend mode.
 /// - The current mask.
+/// - The current overprint settings.
+/// - The current rendering intent.
 ///
 /// This struct provides exposes a builder pattern for setting the various properties
 /// individually.
@@ -75,7 +87,48 @@ impl ExtGState {
         chunk_container: &mut ChunkContainer,
     ) -> Self {
         let mask_ref = sc.register_cacheable(chunk_container, mask);
-        Arc::make_mut(&mut self.0).mask = Some(mask_ref);
+        Arc::make_mut(&mut self.0).mask = Some(Some(mask_ref));
+        self
+    }
+
+    /// Create a graphics state that resets all properties that are set in the
+    /// current one to their initial values.
+    ///
+    /// The mask is also reset if `reset_mask` is true, which is useful in case a
+    /// mask was activated by a separate graphics state.
+    #[must_use]
+    pub(crate) fn neutral(&self, reset_mask: bool) -> Self {
+        let repr = Repr {
+            non_stroking_alpha: self.0.non_stroking_alpha.map(|_| NormalizedF32::ONE),
+            stroking_alpha: self.0.stroking_alpha.map(|_| NormalizedF32::ONE),
+            blend_mode: self.0.blend_mode.map(|_| BlendMode::Normal),
+            mask: (reset_mask || self.0.mask.is_some()).then_some(None),
+            non_stroking_overprint: self.0.non_stroking_overprint.map(|_| false),
+            stroking_overprint: self.0.stroking_overprint.map(|_| false),
+            overprint_mode: self.0.overprint_mode.map(|_| OverprintMode::Zero),
+            rendering_intent: self
+                .0
+                .rendering_intent
+                .map(|_| RenderingIntent::RelativeColorimetric),
+        };
+
+        Self(Arc::new(repr))
+    }
+
+    /// Create a new graphics state with overprint settings.
+    #[must_use]
+    pub(crate) fn overprint(mut self, fill: bool, stroke: bool, mode: OverprintMode) -> Self {
+        let repr = Arc::make_mut(&mut self.0);
+        repr.non_stroking_overprint = Some(fill);
+        repr.stroking_overprint = Some(stroke);
+        repr.overprint_mode = Some(mode);
+        self
+    }
+
+    /// Create a new graphics state with a rendering intent.
+    #[must_use]
+    pub(crate) fn rendering_intent(mut self, rendering_intent: RenderingIntent) -> Self {
+        Arc::make_mut(&mut self.0).rendering_intent = Some(rendering_intent);
         self
     }
 
@@ -85,6 +138,10 @@ impl ExtGState {
             && self.0.stroking_alpha.is_none()
             && self.0.non_stroking_alpha.is_none()
             && self.0.blend_mode.is_none()
+            && self.0.non_stroking_overprint.is_none()
+            && self.0.stroking_overprint.is_none()
+            && self.0.overprint_mode.is_none()
+            && self.0.rendering_intent.is_none()
     }
 
     /// Integrate another graphics state into the current one. This is done by replacing
@@ -106,6 +163,22 @@ impl ExtGState {
         if let Some(mask) = other.0.mask {
             Arc::make_mut(&mut self.0).mask = Some(mask);
         }
+
+        if let Some(non_stroking_overprint) = other.0.non_stroking_overprint {
+            Arc::make_mut(&mut self.0).non_stroking_overprint = Some(non_stroking_overprint);
+        }
+
+        if let Some(stroking_overprint) = other.0.stroking_overprint {
+            Arc::make_mut(&mut self.0).stroking_overprint = Some(stroking_overprint);
+        }
+
+        if let Some(overprint_mode) = other.0.overprint_mode {
+            Arc::make_mut(&mut self.0).overprint_mode = Some(overprint_mode);
+        }
+
+        if let Some(rendering_intent) = other.0.rendering_intent {
+            Arc::make_mut(&mut self.0).rendering_intent = Some(rendering_intent);
+        }
     }
 }
 
@@ -143,10 +216,39 @@ impl Cacheable for ExtGState {
             ext_st.blend_mode(bm);
         }
 
-        if let Some(mask_ref) = self.0.mask {
-            sc.register_validation_error(ValidationError::Transparency(sc.location));
+        match self.0.mask {
+            Some(Some(mask_ref)) => {
+                sc.register_validation_error(ValidationError::Transparency(sc.location));
+
+                ext_st.pair(Name(b"SMask"), mask_ref);
+            }
+            Some(None) => {
+                ext_st.pair(Name(b"SMask"), Name(b"None"));
+            }
+            None => {}
+        }
+
+        if let Some(op) = self.0.non_stroking_overprint {
+            ext_st.pair(Name(b"op"), op);
+        }
+
+        if let Some(op) = self.0.stroking_overprint {
+            ext_st.pair(Name(b"OP"), op);
+        }
+
+        if let Some(opm) = self.0.overprint_mode {
+            let overprints = self.0.non_stroking_overprint == Some(true)
+                || self.0.stroking_overprint == Some(true);
+
+            if opm == OverprintMode::Nonzero && overprints {
+                sc.register_validation_error(ValidationError::NonzeroOverprintMode(sc.location));
+            }
+
+            ext_st.pair(Name(b"OPM"), opm.to_pdf());
+        }
 
-            ext_st.pair(Name(b"SMask"), mask_ref);
+        if let Some(ri) = self.0.rendering_intent {
+            ext_st.rendering_intent(ri.to_pdf());
         }
 
         ext_st.finish();
@@ -1,12 +1,13 @@
-//! Using ICC profiles.
+//! Using ICC profiles and output intents.
 
 use std::ops::Deref;
 use std::ops::DerefMut;
 use std::sync::Arc;
 
-use pdf_writer::{Finish, Name, Ref};
+use pdf_writer::{Finish, Name, Ref, TextStr};
 
 use crate::chunk_container::ChunkContainer;
+use crate::configure::ValidationError;
 use crate::resource;
 use crate::resource::Resourceable;
 use crate::serialize::{Cacheable, SerializeContext};
@@ -60,15 +61,35 @@ impl<const C: u8> Cacheable for ICCProfile<C> {
     }
 }
 
-#[cfg(feature = "raster-images")]
+/// An ICC profile with an arbitrary number of channels.
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
-pub(crate) enum GenericICCProfile {
+pub enum GenericICCProfile {
+    /// A grayscale ICC profile.
     Luma(ICCProfile<1>),
+    /// An RGB ICC profile.
     Rgb(ICCProfile<3>),
+    /// A CMYK ICC profile.
     Cmyk(ICCProfile<4>),
 }
 
-#[cfg(feature = "raster-images")]
+impl From<ICCProfile<1>> for GenericICCProfile {
+    fn from(profile: ICCProfile<1>) -> Self {
+        Self::Luma(profile)
+    }
+}
+
+impl From<ICCProfile<3>> for GenericICCProfile {
+    fn from(profile: ICCProfile<3>) -> Self {
+        Self::Rgb(profile)
+    }
+}
+
+impl From<ICCProfile<4>> for GenericICCProfile {
+    fn from(profile: ICCProfile<4>) -> Self {
+        Self::Cmyk(profile)
+    }
+}
+
 impl GenericICCProfile {
     pub(crate) fn metadata(&self) -> &ICCMetadata {
         match self {
@@ -79,7 +100,6 @@ impl GenericICCProfile {
     }
 }
 
-#[cfg(feature = "raster-images")]
 impl Cacheable for GenericICCProfile {
     fn serialize(
         self,
@@ -95,6 +115,180 @@ impl Cacheable for GenericICCProfile {
     }
 }
 
+/// The subtype of an output intent, indicating the standard it belongs to.
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub enum OutputIntentSubtype {
+    /// An output intent for PDF/A (`GTS_PDFA1`).
+    PdfA,
+    /// An output intent for PDF/X (`GTS_PDFX`).
+    PdfX,
+    /// An output intent with a custom subtype.
+    Custom(String),
+}
+
+impl OutputIntentSubtype {
+    fn to_pdf(&self) -> pdf_writer::types::OutputIntentSubtype<'_> {
+        match self {
+            OutputIntentSubtype::PdfA => pdf_writer::types::OutputIntentSubtype::PDFA,
+            OutputIntentSubtype::PdfX => pdf_writer::types::OutputIntentSubtype::PDFX,
+            OutputIntentSubtype::Custom(name) => {
+                pdf_writer::types::OutputIntentSubtype::Custom(Name(name.as_bytes()))
+            }
+        }
+    }
+}
+
+/// An output intent.
+///
+/// Output intents describe the color characteristics of the output device or
+/// production condition a document is intended for, for example a specific printing
+/// condition like FOGRA39 or GRACoL. Output intents can be set for the whole document
+/// via [`SerializeSettings::output_intents`] and, starting with PDF 2.0, for individual
+/// pages via [`PageSettings::with_output_intents`].
+///
+/// [`SerializeSettings::output_intents`]: crate::SerializeSettings::output_intents
+/// [`PageSettings::with_output_intents`]: crate::page::PageSettings::with_output_intents
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub struct OutputIntent {
+    subtype: Option<OutputIntentSubtype>,
+    output_condition_identifier: String,
+    output_condition: Option<String>,
+    registry_name: Option<String>,
+    info: Option<String>,
+    profile: Option<GenericICCProfile>,
+}
+
+impl OutputIntent {
+    /// Create a new output intent with the given output condition identifier,
+    /// like `FOGRA39` or `CGATS TR 006`.
+    pub fn new(output_condition_identifier: impl Into<String>) -> Self {
+        Self {
+            subtype: None,
+            output_condition_identifier: output_condition_identifier.into(),
+            output_condition: None,
+            registry_name: None,
+            info: None,
+            profile: None,
+        }
+    }
+
+    /// Set the subtype of the output intent.
+    ///
+    /// If not set, the subtype required by the used validator is chosen, or
+    /// [`OutputIntentSubtype::PdfX`] if there is none.
+    pub fn with_subtype(mut self, subtype: OutputIntentSubtype) -> Self {
+        self.subtype = Some(subtype);
+        self
+    }
+
+    /// Set a human-readable name of the output condition.
+    pub fn with_output_condition(mut self, output_condition: impl Into<String>) -> Self {
+        self.output_condition = Some(output_condition.into());
+        self
+    }
+
+    /// Set the registry in which the output condition identifier is defined,
+    /// like `http://www.color.org`.
+    pub fn with_registry_name(mut self, registry_name: impl Into<String>) -> Self {
+        self.registry_name = Some(registry_name.into());
+        self
+    }
+
+    /// Set a human-readable description of the output condition.
+    ///
+    /// This is required if the output condition identifier is not defined
+    /// in a registry.
+    pub fn with_info(mut self, info: impl Into<String>) -> Self {
+        self.info = Some(info.into());
+        self
+    }
+
+    /// Set the ICC profile that characterizes the output condition.
+    ///
+    /// Note that the profile will not be embedded if its version is not
+    /// supported by the PDF version of the document. In this case, a
+    /// [`ValidationError::UnsupportedOutputIntentProfile`] is registered.
+    pub fn with_profile(mut self, profile: impl Into<GenericICCProfile>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    pub(crate) fn profile(&self) -> Option<&GenericICCProfile> {
+        self.profile.as_ref()
+    }
+
+    pub(crate) fn subtype(&self) -> Option<&OutputIntentSubtype> {
+        self.subtype.as_ref()
+    }
+
+    pub(crate) fn with_default_subtype(mut self, subtype: OutputIntentSubtype) -> Self {
+        self.subtype.get_or_insert(subtype);
+        self
+    }
+}
+
+impl Cacheable for OutputIntent {
+    fn serialize(
+        self,
+        sc: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+        root_ref: Ref,
+    ) {
+        // Register the profile by itself, so that it is shared with
+        // ICC-based color spaces that use the same profile.
+        let profile = match self.profile {
+            Some(p)
+                if !sc
+                    .serialize_settings()
+                    .pdf_version()
+                    .supports_icc(p.metadata()) =>
+            {
+                sc.register_validation_error(ValidationError::UnsupportedOutputIntentProfile);
+                None
+            }
+            p => p,
+        };
+
+        if let Some(p) = &profile {
+            if let Err(validation_error) = sc.validation_store().validate_dest_output_profile(p) {
+                sc.register_validation_error(validation_error);
+            }
+        }
+
+        let profile_ref = profile.map(|p| match p {
+            GenericICCProfile::Luma(l) => sc.register_cacheable(chunk_container, l),
+            GenericICCProfile::Rgb(r) => sc.register_cacheable(chunk_container, r),
+            GenericICCProfile::Cmyk(c) => sc.register_cacheable(chunk_container, c),
+        });
+        let subtype = self.subtype.unwrap_or(OutputIntentSubtype::PdfX);
+
+        let chunk = &mut chunk_container.non_stream.output_intents;
+        let mut oi = chunk
+            .indirect(root_ref)
+            .start::<pdf_writer::writers::OutputIntent>();
+        if let Some(profile_ref) = profile_ref {
+            oi.dest_output_profile(profile_ref);
+        }
+
+        oi.subtype(subtype.to_pdf())
+            .output_condition_identifier(TextStr(&self.output_condition_identifier));
+
+        if let Some(output_condition) = &self.output_condition {
+            oi.output_condition(TextStr(output_condition));
+        }
+
+        if let Some(registry_name) = &self.registry_name {
+            oi.registry_name(TextStr(registry_name));
+        }
+
+        if let Some(info) = &self.info {
+            oi.info(TextStr(info));
+        }
+
+        oi.finish();
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
 pub(crate) struct ICCBasedColorSpace<const C: u8>(pub(crate) ICCProfile<C>);
 
@@ -7,6 +7,11 @@
 //! - GIF
 //! - WEBP
 //! - Custom image formats via [`CustomImage`]
+//!
+//! PNG and GIF images that use a palette are embedded with their original palette.
+//!
+//! In addition to that, [`StencilMask`] allows painting monochrome bitmaps
+//! with the current fill.
 
 use std::fmt::{Debug, Formatter};
 use std::hash::{Hash, Hasher};
@@ -14,7 +19,7 @@ use std::io::Cursor;
 use std::ops::DerefMut;
 use std::sync::Arc;
 
-use pdf_writer::{Finish, Name, Ref};
+use pdf_writer::{Finish, Name, Ref, Str};
 use png::{BitDepth, ColorType, Transformations};
 use zune_jpeg::zune_core::colorspace::ColorSpace;
 use zune_jpeg::JpegDecoder;
@@ -23,16 +28,26 @@ use crate::chunk_container::ChunkContainer;
 use crate::configure::ValidationError;
 use crate::error::KrillaError;
 use crate::graphics::color::DEVICE_GRAY;
-use crate::graphics::color::{cmyk, luma, rgb};
+use crate::graphics::color::{cmyk, luma, rgb, RenderingIntent};
 use crate::graphics::icc::{GenericICCProfile, ICCBasedColorSpace, ICCProfile};
 use crate::serialize::SerializeContext;
 use crate::stream::{deflate_encode, FilterStreamBuilder};
-use crate::util::{set_colorspace, Deferred, NameExt, SipHashable};
+use crate::util::{set_colorspace, write_colorspace, Deferred, NameExt, SipHashable};
 use crate::Data;
 
 /// The number of bits per color component.
+///
+/// With less than eight bits per component, the samples are packed into bytes
+/// starting with the most significant bit, and each row starts at a new byte.
 #[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
+#[non_exhaustive]
 pub enum BitsPerComponent {
+    /// One bit per component.
+    One,
+    /// Two bits per component.
+    Two,
+    /// Four bits per component.
+    Four,
     /// Eight bits per component.
     Eight,
     /// Sixteen bits per component.
@@ -42,10 +57,19 @@ pub enum BitsPerComponent {
 impl BitsPerComponent {
     fn as_u8(&self) -> u8 {
         match self {
+            BitsPerComponent::One => 1,
+            BitsPerComponent::Two => 2,
+            BitsPerComponent::Four => 4,
             BitsPerComponent::Eight => 8,
             BitsPerComponent::Sixteen => 16,
         }
     }
+
+    /// The number of bytes of an image with the given number of components per pixel.
+    fn data_len(&self, components: u8, (width, height): (u32, u32)) -> usize {
+        let row_bits = self.as_u8() as usize * components as usize * width as usize;
+        row_bits.div_ceil(8) * height as usize
+    }
 }
 
 /// The color space of the image.
@@ -98,6 +122,8 @@ struct SampledRepr {
     color_channel: Vec<u8>,
     alpha_channel: Option<Vec<u8>>,
     bits_per_component: BitsPerComponent,
+    /// The RGB palette, in which case the color channel consists of indices into it.
+    palette: Option<Vec<u8>>,
 }
 
 struct JpegRepr {
@@ -194,20 +220,24 @@ impl Eq for ImageRepr {}
 ///
 /// This type is cheap to hash and clone, but expensive to create.
 #[derive(Debug, Hash, Eq, PartialEq, Clone)]
-pub struct Image(Arc<ImageRepr>);
+pub struct Image(Arc<ImageRepr>, Option<RenderingIntent>);
 
 impl Image {
+    fn new(repr: ImageRepr) -> Self {
+        Self(Arc::new(repr), None)
+    }
+
     /// Create a new bitmap image from a `.png` file.
     pub fn from_png(data: Data, interpolate: bool) -> Result<Image, String> {
         let hash = data.as_ref().sip_hash();
         let metadata = png_metadata(data.as_ref())?;
 
-        Ok(Self(Arc::new(ImageRepr {
+        Ok(Self::new(ImageRepr {
             inner: Deferred::new(move || decode_png(data.as_ref())),
             metadata,
             sip: hash,
             interpolate,
-        })))
+        }))
     }
 
     /// Create a new bitmap image from a `.jpg` file.
@@ -215,12 +245,12 @@ impl Image {
         let hash = data.as_ref().sip_hash();
         let metadata = jpeg_metadata(data.as_ref())?;
 
-        Ok(Self(Arc::new(ImageRepr {
+        Ok(Self::new(ImageRepr {
             inner: Deferred::new(move || decode_jpeg(data)),
             metadata,
             sip: hash,
             interpolate,
-        })))
+        }))
     }
 
     /// Create a new bitmap image from a `.jpg` file with custom ICC profile.
@@ -236,12 +266,12 @@ impl Image {
             icc_profile.and_then(|d| get_icc_profile_type(d.as_ref(), metadata.color_space));
         metadata.icc = icc_profile;
 
-        Ok(Self(Arc::new(ImageRepr {
+        Ok(Self::new(ImageRepr {
             inner: Deferred::new(move || decode_jpeg(data)),
             metadata,
             sip: hash,
             interpolate,
-        })))
+        }))
     }
 
     /// Create a new bitmap image from a `.gif` file.
@@ -249,12 +279,12 @@ impl Image {
         let hash = data.as_ref().sip_hash();
         let metadata = gif_metadata(data.as_ref())?;
 
-        Ok(Self(Arc::new(ImageRepr {
+        Ok(Self::new(ImageRepr {
             inner: Deferred::new(move || decode_gif(data)),
             metadata,
             sip: hash,
             interpolate,
-        })))
+        }))
     }
 
     /// Create a new bitmap image from a `.webp` file.
@@ -264,12 +294,12 @@ impl Image {
         let hash = data.as_ref().sip_hash();
         let metadata = webp_metadata(data.as_ref())?;
 
-        Ok(Self(Arc::new(ImageRepr {
+        Ok(Self::new(ImageRepr {
             inner: Deferred::new(move || decode_webp(data)),
             metadata,
             sip: hash,
             interpolate,
-        })))
+        }))
     }
 
     /// Create a new image from a custom image.
@@ -288,32 +318,31 @@ impl Image {
                 .and_then(|d| get_icc_profile_type(d, image.color_space())),
         };
 
-        Ok(Self(Arc::new(ImageRepr {
+        Ok(Self::new(ImageRepr {
             inner: Deferred::new(move || {
-                let bytes_per_component = (image.bits_per_component().as_u8() / 8) as u32;
-                let color_channel_len = bytes_per_component
-                    * image.color_space().num_components() as u32
-                    * metadata.size.0
-                    * metadata.size.1;
+                let bits_per_component = image.bits_per_component();
+                let color_channel_len = bits_per_component
+                    .data_len(image.color_space().num_components(), metadata.size);
                 let color_channel = image.color_channel();
-                assert_eq!(color_channel.len(), color_channel_len as usize);
+                assert_eq!(color_channel.len(), color_channel_len);
 
-                let alpha_channel_len = bytes_per_component * metadata.size.0 * metadata.size.1;
+                let alpha_channel_len = bits_per_component.data_len(1, metadata.size);
                 let alpha_channel = image.alpha_channel();
                 if let Some(alpha_channel) = alpha_channel {
-                    assert_eq!(alpha_channel.len(), alpha_channel_len as usize);
+                    assert_eq!(alpha_channel.len(), alpha_channel_len);
                 }
 
                 Ok(Repr::Sampled(SampledRepr {
                     color_channel: deflate_encode(color_channel),
                     alpha_channel: image.alpha_channel().map(deflate_encode),
                     bits_per_component: image.bits_per_component(),
+                    palette: None,
                 }))
             }),
             metadata,
             sip: hash,
             interpolate,
-        })))
+        }))
     }
 
     /// Create a new RGB image from raw RGBA pixels.
@@ -327,7 +356,7 @@ impl Image {
             icc: None,
         };
 
-        Self(Arc::new(ImageRepr {
+        Self::new(ImageRepr {
             inner: Deferred::new(move || {
                 let (color_channel, alpha_channel, bits_per_component) =
                     handle_u8_image(&data, ColorSpace::RGBA);
@@ -336,12 +365,33 @@ impl Image {
                     color_channel,
                     alpha_channel,
                     bits_per_component,
+                    palette: None,
                 }))
             }),
             metadata,
             sip: hash,
             interpolate: false,
-        }))
+        })
+    }
+
+    /// Return a new image with the given rendering intent, which determines how
+    /// the colors of the image are mapped to the color space of the output device.
+    ///
+    /// The rendering intent is not written as the `Intent` entry of the image XObject,
+    /// but set via the `RI` entry of a graphics state around the image when drawing it.
+    /// This way, drawing the same image with different rendering intents only embeds
+    /// it once.
+    pub fn with_rendering_intent(self, rendering_intent: RenderingIntent) -> Self {
+        Self(self.0, Some(rendering_intent))
+    }
+
+    /// Split the image into the image without a rendering intent and the rendering
+    /// intent itself.
+    ///
+    /// The rendering intent is applied via the graphics state when drawing the image,
+    /// so that an image drawn with different rendering intents is only embedded once.
+    pub(crate) fn split_rendering_intent(self) -> (Self, Option<RenderingIntent>) {
+        (Self(self.0, None), self.1)
     }
 
     /// Return the size of the image.
@@ -375,6 +425,8 @@ impl Image {
                 .supports_icc(ic.metadata())
                 && self.color_space().matches_icc_profile(&ic)
             {
+                sc.register_validation_error(ValidationError::NonCmykColorSpace(sc.location));
+
                 let ref_ = match ic {
                     GenericICCProfile::Luma(l) => {
                         sc.register_cacheable(chunk_container, ICCBasedColorSpace(l))
@@ -403,9 +455,7 @@ impl Image {
 
         let cs = {
             let cs = match self.color_space() {
-                ImageColorspace::Rgb => {
-                    rgb::color_space(sc.serialize_settings().no_device_cs).into()
-                }
+                ImageColorspace::Rgb => rgb::color_space(&sc.serialize_settings()).into(),
                 ImageColorspace::Luma => {
                     luma::color_space(sc.serialize_settings().no_device_cs).into()
                 }
@@ -478,7 +528,23 @@ impl Image {
             image_x_object.width(self.size().0 as i32);
             image_x_object.height(self.size().1 as i32);
 
-            if let Some(icc_ref) = icc_ref {
+            let palette = match repr {
+                Repr::Sampled(s) => s.palette.as_ref(),
+                Repr::Jpeg(_) => None,
+            };
+
+            if let Some(palette) = palette {
+                let mut indexed = image_x_object.insert(Name(b"ColorSpace")).array();
+                indexed.item(Name(b"Indexed"));
+                if let Some(icc_ref) = icc_ref {
+                    indexed.item(icc_ref);
+                } else {
+                    write_colorspace(cs, indexed.push());
+                }
+                indexed.item((palette.len() / 3) as i32 - 1);
+                indexed.item(Str(palette));
+                indexed.finish();
+            } else if let Some(icc_ref) = icc_ref {
                 image_x_object.pair(Name(b"ColorSpace"), icc_ref);
             } else {
                 set_colorspace(cs, image_x_object.deref_mut());
@@ -513,22 +579,150 @@ impl Image {
     }
 }
 
+struct StencilMaskRepr {
+    data: Vec<u8>,
+    size: (u32, u32),
+    sip: u128,
+    interpolate: bool,
+}
+
+impl Debug for StencilMaskRepr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "StencilMaskRepr {{..}}")
+    }
+}
+
+impl Hash for StencilMaskRepr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sip.hash(state);
+    }
+}
+
+impl PartialEq for StencilMaskRepr {
+    fn eq(&self, other: &Self) -> bool {
+        self.sip == other.sip
+    }
+}
+
+impl Eq for StencilMaskRepr {}
+
+/// A monochrome bitmap that is painted with the currently active fill.
+///
+/// Stencil masks are much more compact than images with an alpha channel, which
+/// makes them a good fit for monochrome logos or scanned line art.
+///
+/// This type is cheap to hash and clone.
+#[derive(Debug, Hash, Eq, PartialEq, Clone)]
+pub struct StencilMask(Arc<StencilMaskRepr>);
+
+impl StencilMask {
+    /// Create a new stencil mask from packed samples with one bit per pixel.
+    ///
+    /// The samples are packed starting with the most significant bit, and each row
+    /// starts at a new byte. Pixels whose bit is set are painted, while all other
+    /// pixels are left untouched.
+    ///
+    /// Returns an error if the length of the data doesn't match the dimensions.
+    pub fn new(data: Vec<u8>, width: u32, height: u32, interpolate: bool) -> Result<Self, String> {
+        let size = (width, height);
+
+        if data.len() != BitsPerComponent::One.data_len(1, size) {
+            return Err("the length of the data doesn't match the dimensions".to_string());
+        }
+
+        let sip = (data.sip_hash(), size, interpolate).sip_hash();
+
+        Ok(Self(Arc::new(StencilMaskRepr {
+            data,
+            size,
+            sip,
+            interpolate,
+        })))
+    }
+
+    /// Return the size of the stencil mask.
+    pub fn size(&self) -> (u32, u32) {
+        self.0.size
+    }
+
+    pub(crate) fn serialize(
+        self,
+        sc: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+        root_ref: Ref,
+    ) {
+        if self.0.interpolate {
+            sc.register_validation_error(ValidationError::ImageInterpolation(sc.location));
+        }
+
+        let serialize_settings = sc.serialize_settings().clone();
+        let mut chunk = sc.new_chunk();
+
+        let chunk = Deferred::new(move || {
+            let data = deflate_encode(&self.0.data);
+            let stream = FilterStreamBuilder::new_from_deflated(&data).finish(&serialize_settings);
+
+            let mut image_x_object = chunk.image_xobject(root_ref, stream.encoded_data());
+            stream.write_filters(image_x_object.deref_mut().deref_mut());
+            image_x_object.width(self.size().0 as i32);
+            image_x_object.height(self.size().1 as i32);
+            image_x_object.image_mask(true);
+            image_x_object.bits_per_component(1);
+            // By default, samples with a value of zero are painted.
+            image_x_object.decode([1.0, 0.0]);
+
+            if self.0.interpolate {
+                image_x_object.interpolate(true);
+            }
+
+            image_x_object.finish();
+
+            Ok(chunk)
+        });
+
+        chunk_container.streams.images.push(chunk);
+    }
+}
+
 const PNG_TRANSFORMATIONS: Transformations = Transformations::EXPAND;
 
-fn png_metadata(data: &[u8]) -> Result<ImageMetadata, String> {
-    let cursor = Cursor::new(data);
-    let mut decoder = png::Decoder::new(cursor);
-    decoder.set_transformations(PNG_TRANSFORMATIONS);
+/// Create a PNG reader. Images are read as is, since PDF can represent their samples
+/// directly, unless they use a tRNS chunk for transparency that isn't part of a palette.
+/// In this case, the transparency is expanded into an alpha channel.
+fn png_reader(data: &[u8]) -> Result<png::Reader<Cursor<&[u8]>>, String> {
+    let mut decoder = png::Decoder::new(Cursor::new(data));
+    decoder.set_transformations(Transformations::IDENTITY);
     let reader = decoder
         .read_info()
         .map_err(|e| e.to_string().to_ascii_lowercase())?;
     let info = reader.info();
 
+    if info.trns.is_none() || info.color_type == ColorType::Indexed {
+        return Ok(reader);
+    }
+
+    // The tRNS chunk comes after the header, so we only know whether we need
+    // to expand the image after reading the info. The transformations can't be
+    // changed anymore at that point, so we need to start over.
+    let mut decoder = png::Decoder::new(Cursor::new(data));
+    decoder.set_transformations(PNG_TRANSFORMATIONS);
+
+    decoder
+        .read_info()
+        .map_err(|e| e.to_string().to_ascii_lowercase())
+}
+
+fn png_metadata(data: &[u8]) -> Result<ImageMetadata, String> {
+    let reader = png_reader(data)?;
+    let info = reader.info();
+
     let size = (info.width, info.height);
     let (color_type, bit_depth) = reader.output_color_type();
     let bits_per_component = match bit_depth {
-        // We will normalize to 8 when decoding.
-        BitDepth::One | BitDepth::Two | BitDepth::Four | BitDepth::Eight => BitsPerComponent::Eight,
+        BitDepth::One => BitsPerComponent::One,
+        BitDepth::Two => BitsPerComponent::Two,
+        BitDepth::Four => BitsPerComponent::Four,
+        BitDepth::Eight => BitsPerComponent::Eight,
         BitDepth::Sixteen => BitsPerComponent::Sixteen,
     };
 
@@ -537,9 +731,13 @@ fn png_metadata(data: &[u8]) -> Result<ImageMetadata, String> {
         ColorType::GrayscaleAlpha => (ImageColorspace::Luma, true),
         ColorType::Rgb => (ImageColorspace::Rgb, false),
         ColorType::Rgba => (ImageColorspace::Rgb, true),
-        ColorType::Indexed => {
-            return Err("image uses an indexed color space, which is unsupported".to_string())
-        }
+        // Only transparency that isn't fully opaque requires a soft mask.
+        ColorType::Indexed => (
+            ImageColorspace::Rgb,
+            info.trns
+                .as_ref()
+                .is_some_and(|t| t.iter().any(|a| *a != 255)),
+        ),
     };
     let icc = info
         .icc_profile
@@ -556,18 +754,54 @@ fn png_metadata(data: &[u8]) -> Result<ImageMetadata, String> {
 }
 
 fn decode_png(data: &[u8]) -> Result<Repr, String> {
-    let cursor = Cursor::new(data);
-    let mut decoder = png::Decoder::new(cursor);
-    decoder.set_transformations(PNG_TRANSFORMATIONS);
-    let mut reader = decoder
-        .read_info()
-        .map_err(|e| e.to_string().to_ascii_lowercase())?;
+    let mut reader = png_reader(data)?;
     let mut img_data = vec![0; reader.output_buffer_size().ok_or("image is too large")?];
     let _ = reader
         .next_frame(&mut img_data)
         .map_err(|e| e.to_string())?;
     let (color_type, bit_depth) = reader.output_color_type();
 
+    if color_type == ColorType::Indexed {
+        let info = reader.info();
+        let palette = info
+            .palette
+            .as_ref()
+            .ok_or("image is missing a palette")?
+            .to_vec();
+
+        return Ok(Repr::Sampled(
+            match info.trns.as_ref().filter(|t| t.iter().any(|a| *a != 255)) {
+                Some(trns) => {
+                    // The soft mask needs to have the same number of bits per
+                    // component, so unpack the indices.
+                    let indices = unpack_samples(&img_data, bit_depth as u8, info.width);
+                    let alphas = indices
+                        .iter()
+                        .map(|i| trns.get(*i as usize).copied().unwrap_or(255))
+                        .collect::<Vec<_>>();
+
+                    SampledRepr {
+                        color_channel: deflate_encode(&indices),
+                        alpha_channel: Some(deflate_encode(&alphas)),
+                        bits_per_component: BitsPerComponent::Eight,
+                        palette: Some(palette),
+                    }
+                }
+                None => SampledRepr {
+                    color_channel: deflate_encode(&img_data),
+                    alpha_channel: None,
+                    bits_per_component: match bit_depth {
+                        BitDepth::One => BitsPerComponent::One,
+                        BitDepth::Two => BitsPerComponent::Two,
+                        BitDepth::Four => BitsPerComponent::Four,
+                        _ => BitsPerComponent::Eight,
+                    },
+                    palette: Some(palette),
+                },
+            },
+        ));
+    }
+
     let color_space = match color_type {
         ColorType::Rgb => ColorSpace::RGB,
         ColorType::Rgba => ColorSpace::RGBA,
@@ -577,15 +811,19 @@ fn decode_png(data: &[u8]) -> Result<Repr, String> {
     };
 
     let (color_channel, alpha_channel, bits_per_component) = match bit_depth {
+        // Only grayscale images without transparency are left packed.
+        BitDepth::One => (deflate_encode(&img_data), None, BitsPerComponent::One),
+        BitDepth::Two => (deflate_encode(&img_data), None, BitsPerComponent::Two),
+        BitDepth::Four => (deflate_encode(&img_data), None, BitsPerComponent::Four),
         BitDepth::Eight => handle_u8_image(&img_data, color_space),
         BitDepth::Sixteen => handle_u16_image(&img_data, color_space),
-        _ => return Err("image has an unsupported bit-depth".to_string()),
     };
 
     Ok(Repr::Sampled(SampledRepr {
         color_channel,
         alpha_channel,
         bits_per_component,
+        palette: None,
     }))
 }
 
@@ -653,31 +891,54 @@ fn decode_jpeg(data: Data) -> Result<Repr, String> {
 
 fn decode_gif(data: Data) -> Result<Repr, String> {
     let mut decoder = gif::DecodeOptions::new();
-    decoder.set_color_output(gif::ColorOutput::RGBA);
+    decoder.set_color_output(gif::ColorOutput::Indexed);
     let mut decoder = decoder
         .read_info(data.as_ref())
         .map_err(|e| e.to_string().to_ascii_lowercase())?;
+    let global_palette = decoder.global_palette().map(|p| p.to_vec());
     let first_frame = decoder
         .read_next_frame()
         .map_err(|e| e.to_string())?
         .ok_or("GIF image seems to be empty".to_string())?;
 
-    let (color_channel, alpha_channel, bits_per_component) =
-        handle_u8_image(first_frame.buffer.as_ref(), ColorSpace::RGBA);
+    let palette = first_frame
+        .palette
+        .clone()
+        .or(global_palette)
+        .ok_or("GIF image is missing a palette".to_string())?;
+    let alpha_channel = first_frame.transparent.map(|transparent| {
+        let alphas = first_frame
+            .buffer
+            .iter()
+            .map(|i| if *i == transparent { 0 } else { 255 })
+            .collect::<Vec<_>>();
+        deflate_encode(&alphas)
+    });
 
     Ok(Repr::Sampled(SampledRepr {
-        color_channel,
+        color_channel: deflate_encode(&first_frame.buffer),
         alpha_channel,
-        bits_per_component,
+        bits_per_component: BitsPerComponent::Eight,
+        palette: Some(palette),
     }))
 }
 
 fn gif_metadata(data: &[u8]) -> Result<ImageMetadata, String> {
     let size = imagesize::blob_size(data).map_err(|e| e.to_string().to_ascii_lowercase())?;
 
+    let mut decoder = gif::DecodeOptions::new();
+    decoder.set_color_output(gif::ColorOutput::Indexed);
+    let mut decoder = decoder
+        .read_info(data)
+        .map_err(|e| e.to_string().to_ascii_lowercase())?;
+    // Only reads the frame header, not the image data.
+    let has_alpha = decoder
+        .next_frame_info()
+        .map_err(|e| e.to_string().to_ascii_lowercase())?
+        .is_some_and(|f| f.transparent.is_some());
+
     Ok(ImageMetadata {
-        // We always decode GIFs using RGBA, see `decode_gif`
-        has_alpha: true,
+        has_alpha,
         bits_per_component: BitsPerComponent::Eight,
         size: (size.width as u32, size.height as u32),
         color_space: ImageColorspace::Rgb,
@@ -725,9 +986,31 @@ fn decode_webp(data: Data) -> Result<Repr, String> {
         color_channel,
         alpha_channel,
         bits_per_component,
+        palette: None,
     }))
 }
 
+/// Unpack samples with less than eight bits into one byte per sample.
+fn unpack_samples(data: &[u8], bits: u8, width: u32) -> Vec<u8> {
+    if bits == 8 {
+        return data.to_vec();
+    }
+
+    let bits = bits as usize;
+    let width = width as usize;
+    let row_len = (width * bits).div_ceil(8);
+    let mask = (1u8 << bits) - 1;
+
+    data.chunks_exact(row_len)
+        .flat_map(|row| {
+            (0..width).map(move |i| {
+                let shift = 8 - bits - (i * bits) % 8;
+                (row[i * bits / 8] >> shift) & mask
+            })
+        })
+        .collect()
+}
+
 fn handle_u8_image(data: &[u8], cs: ColorSpace) -> (Vec<u8>, Option<Vec<u8>>, BitsPerComponent) {
     let mut alphas = if cs.has_alpha() {
         Vec::with_capacity(data.len() / cs.num_components())
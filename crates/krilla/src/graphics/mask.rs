@@ -49,17 +49,8 @@ impl Mask {
         serializer_context: &mut SerializeContext,
         chunk_container: &mut ChunkContainer,
     ) -> Option<Self> {
-        match &gradient_properties {
-            GradientProperties::RadialAxialGradient(rag) => {
-                if rag.stops.iter().all(|s| s.opacity.get() == 1.0) {
-                    return None;
-                }
-            }
-            GradientProperties::PostScriptGradient(psg) => {
-                if psg.stops.iter().all(|s| s.opacity.get() == 1.0) {
-                    return None;
-                }
-            }
+        if !gradient_properties.has_opacities() {
+            return None;
         }
 
         let shading_function = ShadingFunction::new(gradient_properties, true);
@@ -1,5 +1,7 @@
 pub mod blend;
+pub(crate) mod cie;
 pub mod color;
+pub(crate) mod devicen;
 pub mod graphic;
 pub(crate) mod graphics_state;
 pub mod icc;
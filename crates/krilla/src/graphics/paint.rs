@@ -6,7 +6,7 @@ use std::sync::Arc;
 use pdf_writer::types::{LineCapStyle, LineJoinStyle};
 
 use crate::color::SpecialColor;
-use crate::geom::Transform;
+use crate::geom::{Point, Transform};
 use crate::graphics::color::{cmyk, luma, rgb, Color};
 use crate::num::NormalizedF32;
 use crate::stream::Stream;
@@ -99,6 +99,10 @@ impl Hash for RadialGradient {
 /// A sweep gradient.
 ///
 /// Angles start from the right and go counter-clockwise with increasing values.
+///
+/// Sweep gradients are usually written using a PostScript function. If the
+/// configured validator forbids PostScript (like PDF/A does), the gradient is
+/// instead approximated by a mesh of Coons patches.
 #[derive(Debug, Clone, PartialEq)]
 pub struct SweepGradient {
     /// The x coordinate of the center.
@@ -136,6 +140,192 @@ impl Hash for SweepGradient {
     }
 }
 
+/// A mesh gradient.
+///
+/// Unlike the other gradients, a mesh gradient doesn't interpolate colors along
+/// a single direction, but within the triangles or patches of a mesh, each of which
+/// has its own colors at its corners. Only the area covered by the mesh is painted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshGradient {
+    pub(crate) mesh: Mesh,
+    pub(crate) transform: Transform,
+    pub(crate) anti_alias: bool,
+}
+
+impl MeshGradient {
+    /// Create a new mesh gradient from a mesh, a transform that should be applied
+    /// to it and whether it should be anti-aliased.
+    ///
+    /// Returns an error if the colors of the mesh are not all in the same color
+    /// space, or if a lattice has fewer than two vertices per row or fewer than
+    /// two complete rows.
+    pub fn new(mesh: Mesh, transform: Transform, anti_alias: bool) -> Result<Self, String> {
+        if let Mesh::Lattice {
+            vertices,
+            vertices_per_row,
+        } = &mesh
+        {
+            if *vertices_per_row < 2 {
+                return Err("a lattice needs at least two vertices per row".to_string());
+            }
+
+            if vertices.len() / (*vertices_per_row as usize) < 2 {
+                return Err("a lattice needs at least two complete rows".to_string());
+            }
+        }
+
+        let colors = mesh.colors();
+
+        if let Some((first, _)) = colors.first() {
+            if colors.iter().any(|(c, _)| !first.is_same_space(c)) {
+                return Err("all colors of a mesh need to be in the same color space".to_string());
+            }
+        }
+
+        Ok(Self {
+            mesh,
+            transform,
+            anti_alias,
+        })
+    }
+}
+
+impl Eq for MeshGradient {}
+
+impl Hash for MeshGradient {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.mesh.hash(state);
+        self.transform.hash(state);
+        self.anti_alias.hash(state);
+    }
+}
+
+/// The mesh of a mesh gradient.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Mesh {
+    /// A free-form mesh of triangles, each of which is shaded independently.
+    Triangles(Vec<[MeshVertex; 3]>),
+    /// A lattice of vertices, arranged in rows of `vertices_per_row` vertices each.
+    /// Each group of four adjacent vertices forms two triangles.
+    ///
+    /// If the number of vertices is not a multiple of `vertices_per_row`, the
+    /// incomplete last row is ignored.
+    Lattice {
+        /// The vertices of the lattice, row by row. Must contain at least two
+        /// complete rows.
+        vertices: Vec<MeshVertex>,
+        /// The number of vertices in each row. Must be at least 2.
+        vertices_per_row: u32,
+    },
+    /// Coons patches, whose shape is defined by four cubic Bézier curves.
+    CoonsPatches(Vec<CoonsPatch>),
+    /// Tensor-product patches, whose shape is defined by a grid of 4x4 control points.
+    TensorPatches(Vec<TensorPatch>),
+}
+
+/// A vertex of a triangle mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshVertex {
+    /// The position of the vertex.
+    pub point: Point,
+    /// The color of the vertex.
+    pub color: Color,
+    /// The opacity of the vertex.
+    pub opacity: NormalizedF32,
+}
+
+impl Eq for MeshVertex {}
+
+impl Hash for MeshVertex {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_point(self.point, state);
+        self.color.hash(state);
+        self.opacity.hash(state);
+    }
+}
+
+/// A Coons patch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoonsPatch {
+    /// The points of the four cubic Bézier curves that form the boundary of the
+    /// patch. Starting at a corner, each curve consists of its start point followed
+    /// by its two control points, and ends at the start point of the next curve.
+    pub points: [Point; 12],
+    /// The colors of the four corners `points[0]`, `points[3]`, `points[6]`
+    /// and `points[9]`, in that order.
+    pub colors: [Color; 4],
+    /// The opacities of the four corners, in the same order as the colors.
+    pub opacities: [NormalizedF32; 4],
+}
+
+impl Eq for CoonsPatch {}
+
+impl Hash for CoonsPatch {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for point in self.points {
+            hash_point(point, state);
+        }
+
+        self.colors.hash(state);
+        self.opacities.hash(state);
+    }
+}
+
+/// A tensor-product patch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TensorPatch {
+    /// The 4x4 control points of the patch, row by row. The corners of the patch
+    /// are `points[0][0]`, `points[0][3]`, `points[3][3]` and `points[3][0]`.
+    pub points: [[Point; 4]; 4],
+    /// The colors of the four corners `points[0][0]`, `points[0][3]`, `points[3][3]`
+    /// and `points[3][0]`, in that order.
+    pub colors: [Color; 4],
+    /// The opacities of the four corners, in the same order as the colors.
+    pub opacities: [NormalizedF32; 4],
+}
+
+impl Eq for TensorPatch {}
+
+impl Hash for TensorPatch {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for point in self.points.iter().flatten() {
+            hash_point(*point, state);
+        }
+
+        self.colors.hash(state);
+        self.opacities.hash(state);
+    }
+}
+
+fn hash_point<H: Hasher>(point: Point, state: &mut H) {
+    point.x.to_bits().hash(state);
+    point.y.to_bits().hash(state);
+}
+
+impl Mesh {
+    /// The colors of all vertices or corners of the mesh, alongside their opacities.
+    pub(crate) fn colors(&self) -> Vec<(&Color, NormalizedF32)> {
+        match self {
+            Mesh::Triangles(triangles) => triangles
+                .iter()
+                .flatten()
+                .map(|v| (&v.color, v.opacity))
+                .collect(),
+            Mesh::Lattice { vertices, .. } => {
+                vertices.iter().map(|v| (&v.color, v.opacity)).collect()
+            }
+            Mesh::CoonsPatches(patches) => patches
+                .iter()
+                .flat_map(|p| p.colors.iter().zip(p.opacities))
+                .collect(),
+            Mesh::TensorPatches(patches) => patches
+                .iter()
+                .flat_map(|p| p.colors.iter().zip(p.opacities))
+                .collect(),
+        }
+    }
+}
+
 /// A pattern.
 ///
 /// IMPORTANT: Note that you must only use a mask in the document that you created it with!
@@ -163,13 +353,47 @@ impl Hash for Pattern {
     }
 }
 
+/// An uncolored pattern.
+///
+/// In contrast to a normal [`Pattern`], the stream of an uncolored pattern only
+/// describes the shape of the pattern cell, which is painted with the color given
+/// here. Since the color is not part of the pattern, the same pattern object is
+/// reused for each color it is painted with.
+///
+/// IMPORTANT: Note that you must only use a pattern in the document that you created it with!
+/// If you use it in a different document, you will end up with an invalid PDF file.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct UncoloredPattern {
+    pub(crate) pattern: Pattern,
+    pub(crate) color: Color,
+}
+
+impl UncoloredPattern {
+    /// Create a new uncolored pattern that is painted with the given color.
+    ///
+    /// The stream of the pattern must be drawn with
+    /// [`StreamBuilder::uncolored_surface`], which leaves out all colors. Returns
+    /// `None` if it was drawn with a normal surface instead.
+    ///
+    /// [`StreamBuilder::uncolored_surface`]: crate::stream::StreamBuilder::uncolored_surface
+    pub fn new(pattern: Pattern, color: Color) -> Option<Self> {
+        if !pattern.stream.uncolored {
+            return None;
+        }
+
+        Some(Self { pattern, color })
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub(crate) enum InnerPaint {
     Color(Color),
     LinearGradient(LinearGradient),
     RadialGradient(RadialGradient),
     SweepGradient(SweepGradient),
+    MeshGradient(MeshGradient),
     Pattern(Arc<Pattern>),
+    UncoloredPattern(Arc<UncoloredPattern>),
 }
 
 /// A paint.
@@ -185,7 +409,9 @@ impl Paint {
         match &self.0 {
             InnerPaint::Color(c) => match c {
                 Color::Regular(c) => c.as_rgb(),
+                Color::ICCBased(c) => c.to_regular().as_rgb(),
                 Color::Special(SpecialColor::Separation(c)) => c.space.fallback.as_rgb(),
+                Color::Special(SpecialColor::DeviceN(c)) => c.to_regular().as_rgb(),
             },
             _ => None,
         }
@@ -204,6 +430,18 @@ impl From<luma::Color> for Paint {
     }
 }
 
+impl From<rgb::ICCColor> for Paint {
+    fn from(value: rgb::ICCColor) -> Self {
+        Paint(InnerPaint::Color(value.into()))
+    }
+}
+
+impl From<luma::ICCColor> for Paint {
+    fn from(value: luma::ICCColor) -> Self {
+        Paint(InnerPaint::Color(value.into()))
+    }
+}
+
 impl From<cmyk::Color> for Paint {
     fn from(value: cmyk::Color) -> Self {
         Paint(InnerPaint::Color(value.into()))
@@ -234,12 +472,24 @@ impl From<SweepGradient> for Paint {
     }
 }
 
+impl From<MeshGradient> for Paint {
+    fn from(value: MeshGradient) -> Self {
+        Paint(InnerPaint::MeshGradient(value))
+    }
+}
+
 impl From<Pattern> for Paint {
     fn from(value: Pattern) -> Self {
         Paint(InnerPaint::Pattern(Arc::new(value)))
     }
 }
 
+impl From<UncoloredPattern> for Paint {
+    fn from(value: UncoloredPattern) -> Self {
+        Paint(InnerPaint::UncoloredPattern(Arc::new(value)))
+    }
+}
+
 /// A spread method.
 #[derive(Debug, Hash, Eq, PartialEq, Copy, Clone, Default)]
 pub enum SpreadMethod {
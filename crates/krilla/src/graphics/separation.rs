@@ -3,9 +3,9 @@ use pdf_writer::{Finish, Name, Ref, Writer};
 
 use crate::chunk_container::ChunkContainer;
 use crate::color::separation::SeparationSpace;
-use crate::color::{DEVICE_CMYK, DEVICE_GRAY, DEVICE_RGB};
-use crate::resource::{self, Resource, Resourceable};
-use crate::serialize::{Cacheable, MaybeDeviceColorSpace, SerializeContext};
+use crate::resource::{self, Resourceable};
+use crate::serialize::{Cacheable, SerializeContext};
+use crate::util::write_colorspace;
 
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
 pub(crate) struct SeparationColorSpace {
@@ -29,18 +29,16 @@ impl Cacheable for SeparationColorSpace {
         let fallback_cs = self.space.fallback.color_space(sc);
         let fallback_cs_resource = sc.register_colorspace(chunk_container, fallback_cs.into());
 
-        // Get fallback color components for tint function
-        let fallback_color = crate::color::Color::from(self.space.fallback).to_pdf_color();
-        let num_components = fallback_color.len();
+        // Get fallback color components and their ranges for tint function
+        let fallback_color = crate::color::Color::from(self.space.fallback);
+        let fallback_range = fallback_color.pdf_range();
+        let fallback_color = fallback_color.to_pdf_color();
 
-        // Determine C0 based on whether the fallback is subtractive or additive
+        // Determine C0 based on the fallback color space
         // - Additive (RGB, Luma): tint 0.0 = white = [1.0, 1.0, 1.0]
         // - Subtractive (CMYK): tint 0.0 = white = [0.0, 0.0, 0.0, 0.0]
-        let c0_value = if self.space.fallback.is_subtractive() {
-            0.0
-        } else {
-            1.0
-        };
+        // - Lab: tint 0.0 = white = [100.0, 0.0, 0.0]
+        let c0 = self.space.fallback.white_pdf_color();
 
         if let Err(validation_error) = sc.validation_store().validate_separation(&self.space) {
             sc.register_validation_error(validation_error);
@@ -56,23 +54,15 @@ impl Cacheable for SeparationColorSpace {
         array.item(self.space.colorant.to_pdf());
 
         // Fallback color space - write as name for device CS, or ref for others
-        match fallback_cs_resource {
-            MaybeDeviceColorSpace::DeviceRgb => array.item(Name(DEVICE_RGB.as_bytes())),
-            MaybeDeviceColorSpace::DeviceGray => array.item(Name(DEVICE_GRAY.as_bytes())),
-            MaybeDeviceColorSpace::DeviceCMYK => array.item(Name(DEVICE_CMYK.as_bytes())),
-            MaybeDeviceColorSpace::ColorSpace(cs) => {
-                // Use get_ref() to extract the underlying Ref
-                array.item(cs.get_ref())
-            }
-        };
+        write_colorspace(fallback_cs_resource, array.push());
 
         // Write Type 2 (Exponential) function for tint transform
         // Maps tint [0.0-1.0] from white (no ink) to fallback color (full ink)
         ExponentialFunction::start(array.push())
             .domain([0.0, 1.0])
-            .range([0.0, 1.0].repeat(num_components))
+            .range(fallback_range)
             // C0: white/no ink (tint = 0.0) - value depends on color space
-            .c0(vec![c0_value; num_components])
+            .c0(c0)
             // C1: fallback color (tint = 1.0)
             .c1(fallback_color)
             .n(1.0);
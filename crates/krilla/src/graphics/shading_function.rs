@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use bumpalo::Bump;
 use pdf_writer::types::{FunctionShadingType, PostScriptOp};
-use pdf_writer::{Chunk, Finish, Ref};
+use pdf_writer::{Chunk, Finish, Name, Ref};
 use tiny_skia_path::Point;
 
 use crate::chunk_container::ChunkContainer;
@@ -12,7 +12,7 @@ use crate::configure::ValidationError;
 use crate::geom::{Rect, Transform};
 use crate::graphics::color::luma;
 use crate::graphics::color::{Color, ColorSpace};
-use crate::graphics::paint::{LinearGradient, RadialGradient, SweepGradient};
+use crate::graphics::paint::{LinearGradient, Mesh, MeshGradient, RadialGradient, SweepGradient};
 use crate::graphics::paint::{SpreadMethod, Stop};
 use crate::num::NormalizedF32;
 use crate::resource;
@@ -78,10 +78,17 @@ impl Hash for PostScriptGradient {
     }
 }
 
+#[derive(Debug, Hash, Eq, PartialEq, Clone)]
+pub(crate) struct MeshShading {
+    pub(crate) mesh: Mesh,
+    pub(crate) anti_alias: bool,
+}
+
 #[derive(Debug, Hash, Eq, PartialEq, Clone)]
 pub(crate) enum GradientProperties {
     RadialAxialGradient(RadialAxialGradient),
     PostScriptGradient(PostScriptGradient),
+    MeshShading(MeshShading),
 }
 
 impl GradientProperties {
@@ -98,10 +105,34 @@ impl GradientProperties {
                     return Some((&psg.stops[0].color, psg.stops[0].opacity));
                 }
             }
+            GradientProperties::MeshShading(ms) => {
+                let colors = ms.mesh.colors();
+
+                if let Some(first) = colors.first() {
+                    if colors.iter().all(|c| c == first) {
+                        return Some(*first);
+                    }
+                }
+            }
         }
 
         None
     }
+
+    /// Whether any of the colors of the gradient is not fully opaque.
+    pub(crate) fn has_opacities(&self) -> bool {
+        match self {
+            GradientProperties::RadialAxialGradient(rag) => {
+                rag.stops.iter().any(|s| s.opacity.get() != 1.0)
+            }
+            GradientProperties::PostScriptGradient(psg) => {
+                psg.stops.iter().any(|s| s.opacity.get() != 1.0)
+            }
+            GradientProperties::MeshShading(ms) => {
+                ms.mesh.colors().iter().any(|(_, o)| o.get() != 1.0)
+            }
+        }
+    }
 }
 
 pub(crate) trait GradientPropertiesExt {
@@ -207,6 +238,18 @@ impl GradientPropertiesExt for RadialGradient {
     }
 }
 
+impl GradientPropertiesExt for MeshGradient {
+    fn gradient_properties(self, _: Rect) -> (GradientProperties, Transform) {
+        (
+            GradientProperties::MeshShading(MeshShading {
+                mesh: self.mesh,
+                anti_alias: self.anti_alias,
+            }),
+            self.transform,
+        )
+    }
+}
+
 #[derive(Debug, Hash, Eq, PartialEq)]
 struct Repr {
     pub(crate) properties: GradientProperties,
@@ -243,6 +286,7 @@ impl Cacheable for ShadingFunction {
                 serialize_axial_radial_shading(
                     sc,
                     chunk,
+                    &mut stream_chunk,
                     root_ref,
                     rag,
                     self.0.use_opacities,
@@ -250,17 +294,63 @@ impl Cacheable for ShadingFunction {
                 )
             }
             GradientProperties::PostScriptGradient(psg) => {
-                sc.register_validation_error(ValidationError::ContainsPostScript(sc.location));
                 let shading_cs =
                     shading_color_space(sc, psg.stops[0].color.clone(), self.0.use_opacities);
                 let registered_cs = sc.register_colorspace(chunk_container, shading_cs);
-                let chunk = &mut chunk_container.non_stream.shading_functions;
-                serialize_postscript_shading(
+
+                let sampled = sc.serialize_settings().gradient_samples.is_some();
+
+                // Sweep gradients can be approximated closely with Coons patches, so if
+                // PostScript is not allowed, we use those instead.
+                if !sampled
+                    && psg.gradient_type == GradientType::Sweep
+                    && sc
+                        .serialize_settings()
+                        .validators()
+                        .prohibits(&ValidationError::ContainsPostScript(None))
+                        .is_some()
+                {
+                    serialize_sweep_coons_shading(
+                        sc,
+                        &mut stream_chunk,
+                        root_ref,
+                        psg,
+                        self.0.use_opacities,
+                        registered_cs,
+                    )
+                } else {
+                    if !sampled {
+                        sc.register_validation_error(ValidationError::ContainsPostScript(
+                            sc.location,
+                        ));
+                    }
+
+                    let chunk = &mut chunk_container.non_stream.shading_functions;
+                    serialize_postscript_shading(
+                        sc,
+                        chunk,
+                        &mut stream_chunk,
+                        root_ref,
+                        psg,
+                        self.0.use_opacities,
+                        registered_cs,
+                    )
+                }
+            }
+            GradientProperties::MeshShading(ms) => {
+                let color = ms
+                    .mesh
+                    .colors()
+                    .first()
+                    .map(|(c, _)| (*c).clone())
+                    .unwrap_or(luma::Color::black().into());
+                let shading_cs = shading_color_space(sc, color, self.0.use_opacities);
+                let registered_cs = sc.register_colorspace(chunk_container, shading_cs);
+                serialize_mesh_shading(
                     sc,
-                    chunk,
                     &mut stream_chunk,
                     root_ref,
-                    psg,
+                    ms,
                     self.0.use_opacities,
                     registered_cs,
                 )
@@ -313,16 +403,261 @@ fn serialize_postscript_shading(
     shading.finish();
 }
 
+fn serialize_mesh_shading(
+    sc: &mut SerializeContext,
+    stream_chunk: &mut Chunk,
+    root_ref: Ref,
+    mesh_shading: &MeshShading,
+    use_opacities: bool,
+    cs: MaybeDeviceColorSpace,
+) {
+    let component_range = if use_opacities {
+        vec![0.0, 1.0]
+    } else {
+        mesh_shading
+            .mesh
+            .colors()
+            .first()
+            .map(|(c, _)| c.pdf_range())
+            .unwrap_or_else(|| vec![0.0, 1.0])
+    };
+
+    let points: Vec<crate::geom::Point> = match &mesh_shading.mesh {
+        Mesh::Triangles(triangles) => triangles.iter().flatten().map(|v| v.point).collect(),
+        Mesh::Lattice { vertices, .. } => vertices.iter().map(|v| v.point).collect(),
+        Mesh::CoonsPatches(patches) => patches.iter().flat_map(|p| p.points).collect(),
+        Mesh::TensorPatches(patches) => patches
+            .iter()
+            .flat_map(|p| p.points.into_iter().flatten())
+            .collect(),
+    };
+
+    let mut encoder = MeshEncoder::new(&points, component_range, use_opacities);
+
+    let (shading_type, vertices_per_row) = match &mesh_shading.mesh {
+        Mesh::Triangles(triangles) => {
+            for triangle in triangles {
+                for vertex in triangle {
+                    // Each triangle is independent of the previous one.
+                    encoder.flag();
+                    encoder.point(vertex.point);
+                    encoder.color(&vertex.color, vertex.opacity);
+                }
+            }
+
+            (4, None)
+        }
+        Mesh::Lattice {
+            vertices,
+            vertices_per_row,
+        } => {
+            let per_row = *vertices_per_row as usize;
+
+            for vertex in &vertices[..vertices.len() / per_row * per_row] {
+                encoder.point(vertex.point);
+                encoder.color(&vertex.color, vertex.opacity);
+            }
+
+            (5, Some(per_row as i32))
+        }
+        Mesh::CoonsPatches(patches) => {
+            for patch in patches {
+                encoder.flag();
+
+                for point in patch.points {
+                    encoder.point(point);
+                }
+
+                for (color, opacity) in patch.colors.iter().zip(patch.opacities) {
+                    encoder.color(color, opacity);
+                }
+            }
+
+            (6, None)
+        }
+        Mesh::TensorPatches(patches) => {
+            // The order in which the PDF specification expects the control points.
+            const ORDER: [(usize, usize); 16] = [
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (0, 3),
+                (1, 3),
+                (2, 3),
+                (3, 3),
+                (3, 2),
+                (3, 1),
+                (3, 0),
+                (2, 0),
+                (1, 0),
+                (1, 1),
+                (1, 2),
+                (2, 2),
+                (2, 1),
+            ];
+
+            for patch in patches {
+                encoder.flag();
+
+                for (row, col) in ORDER {
+                    encoder.point(patch.points[row][col]);
+                }
+
+                for (color, opacity) in patch.colors.iter().zip(patch.opacities) {
+                    encoder.color(color, opacity);
+                }
+            }
+
+            (7, None)
+        }
+    };
+
+    write_mesh_shading(
+        sc,
+        stream_chunk,
+        root_ref,
+        encoder,
+        shading_type,
+        vertices_per_row,
+        mesh_shading.anti_alias,
+        cs,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_mesh_shading(
+    sc: &mut SerializeContext,
+    stream_chunk: &mut Chunk,
+    root_ref: Ref,
+    encoder: MeshEncoder,
+    shading_type: i32,
+    vertices_per_row: Option<i32>,
+    anti_alias: bool,
+    cs: MaybeDeviceColorSpace,
+) {
+    let decode = encoder.decode();
+    let data = encoder.data;
+
+    let encoded = FilterStreamBuilder::new_from_binary_data(&data).finish(&sc.serialize_settings());
+    let mut shading = stream_chunk.stream(root_ref, encoded.encoded_data());
+    encoded.write_filters(shading.deref_mut());
+
+    shading.pair(Name(b"ShadingType"), shading_type);
+    set_colorspace(cs, shading.deref_mut());
+    shading.pair(Name(b"AntiAlias"), anti_alias);
+    shading.pair(Name(b"BitsPerCoordinate"), 32);
+    shading.pair(Name(b"BitsPerComponent"), 16);
+
+    match vertices_per_row {
+        Some(vertices_per_row) => {
+            shading.pair(Name(b"VerticesPerRow"), vertices_per_row);
+        }
+        None => {
+            shading.pair(Name(b"BitsPerFlag"), 8);
+        }
+    }
+
+    shading.insert(Name(b"Decode")).array().items(decode);
+    shading.finish();
+}
+
+/// Encodes the vertices of a mesh shading.
+///
+/// Coordinates are encoded with 32 bits and color components with 16 bits, so
+/// that all values are byte-aligned.
+struct MeshEncoder {
+    data: Vec<u8>,
+    x_range: (f32, f32),
+    y_range: (f32, f32),
+    component_range: Vec<f32>,
+    use_opacities: bool,
+}
+
+impl MeshEncoder {
+    fn new(points: &[crate::geom::Point], component_range: Vec<f32>, use_opacities: bool) -> Self {
+        let range = |values: &mut dyn Iterator<Item = f32>| {
+            let (min, max) = values.fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), v| {
+                (min.min(v), max.max(v))
+            });
+
+            if min > max {
+                (0.0, 1.0)
+            } else if min == max {
+                (min, min + 1.0)
+            } else {
+                (min, max)
+            }
+        };
+
+        Self {
+            data: vec![],
+            x_range: range(&mut points.iter().map(|p| p.x)),
+            y_range: range(&mut points.iter().map(|p| p.y)),
+            component_range,
+            use_opacities,
+        }
+    }
+
+    fn flag(&mut self) {
+        // We always start a new triangle or patch, so the flag is always 0.
+        self.data.push(0);
+    }
+
+    fn point(&mut self, point: crate::geom::Point) {
+        for (value, (min, max)) in [(point.x, self.x_range), (point.y, self.y_range)] {
+            let normalized = (value as f64 - min as f64) / (max as f64 - min as f64);
+            let encoded = (normalized.clamp(0.0, 1.0) * u32::MAX as f64).round() as u32;
+            self.data.extend(encoded.to_be_bytes());
+        }
+    }
+
+    fn color(&mut self, color: &Color, opacity: NormalizedF32) {
+        let components = if self.use_opacities {
+            vec![opacity.get()]
+        } else {
+            color.to_pdf_color()
+        };
+
+        self.components(components);
+    }
+
+    fn components(&mut self, components: Vec<f32>) {
+        encode_components(&mut self.data, components, &self.component_range);
+    }
+
+    fn decode(&self) -> Vec<f32> {
+        let mut decode = vec![
+            self.x_range.0,
+            self.x_range.1,
+            self.y_range.0,
+            self.y_range.1,
+        ];
+
+        decode.extend(self.component_range.iter().copied());
+
+        decode
+    }
+}
+
 fn serialize_axial_radial_shading(
     sc: &mut SerializeContext,
     chunk: &mut Chunk,
+    stream_chunk: &mut Chunk,
     root_ref: Ref,
     radial_axial_gradient: &RadialAxialGradient,
     use_opacities: bool,
     cs: MaybeDeviceColorSpace,
 ) {
-    let function_ref =
-        select_axial_radial_function(radial_axial_gradient, chunk, sc, use_opacities);
+    let function_ref = match sc.serialize_settings().gradient_samples {
+        Some(samples) => serialize_sampled_axial_radial(
+            radial_axial_gradient,
+            stream_chunk,
+            sc,
+            samples,
+            use_opacities,
+        ),
+        None => select_axial_radial_function(radial_axial_gradient, chunk, sc, use_opacities),
+    };
     let mut shading = chunk.function_shading(root_ref);
     if radial_axial_gradient.shading_type == FunctionShadingType::Radial {
         shading.shading_type(FunctionShadingType::Radial);
@@ -366,10 +701,13 @@ fn select_axial_radial_function(
     }
 
     if stops.len() == 2 {
+        let range = stop_range(&stops, use_opacities);
+
         if use_opacities {
             serialize_exponential(
                 vec![stops[0].opacity.get()],
                 vec![stops[1].opacity.get()],
+                range,
                 chunk,
                 sc,
             )
@@ -385,6 +723,7 @@ fn select_axial_radial_function(
                     .to_pdf_color()
                     .into_iter()
                     .collect::<Vec<_>>(),
+                range,
                 chunk,
                 sc,
             )
@@ -403,7 +742,9 @@ fn select_postscript_function(
 ) -> Ref {
     debug_assert!(properties.stops.len() > 1);
 
-    if properties.gradient_type == GradientType::Linear {
+    if let Some(samples) = sc.serialize_settings().gradient_samples {
+        serialize_sampled_postscript(properties, chunk, sc, samples, use_opacities)
+    } else if properties.gradient_type == GradientType::Linear {
         serialize_linear_postscript(properties, chunk, sc, use_opacities)
     } else if properties.gradient_type == GradientType::Sweep {
         serialize_sweep_postscript(properties, chunk, sc, bump, use_opacities)
@@ -497,15 +838,437 @@ fn serialize_sweep_postscript(
         properties.domain.bottom(),
     ]);
 
+    postscript_function.range(stop_range(&properties.stops, use_opacities));
+
+    root_ref
+}
+
+/// Serialize the function of an axial or radial gradient as a sampled function
+/// with `samples` samples.
+fn serialize_sampled_axial_radial(
+    properties: &RadialAxialGradient,
+    chunk: &mut Chunk,
+    sc: &mut SerializeContext,
+    samples: u32,
+    use_opacities: bool,
+) -> Ref {
+    let samples = samples.max(2);
+    let stops = &properties.stops;
+
+    let data = (0..samples).map(|i| {
+        let t = i as f32 / (samples - 1) as f32;
+        interpolate_stops(stops, t, t, use_opacities)
+    });
+
+    serialize_sampled_function(
+        chunk,
+        sc,
+        &[samples],
+        &[0.0, 1.0],
+        &stop_range(stops, use_opacities),
+        data,
+    )
+}
+
+/// Serialize the function of a linear gradient with a spread method or a sweep
+/// gradient as a sampled function over its domain, instead of a PostScript function.
+///
+/// Linear gradients only vary along the x axis, so they are only sampled `samples`
+/// times along that axis, while sweep gradients are sampled on a grid of
+/// `samples` x `samples` points.
+fn serialize_sampled_postscript(
+    properties: &PostScriptGradient,
+    chunk: &mut Chunk,
+    sc: &mut SerializeContext,
+    samples: u32,
+    use_opacities: bool,
+) -> Ref {
+    let samples = samples.max(2);
+    let domain = properties.domain;
+    let rows = match properties.gradient_type {
+        GradientType::Linear => 2,
+        GradientType::Sweep => samples,
+    };
+
+    let lerp = |min: f32, max: f32, i: u32, n: u32| min + (max - min) * i as f32 / (n - 1) as f32;
+
+    let data = (0..rows).flat_map(|row| {
+        let y = lerp(domain.top(), domain.bottom(), row, rows);
+
+        (0..samples).map(move |col| {
+            let x = lerp(domain.left(), domain.right(), col, samples);
+
+            let value = match properties.gradient_type {
+                GradientType::Linear => x,
+                GradientType::Sweep => (y - properties.cy)
+                    .atan2(x - properties.cx)
+                    .to_degrees()
+                    .rem_euclid(360.0),
+            };
+
+            let t = spread_position(
+                value,
+                properties.min,
+                properties.max,
+                properties.spread_method,
+            );
+
+            interpolate_stops(&properties.stops, t, t, use_opacities)
+        })
+    });
+
+    serialize_sampled_function(
+        chunk,
+        sc,
+        &[samples, rows],
+        &[domain.left(), domain.right(), domain.top(), domain.bottom()],
+        &stop_range(&properties.stops, use_opacities),
+        data,
+    )
+}
+
+/// Map a value to its position between `min` and `max`, normalized to the
+/// range 0 to 1 according to the spread method.
+fn spread_position(value: f32, min: f32, max: f32, spread_method: SpreadMethod) -> f32 {
+    let length = max - min;
+
+    if length <= 0.0 {
+        return if value < min { 0.0 } else { 1.0 };
+    }
+
+    let n = (value - min) / length;
+
+    match spread_method {
+        SpreadMethod::Pad => n.clamp(0.0, 1.0),
+        SpreadMethod::Repeat => n - n.floor(),
+        SpreadMethod::Reflect => {
+            let i = n.floor();
+
+            if i.rem_euclid(2.0) > 0.0 {
+                1.0 - (n - i)
+            } else {
+                n - i
+            }
+        }
+    }
+}
+
+/// The range of the components of the color space of the stops, or of the
+/// opacities if `use_opacities` is set.
+fn stop_range(stops: &[Stop], use_opacities: bool) -> Vec<f32> {
     if use_opacities {
-        postscript_function.range([0.0, 1.0]);
+        vec![0.0, 1.0]
     } else {
-        postscript_function.range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0]);
+        stops[0].color.pdf_range()
+    }
+}
+
+/// Encode color components with 16 bits each, normalized to the given range.
+fn encode_components(data: &mut Vec<u8>, mut components: Vec<f32>, range: &[f32]) {
+    // All colors should be in the same color space, but make sure that we never
+    // write data that doesn't match the color space of the shading.
+    components.resize(range.len() / 2, 0.0);
+
+    for (component, range) in components.into_iter().zip(range.chunks_exact(2)) {
+        let normalized = (component - range[0]) / (range[1] - range[0]);
+        let encoded = (normalized.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16;
+        data.extend(encoded.to_be_bytes());
     }
+}
+
+/// Serialize a sampled (type 0) function with 16 bits per sample. The samples
+/// must be ordered such that the first dimension varies fastest.
+pub(crate) fn serialize_sampled_function(
+    chunk: &mut Chunk,
+    sc: &mut SerializeContext,
+    size: &[u32],
+    domain: &[f32],
+    range: &[f32],
+    samples: impl Iterator<Item = Vec<f32>>,
+) -> Ref {
+    let root_ref = sc.new_ref();
+
+    let mut data = vec![];
+
+    for components in samples {
+        encode_components(&mut data, components, range);
+    }
+
+    let encoded = FilterStreamBuilder::new_from_binary_data(&data).finish(&sc.serialize_settings());
+    let mut function = chunk.stream(root_ref, encoded.encoded_data());
+    encoded.write_filters(function.deref_mut());
+
+    function.pair(Name(b"FunctionType"), 0);
+    function
+        .insert(Name(b"Domain"))
+        .array()
+        .items(domain.iter().copied());
+    function
+        .insert(Name(b"Range"))
+        .array()
+        .items(range.iter().copied());
+    function
+        .insert(Name(b"Size"))
+        .array()
+        .items(size.iter().map(|s| *s as i32));
+    function.pair(Name(b"BitsPerSample"), 16);
+    function.finish();
 
     root_ref
 }
 
+/// The maximum angle in degrees that a single patch of a sweep gradient spans.
+const MAX_SWEEP_PATCH_ANGLE: f32 = 15.0;
+
+/// The maximum number of periods of a repeated or reflected sweep gradient that
+/// get their own breakpoints. Any further periods are only approximated by the
+/// patches, since a tiny angle range would otherwise produce an unbounded number
+/// of patches.
+const MAX_SWEEP_PERIODS: i64 = 360;
+
+/// Serialize a sweep gradient as a Coons patch mesh, which, unlike the PostScript
+/// version, is allowed in all export formats.
+///
+/// The full circle around the center is divided into wedges such that the color
+/// changes linearly within each of them. Each wedge is then written as a Coons patch
+/// with one of its edges collapsed into the center.
+fn serialize_sweep_coons_shading(
+    sc: &mut SerializeContext,
+    stream_chunk: &mut Chunk,
+    root_ref: Ref,
+    properties: &PostScriptGradient,
+    use_opacities: bool,
+    cs: MaybeDeviceColorSpace,
+) {
+    let center = crate::geom::Point::from_xy(properties.cx, properties.cy);
+    let domain = properties.domain;
+
+    // Make sure the patches cover the whole domain.
+    let radius = [
+        (domain.left(), domain.top()),
+        (domain.right(), domain.top()),
+        (domain.left(), domain.bottom()),
+        (domain.right(), domain.bottom()),
+    ]
+    .into_iter()
+    .map(|(x, y)| (x - center.x).hypot(y - center.y))
+    .fold(1.0, f32::max)
+        * 1.01;
+
+    let component_range = stop_range(&properties.stops, use_opacities);
+
+    let boundary = [
+        crate::geom::Point::from_xy(center.x - radius, center.y - radius),
+        crate::geom::Point::from_xy(center.x + radius, center.y + radius),
+    ];
+    let mut encoder = MeshEncoder::new(&boundary, component_range, use_opacities);
+
+    let angles = sweep_breakpoints(properties);
+
+    for window in angles.windows(2) {
+        let (a0, a1) = (window[0], window[1]);
+        let (c0, c1) = sweep_segment_colors(properties, a0, a1, use_opacities);
+
+        let arc_point = |angle: f32| {
+            let (sin, cos) = angle.to_radians().sin_cos();
+            crate::geom::Point::from_xy(center.x + radius * cos, center.y + radius * sin)
+        };
+        let on_line = |p: crate::geom::Point, t: f32| {
+            crate::geom::Point::from_xy(
+                center.x + (p.x - center.x) * t,
+                center.y + (p.y - center.y) * t,
+            )
+        };
+
+        let p0 = arc_point(a0);
+        let p1 = arc_point(a1);
+
+        // Control point distance for approximating the arc with a cubic Bézier curve.
+        let k = 4.0 / 3.0 * ((a0 - a1).to_radians() / 4.0).tan() * radius;
+        let (sin1, cos1) = a1.to_radians().sin_cos();
+        let (sin0, cos0) = a0.to_radians().sin_cos();
+
+        encoder.flag();
+
+        for point in [
+            // The collapsed edge in the center.
+            center,
+            center,
+            center,
+            center,
+            // The edge from the center to the end of the arc.
+            on_line(p1, 1.0 / 3.0),
+            on_line(p1, 2.0 / 3.0),
+            p1,
+            // The arc, going back to its start.
+            crate::geom::Point::from_xy(p1.x - k * sin1, p1.y + k * cos1),
+            crate::geom::Point::from_xy(p0.x + k * sin0, p0.y - k * cos0),
+            p0,
+            // The edge from the start of the arc back to the center.
+            on_line(p0, 2.0 / 3.0),
+            on_line(p0, 1.0 / 3.0),
+        ] {
+            encoder.point(point);
+        }
+
+        for components in [c0.clone(), c1.clone(), c1, c0] {
+            encoder.components(components);
+        }
+    }
+
+    write_mesh_shading(
+        sc,
+        stream_chunk,
+        root_ref,
+        encoder,
+        6,
+        None,
+        properties.anti_alias,
+        cs,
+    );
+}
+
+/// The angles at which the color of a sweep gradient doesn't change linearly,
+/// including the start and end of the full circle.
+fn sweep_breakpoints(properties: &PostScriptGradient) -> Vec<f32> {
+    let min = properties.min;
+    let length = properties.max - properties.min;
+
+    let mut offsets = vec![0.0, 1.0];
+    offsets.extend(properties.stops.iter().map(|s| s.offset.get()));
+
+    let mut angles = vec![];
+
+    if properties.spread_method == SpreadMethod::Pad || length <= 0.0 {
+        angles.extend(offsets.iter().map(|o| min + o * length.max(0.0)));
+    } else {
+        let first = ((0.0 - min) / length).floor() as i64;
+        let last = ((360.0 - min) / length).ceil() as i64;
+
+        for i in first..=last.min(first.saturating_add(MAX_SWEEP_PERIODS)) {
+            for offset in &offsets {
+                angles.push(min + (i as f32 + offset) * length);
+                // Periods are mirrored with the `Reflect` spread method.
+                angles.push(min + (i as f32 + 1.0 - offset) * length);
+            }
+        }
+    }
+
+    let segments = (360.0 / MAX_SWEEP_PATCH_ANGLE) as i32;
+    angles.extend((0..=segments).map(|i| i as f32 * MAX_SWEEP_PATCH_ANGLE));
+    angles.retain(|a| (0.0..=360.0).contains(a));
+    angles.sort_by(f32::total_cmp);
+    angles.dedup_by(|a, b| (*a - *b).abs() < 0.001);
+
+    // Make sure that the patches never exceed the maximum angle, even if deduplication
+    // dropped one of the angles.
+    let mut result: Vec<f32> = vec![];
+
+    for angle in angles {
+        if let Some(&prev) = result.last() {
+            let steps = ((angle - prev) / MAX_SWEEP_PATCH_ANGLE).ceil().max(1.0) as i32;
+
+            for step in 1..steps {
+                result.push(prev + (angle - prev) * step as f32 / steps as f32);
+            }
+        }
+
+        result.push(angle);
+    }
+
+    result
+}
+
+/// The color components at the start and end of a segment of a sweep gradient,
+/// in which the color changes linearly.
+fn sweep_segment_colors(
+    properties: &PostScriptGradient,
+    a0: f32,
+    a1: f32,
+    use_opacities: bool,
+) -> (Vec<f32>, Vec<f32>) {
+    let min = properties.min;
+    let length = properties.max - properties.min;
+    let mid = (a0 + a1) / 2.0;
+
+    // The position of an angle within the gradient, with the period determined
+    // by the middle of the segment, so that discontinuities at the segment
+    // boundaries are handled correctly.
+    let position = |angle: f32| -> f32 {
+        if length <= 0.0 {
+            return if mid < min { 0.0 } else { 1.0 };
+        }
+
+        let n = (angle - min) / length;
+
+        match properties.spread_method {
+            SpreadMethod::Pad => n.clamp(0.0, 1.0),
+            SpreadMethod::Repeat => n - ((mid - min) / length).floor(),
+            SpreadMethod::Reflect => {
+                let i = ((mid - min) / length).floor();
+                let offset = n - i;
+
+                if i.rem_euclid(2.0) > 0.0 {
+                    1.0 - offset
+                } else {
+                    offset
+                }
+            }
+        }
+    };
+
+    let t0 = position(a0);
+    let t1 = position(a1);
+    let t_mid = position(mid);
+
+    (
+        interpolate_stops(&properties.stops, t_mid, t0, use_opacities),
+        interpolate_stops(&properties.stops, t_mid, t1, use_opacities),
+    )
+}
+
+/// Interpolate the color components at position `t` within the stops, using the
+/// pair of stops that surrounds the position `reference`.
+fn interpolate_stops(stops: &[Stop], reference: f32, t: f32, use_opacities: bool) -> Vec<f32> {
+    let components = |stop: &Stop| {
+        if use_opacities {
+            vec![stop.opacity.get()]
+        } else {
+            stop.color.to_pdf_color()
+        }
+    };
+
+    let first = &stops[0];
+    let last = &stops[stops.len() - 1];
+
+    if reference <= first.offset.get() {
+        return components(first);
+    }
+
+    if reference >= last.offset.get() {
+        return components(last);
+    }
+
+    let index = stops
+        .windows(2)
+        .position(|w| w[0].offset.get() <= reference && reference <= w[1].offset.get())
+        .unwrap_or(0);
+    let (s0, s1) = (&stops[index], &stops[index + 1]);
+
+    let span = s1.offset.get() - s0.offset.get();
+    let factor = if span > 0.0 {
+        ((t - s0.offset.get()) / span).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    components(s0)
+        .into_iter()
+        .zip(components(s1))
+        .map(|(c0, c1)| c0 + (c1 - c0) * factor)
+        .collect()
+}
+
 const MAX_POSTSCRIPT_STOPS: usize = 97;
 
 fn trim_stops(stops: &[Stop]) -> Vec<Stop> {
@@ -563,11 +1326,7 @@ fn serialize_linear_postscript(
         properties.domain.bottom(),
     ]);
 
-    if use_opacities {
-        postscript_function.range([0.0, 1.0]);
-    } else {
-        postscript_function.range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0]);
-    }
+    postscript_function.range(stop_range(&properties.stops, use_opacities));
 
     root_ref
 }
@@ -805,7 +1564,7 @@ fn serialize_stitching(
     let mut functions = vec![];
     let mut bounds = vec![];
     let mut encode = vec![];
-    let mut count = 0;
+    let range = stop_range(stops, use_opacities);
 
     for window in stops.windows(2) {
         let (first, second) = (&window[0], &window[1]);
@@ -820,9 +1579,7 @@ fn serialize_stitching(
             )
         };
 
-        count = c0_components.len();
-
-        let exp_ref = serialize_exponential(c0_components, c1_components, chunk, sc);
+        let exp_ref = serialize_exponential(c0_components, c1_components, range.clone(), chunk, sc);
 
         functions.push(exp_ref);
         encode.extend([0.0, 1.0]);
@@ -831,7 +1588,7 @@ fn serialize_stitching(
     bounds.pop();
     let mut stitching_function = chunk.stitching_function(root_ref);
     stitching_function.domain([0.0, 1.0]);
-    stitching_function.range([0.0, 1.0].repeat(count));
+    stitching_function.range(range);
     stitching_function.functions(functions);
     stitching_function.bounds(bounds);
     stitching_function.encode(encode);
@@ -842,6 +1599,7 @@ fn serialize_stitching(
 fn serialize_exponential(
     c0: Vec<f32>,
     c1: Vec<f32>,
+    range: Vec<f32>,
     chunk: &mut Chunk,
     sc: &mut SerializeContext,
 ) -> Ref {
@@ -851,11 +1609,10 @@ fn serialize_exponential(
         c1.len(),
         "cannot create gradient with stops from different color spaces"
     );
-    let num_components = c0.len();
 
     let mut exp = chunk.exponential_function(root_ref);
 
-    exp.range([0.0, 1.0].repeat(num_components));
+    exp.range(range);
     exp.c0(c0);
     exp.c1(c1);
     exp.domain([0.0, 1.0]);
@@ -863,3 +1620,49 @@ fn serialize_exponential(
     exp.finish();
     root_ref
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::rgb;
+
+    #[test]
+    fn sweep_breakpoints_tiny_range() {
+        let stop = |offset: f32, color: rgb::Color| Stop {
+            offset: NormalizedF32::new(offset).unwrap(),
+            color: color.into(),
+            opacity: NormalizedF32::ONE,
+        };
+        let length = 0.0001;
+        let properties = PostScriptGradient {
+            min: 0.0,
+            max: length,
+            cx: 100.0,
+            cy: 100.0,
+            stops: vec![
+                stop(0.0, rgb::Color::new(255, 0, 0)),
+                stop(1.0, rgb::Color::new(0, 0, 255)),
+            ],
+            domain: Rect::from_xywh(0.0, 0.0, 200.0, 200.0).unwrap(),
+            spread_method: SpreadMethod::Repeat,
+            gradient_type: GradientType::Sweep,
+            anti_alias: false,
+        };
+
+        let angles = sweep_breakpoints(&properties);
+
+        // Only the first periods get their own breakpoints, the rest of the circle
+        // is covered by patches of the maximum angle.
+        let periods_end = (MAX_SWEEP_PERIODS + 1) as f32 * length;
+        let segments = (360.0 / MAX_SWEEP_PATCH_ANGLE) as usize;
+        assert_eq!(
+            angles.iter().filter(|a| **a > periods_end + 0.001).count(),
+            segments
+        );
+        assert!(angles
+            .windows(2)
+            .all(|w| w[1] - w[0] <= MAX_SWEEP_PATCH_ANGLE + 0.001));
+        // Breakpoints closer than the deduplication threshold are merged.
+        assert!(angles.len() <= segments + (periods_end / 0.001).ceil() as usize + 1);
+    }
+}
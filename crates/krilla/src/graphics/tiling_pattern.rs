@@ -4,9 +4,10 @@ use std::hash::{Hash, Hasher};
 use std::ops::DerefMut;
 
 use pdf_writer::types::{PaintType, TilingType};
-use pdf_writer::{Finish, Ref};
+use pdf_writer::{Finish, Name, Ref};
 
 use crate::chunk_container::ChunkContainer;
+use crate::color::ColorSpace;
 use crate::geom::Transform;
 use crate::num::NormalizedF32;
 use crate::resource;
@@ -14,6 +15,7 @@ use crate::resource::Resourceable;
 use crate::serialize::{Cacheable, SerializeContext};
 use crate::stream::StreamBuilder;
 use crate::stream::{FilterStreamBuilder, Stream};
+use crate::util::write_colorspace;
 
 #[derive(Debug, PartialEq)]
 pub(crate) struct TilingPattern {
@@ -22,6 +24,7 @@ pub(crate) struct TilingPattern {
     base_opacity: NormalizedF32,
     width: f32,
     height: f32,
+    uncolored: bool,
 }
 
 impl Eq for TilingPattern {}
@@ -33,16 +36,19 @@ impl Hash for TilingPattern {
         self.base_opacity.hash(state);
         self.width.to_bits().hash(state);
         self.height.to_bits().hash(state);
+        self.uncolored.hash(state);
     }
 }
 
 impl TilingPattern {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         stream: Stream,
         transform: Transform,
         base_opacity: NormalizedF32,
         width: f32,
         height: f32,
+        uncolored: bool,
         serializer_context: &mut SerializeContext,
         chunk_container: &mut ChunkContainer,
     ) -> Self {
@@ -68,6 +74,7 @@ impl TilingPattern {
             base_opacity,
             width,
             height,
+            uncolored,
         }
     }
 }
@@ -100,10 +107,15 @@ impl Cacheable for TilingPattern {
         );
 
         let final_bbox = pdf_writer::Rect::new(0.0, 0.0, self.width, self.height);
+        let paint_type = if self.uncolored {
+            PaintType::Uncolored
+        } else {
+            PaintType::Colored
+        };
 
         tiling_pattern
             .tiling_type(TilingType::ConstantSpacing)
-            .paint_type(PaintType::Colored)
+            .paint_type(paint_type)
             .bbox(final_bbox)
             .matrix(self.transform.to_pdf_transform())
             .x_step(final_bbox.x2 - final_bbox.x1)
@@ -117,3 +129,39 @@ impl Cacheable for TilingPattern {
 impl Resourceable for TilingPattern {
     type Resource = resource::Pattern;
 }
+
+/// The `[/Pattern base]` color space that uncolored tiling patterns are painted with.
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub(crate) struct PatternColorSpace {
+    base: ColorSpace,
+}
+
+impl PatternColorSpace {
+    pub(crate) fn new(base: ColorSpace) -> Self {
+        Self { base }
+    }
+}
+
+impl Cacheable for PatternColorSpace {
+    fn serialize(
+        self,
+        sc: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+        root_ref: Ref,
+    ) {
+        let base = sc.register_colorspace(chunk_container, self.base);
+
+        let mut array = chunk_container
+            .non_stream
+            .color_spaces
+            .indirect(root_ref)
+            .array();
+        array.item(Name(b"Pattern"));
+        write_colorspace(base, array.push());
+        array.finish();
+    }
+}
+
+impl Resourceable for PatternColorSpace {
+    type Resource = resource::ColorSpace;
+}
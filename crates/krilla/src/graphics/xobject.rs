@@ -6,12 +6,12 @@ use pdf_writer::{Finish, Name, Ref};
 use crate::chunk_container::ChunkContainer;
 use crate::configure::ValidationError;
 use crate::geom::Rect;
-use crate::graphics::color::{rgb, DEVICE_RGB};
+use crate::graphics::blend::{GroupColorSpace, TransparencyGroup};
 use crate::resource;
-use crate::resource::{Resource, Resourceable};
-use crate::serialize::{Cacheable, MaybeDeviceColorSpace, SerializeContext};
+use crate::resource::Resourceable;
+use crate::serialize::{Cacheable, SerializeContext};
 use crate::stream::{FilterStreamBuilder, Stream};
-use crate::util::{NameExt, Prehashed};
+use crate::util::{write_colorspace, Prehashed};
 
 #[derive(Debug, Hash, Eq, PartialEq)]
 struct Repr {
@@ -19,6 +19,8 @@ struct Repr {
     isolated: bool,
     transparency_group_color_space: bool,
     custom_bbox: Option<Rect>,
+    /// An explicitly requested transparency group, which overrides `isolated`.
+    group: Option<TransparencyGroup>,
 }
 
 #[derive(Debug, Hash, Clone, Eq, PartialEq)]
@@ -48,6 +50,18 @@ impl XObject {
             isolated,
             transparency_group_color_space,
             custom_bbox,
+            group: None,
+        })))
+    }
+
+    /// Create a new XObject that is a transparency group with the given properties.
+    pub(crate) fn new_transparency_group(stream: Stream, group: TransparencyGroup) -> Self {
+        Self(Arc::new(Prehashed::new(Repr {
+            stream,
+            isolated: group.isolated,
+            transparency_group_color_space: true,
+            custom_bbox: None,
+            group: Some(group),
         })))
     }
 
@@ -89,10 +103,13 @@ impl Cacheable for XObject {
         // So while we don't technically always need to write it, let's just play it save
         // and always add a group color space in case we have a transparency group.
         let transparency_group_cs = if use_transparency_group {
-            Some(sc.register_colorspace(
-                chunk_container,
-                rgb::color_space(serialize_settings.no_device_cs).into(),
-            ))
+            let color_space = self
+                .0
+                .group
+                .map(|g| g.color_space)
+                .unwrap_or(GroupColorSpace::Rgb)
+                .color_space(sc);
+            Some(sc.register_colorspace(chunk_container, color_space))
         } else {
             None
         };
@@ -125,15 +142,12 @@ impl Cacheable for XObject {
                 transparency.isolated(self.0.isolated);
             }
 
+            if self.0.group.is_some_and(|g| g.knockout) {
+                transparency.pair(Name(b"K"), true);
+            }
+
             if let Some(transparency_group_cs) = transparency_group_cs {
-                let pdf_cs = transparency.insert(Name(b"CS"));
-
-                match transparency_group_cs {
-                    MaybeDeviceColorSpace::DeviceRgb => pdf_cs.primitive(DEVICE_RGB.to_pdf_name()),
-                    // Can only be SRGB
-                    MaybeDeviceColorSpace::ColorSpace(cs) => pdf_cs.primitive(cs.get_ref()),
-                    _ => unreachable!(),
-                }
+                write_colorspace(transparency_group_cs, transparency.insert(Name(b"CS")));
             }
 
             transparency.finish();
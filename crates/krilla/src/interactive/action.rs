@@ -3,15 +3,21 @@
 //! PDF has the concept of "actions", which encompass things like navigating to a URL,
 //! opening some file on the system, and so on. The PDF reference defines a whole bunch
 //! of actions, but krilla does not expose nearly all of them, and never will. As of right now,
-//! the only available action is the link action, which allows you to specify a link that
-//! should be opened, when activating the action.
+//! the only available actions are link actions, which allow you to specify a link that
+//! should be opened, when activating the action, go-to actions, submit-form and reset-form
+//! actions for [interactive forms], as well as rendition actions, which control the playback
+//! of the media clip of a screen annotation.
+//!
+//! [interactive forms]: crate::interactive::form
 
 use pdf_writer::types::ActionType;
-use pdf_writer::{Name, Str};
+use pdf_writer::{Name, Ref, Str, TextStr};
 
+use crate::configure::ValidationError;
 use crate::error::KrillaResult;
 use crate::interactive::destination::Destination;
 use crate::serialize::SerializeContext;
+use crate::surface::Location;
 
 /// A type of action.
 pub enum Action {
@@ -19,6 +25,10 @@ pub enum Action {
     Link(LinkAction),
     /// A go-to action.
     Goto(Destination),
+    /// A submit-form action.
+    SubmitForm(SubmitFormAction),
+    /// A reset-form action.
+    ResetForm(ResetFormAction),
 }
 
 impl Action {
@@ -26,6 +36,7 @@ impl Action {
         &self,
         sc: &mut SerializeContext,
         mut action: pdf_writer::writers::Action,
+        location: Option<Location>,
     ) -> KrillaResult<()> {
         match self {
             Action::Link(link) => {
@@ -37,6 +48,17 @@ impl Action {
                 let dest_entry = action.action_type(ActionType::GoTo).insert(Name(b"D"));
                 dest.serialize(sc, dest_entry)
             }
+            Action::SubmitForm(submit) => {
+                submit.serialize(action);
+
+                Ok(())
+            }
+            Action::ResetForm(reset) => {
+                sc.register_validation_error(ValidationError::ResetFormAction(location));
+                reset.serialize(action);
+
+                Ok(())
+            }
         }
     }
 }
@@ -66,3 +88,219 @@ impl LinkAction {
             .uri(Str(self.uri.as_bytes()));
     }
 }
+
+// Flags of submit-form and reset-form actions.
+const EXCLUDE: i32 = 1 << 0;
+const EXPORT_FORMAT: i32 = 1 << 2;
+const GET_METHOD: i32 = 1 << 3;
+const XFDF: i32 = 1 << 5;
+const SUBMIT_PDF: i32 = 1 << 8;
+
+/// The format in which a submit-form action submits the form.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum SubmitFormat {
+    /// Submit the values of the fields in Forms Data Format (FDF).
+    #[default]
+    Fdf,
+    /// Submit the values of the fields in XML Forms Data Format (XFDF).
+    Xfdf,
+    /// Submit the values of the fields like an HTML form.
+    Html,
+    /// Submit the whole document as PDF.
+    Pdf,
+}
+
+impl SubmitFormat {
+    fn to_flags(self) -> i32 {
+        match self {
+            SubmitFormat::Fdf => 0,
+            SubmitFormat::Xfdf => XFDF,
+            SubmitFormat::Html => EXPORT_FORMAT,
+            SubmitFormat::Pdf => SUBMIT_PDF,
+        }
+    }
+}
+
+/// Which fields a submit-form or reset-form action applies to.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub enum FieldSelection {
+    /// All fields of the form.
+    #[default]
+    All,
+    /// Only the fields with the given names.
+    Include(Vec<String>),
+    /// All fields except the ones with the given names.
+    Exclude(Vec<String>),
+}
+
+impl FieldSelection {
+    /// Write the fields and return the flags of the selection.
+    fn write(&self, action: &mut pdf_writer::writers::Action) -> i32 {
+        let (names, flags) = match self {
+            FieldSelection::All => return 0,
+            FieldSelection::Include(names) => (names, 0),
+            FieldSelection::Exclude(names) => (names, EXCLUDE),
+        };
+
+        action
+            .insert(Name(b"Fields"))
+            .array()
+            .items(names.iter().map(|n| TextStr(n)));
+
+        flags
+    }
+}
+
+/// A submit-form action. Will send the values of the fields of the form to a URL
+/// when clicked.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct SubmitFormAction {
+    url: String,
+    fields: FieldSelection,
+    format: SubmitFormat,
+    get_method: bool,
+}
+
+impl From<SubmitFormAction> for Action {
+    fn from(value: SubmitFormAction) -> Self {
+        Action::SubmitForm(value)
+    }
+}
+
+impl SubmitFormAction {
+    /// Create a new submit-form action that will submit all fields of the form to
+    /// a URL in Forms Data Format (FDF), using a POST request.
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            fields: FieldSelection::default(),
+            format: SubmitFormat::default(),
+            get_method: false,
+        }
+    }
+
+    /// Set the fields that should be submitted.
+    pub fn with_fields(self, fields: FieldSelection) -> Self {
+        Self { fields, ..self }
+    }
+
+    /// Set the format in which the form should be submitted.
+    pub fn with_format(self, format: SubmitFormat) -> Self {
+        Self { format, ..self }
+    }
+
+    /// Set whether the form should be submitted using a GET instead of a POST request.
+    ///
+    /// This only has an effect if the form is submitted like an HTML form.
+    pub fn with_get_method(self, get_method: bool) -> Self {
+        Self { get_method, ..self }
+    }
+
+    fn serialize(&self, mut action: pdf_writer::writers::Action) {
+        action.pair(Name(b"S"), Name(b"SubmitForm"));
+        action
+            .insert(Name(b"F"))
+            .dict()
+            .pair(Name(b"FS"), Name(b"URL"))
+            .pair(Name(b"F"), Str(self.url.as_bytes()));
+
+        let mut flags = self.fields.write(&mut action) | self.format.to_flags();
+
+        if self.get_method && self.format == SubmitFormat::Html {
+            flags |= GET_METHOD;
+        }
+
+        if flags != 0 {
+            action.pair(Name(b"Flags"), flags);
+        }
+    }
+}
+
+/// A reset-form action. Will reset the fields of the form to their default values
+/// when clicked.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct ResetFormAction {
+    fields: FieldSelection,
+}
+
+impl From<ResetFormAction> for Action {
+    fn from(value: ResetFormAction) -> Self {
+        Action::ResetForm(value)
+    }
+}
+
+impl ResetFormAction {
+    /// Create a new reset-form action that will reset all fields of the form.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the fields that should be reset.
+    pub fn with_fields(self, fields: FieldSelection) -> Self {
+        Self { fields }
+    }
+
+    fn serialize(&self, mut action: pdf_writer::writers::Action) {
+        action.pair(Name(b"S"), Name(b"ResetForm"));
+
+        let flags = self.fields.write(&mut action);
+
+        if flags != 0 {
+            action.pair(Name(b"Flags"), flags);
+        }
+    }
+}
+
+/// What a rendition action should do with the media clip of a screen annotation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum RenditionOperation {
+    /// Play the media clip from the start, stopping it first if it is already playing.
+    Play,
+    /// Stop the media clip.
+    Stop,
+    /// Pause the media clip.
+    Pause,
+    /// Resume the media clip if it is paused.
+    Resume,
+}
+
+impl RenditionOperation {
+    fn to_pdf(self) -> i32 {
+        match self {
+            RenditionOperation::Play => 0,
+            RenditionOperation::Stop => 1,
+            RenditionOperation::Pause => 2,
+            RenditionOperation::Resume => 3,
+        }
+    }
+}
+
+/// A rendition action. Controls the playback of the media clip of a screen annotation.
+///
+/// Rendition actions always refer to the screen annotation they are attached to, see
+/// [`ScreenAnnotation::with_action`].
+///
+/// [`ScreenAnnotation::with_action`]: crate::annotation::ScreenAnnotation::with_action
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct RenditionAction {
+    operation: RenditionOperation,
+}
+
+impl RenditionAction {
+    /// Create a new rendition action.
+    pub fn new(operation: RenditionOperation) -> Self {
+        Self { operation }
+    }
+
+    pub(crate) fn serialize(
+        &self,
+        mut action: pdf_writer::writers::Action,
+        annotation: Ref,
+        rendition: Ref,
+    ) {
+        action.pair(Name(b"S"), Name(b"Rendition"));
+        action.pair(Name(b"OP"), self.operation.to_pdf());
+        action.pair(Name(b"AN"), annotation);
+        action.pair(Name(b"R"), rendition);
+    }
+}
@@ -2,24 +2,40 @@
 //!
 //! PDF has the concept of annotations, which allow you to associate certain regions of
 //! a page with an "annotation". The PDF reference defines many different actions, however,
-//! krilla does not and never will expose all of them. As of right now, the only annotations
-//! that are supported are "link annotations", which allow you associate a certain region of
-//! the page with a link.
+//! krilla does not and never will expose all of them. As of right now, the following annotations
+//! are supported:
+//!
+//! - "Link annotations", which allow you associate a certain region of the page with a link.
+//! - "Stamp annotations", which display a rubber stamp such as "Approved" or "Draft".
+//! - "Watermark annotations", which display content at a fixed size and position when
+//!   printed, regardless of the dimensions of the printed page.
+//! - "File attachment annotations", which attach an embedded file to a specific location
+//!   on the page.
+//! - "Screen annotations", which play an embedded media clip, like an audio or video file.
+//! - "Widget annotations", which display the fields of an interactive form. See the
+//!   [`form`] module for more information.
+//!
+//! [`form`]: crate::interactive::form
 
 use core::f32;
 
 use pdf_writer::types::AnnotationFlags;
-use pdf_writer::{Finish, Name, Ref, TextStr};
+use pdf_writer::{Finish, Name, Ref, Str, TextStr};
 
 use crate::chunk_container::ChunkContainer;
 use crate::color::Color;
+use crate::configure::validate::VersionedFeature;
 use crate::configure::{PdfVersion, ValidationError};
 use crate::error::KrillaResult;
-use crate::geom::{Quadrilateral, Rect};
-use crate::interactive::action::Action;
+use crate::geom::{Quadrilateral, Rect, Transform};
+use crate::graphics::xobject::XObject;
+use crate::interactive::action::{Action, RenditionAction};
 use crate::interactive::destination::Destination;
+use crate::interactive::form::Widget;
+use crate::interchange::embed::EmbeddedFile;
 use crate::page::page_root_transform;
 use crate::serialize::SerializeContext;
+use crate::stream::Stream;
 use crate::surface::Location;
 
 /// An annotation.
@@ -28,37 +44,130 @@ pub struct Annotation {
     pub(crate) alt: Option<String>,
     pub(crate) struct_parent: Option<i32>,
     pub(crate) location: Option<Location>,
+    pub(crate) print: Option<bool>,
+    pub(crate) no_view: bool,
 }
 
 impl Annotation {
-    /// Create a new link annotation with some alt text.
-    ///
-    /// Note that the alt text might be required in some cases, for example
-    /// when exporting to PDF/UA.
-    pub fn new_link(annotation: LinkAnnotation, alt_text: Option<String>) -> Self {
+    fn new(annotation_type: AnnotationType, alt_text: Option<String>) -> Self {
         Self {
-            annotation_type: AnnotationType::Link(annotation),
+            annotation_type,
             alt: alt_text,
             struct_parent: None,
             location: None,
+            print: None,
+            no_view: false,
         }
     }
 
+    /// Create a new link annotation with some alt text.
+    ///
+    /// Note that the alt text might be required in some cases, for example
+    /// when exporting to PDF/UA.
+    pub fn new_link(annotation: LinkAnnotation, alt_text: Option<String>) -> Self {
+        Self::new(AnnotationType::Link(annotation), alt_text)
+    }
+
+    /// Create a new stamp annotation with some alt text.
+    ///
+    /// Note that the alt text might be required in some cases, for example
+    /// when exporting to PDF/UA.
+    pub fn new_stamp(annotation: StampAnnotation, alt_text: Option<String>) -> Self {
+        Self::new(AnnotationType::Stamp(annotation), alt_text)
+    }
+
+    /// Create a new watermark annotation with some alt text.
+    ///
+    /// Note that the alt text might be required in some cases, for example
+    /// when exporting to PDF/UA.
+    pub fn new_watermark(annotation: WatermarkAnnotation, alt_text: Option<String>) -> Self {
+        Self::new(AnnotationType::Watermark(annotation), alt_text)
+    }
+
+    /// Create a new file attachment annotation with some alt text.
+    ///
+    /// Note that the alt text might be required in some cases, for example
+    /// when exporting to PDF/UA.
+    pub fn new_file_attachment(
+        annotation: FileAttachmentAnnotation,
+        alt_text: Option<String>,
+    ) -> Self {
+        Self::new(AnnotationType::FileAttachment(annotation), alt_text)
+    }
+
+    /// Create a new screen annotation with some alt text.
+    ///
+    /// Note that the alt text might be required in some cases, for example
+    /// when exporting to PDF/UA.
+    pub fn new_screen(annotation: ScreenAnnotation, alt_text: Option<String>) -> Self {
+        Self::new(AnnotationType::Screen(annotation), alt_text)
+    }
+
+    /// Create a new widget annotation for a form field with a tooltip.
+    ///
+    /// The tooltip is shown when hovering over the field, and is used as the
+    /// alternate description of the field by assistive technology. Note that it
+    /// might be required in some cases, for example when exporting to PDF/UA.
+    pub fn new_widget(widget: Widget, tooltip: Option<String>) -> Self {
+        Self::new(AnnotationType::Widget(widget), tooltip)
+    }
+
     /// Sets the location of the annotation.
     pub fn with_location(mut self, location: Option<Location>) -> Self {
         self.location = location;
         self
     }
+
+    /// Sets whether the annotation should be printed.
+    ///
+    /// If not set, krilla will decide on its own: Link annotations with a visible
+    /// border are not printed, while all other annotations are.
+    ///
+    /// Note that when exporting to PDF/A, annotations will always be printed.
+    pub fn with_print(mut self, print: bool) -> Self {
+        self.print = Some(print);
+        self
+    }
+
+    /// Sets whether the annotation should be hidden when viewing the document
+    /// on screen. In combination with [`Annotation::with_print`], this allows you to
+    /// create annotations that only show up when printing.
+    ///
+    /// Note that this flag will be ignored when exporting to PDF/A, since it
+    /// forbids annotations that are not visible on screen.
+    pub fn with_no_view(mut self, no_view: bool) -> Self {
+        self.no_view = no_view;
+        self
+    }
 }
 
 impl From<LinkAnnotation> for Annotation {
     fn from(value: LinkAnnotation) -> Self {
-        Self {
-            annotation_type: AnnotationType::Link(value),
-            alt: None,
-            struct_parent: None,
-            location: None,
-        }
+        Self::new(AnnotationType::Link(value), None)
+    }
+}
+
+impl From<StampAnnotation> for Annotation {
+    fn from(value: StampAnnotation) -> Self {
+        Self::new(AnnotationType::Stamp(value), None)
+    }
+}
+
+impl From<WatermarkAnnotation> for Annotation {
+    fn from(value: WatermarkAnnotation) -> Self {
+        Self::new(AnnotationType::Watermark(value), None)
+    }
+}
+
+impl From<FileAttachmentAnnotation> for Annotation {
+    fn from(value: FileAttachmentAnnotation) -> Self {
+        Self::new(AnnotationType::FileAttachment(value), None)
+    }
+}
+
+impl From<ScreenAnnotation> for Annotation {
+    fn from(value: ScreenAnnotation) -> Self {
+        Self::new(AnnotationType::Screen(value), None)
     }
 }
 
@@ -70,28 +179,55 @@ impl Annotation {
         root_ref: Ref,
         page_height: f32,
     ) -> KrillaResult<()> {
+        // Needs to happen before we start writing the annotation, since the dependencies
+        // are written to different chunks.
+        let dependencies = self
+            .annotation_type
+            .register_dependencies(sc, chunk_container);
+
         let chunk = &mut chunk_container.non_stream.annotations;
         let mut annotation = chunk
             .indirect(root_ref)
             .start::<pdf_writer::writers::Annotation>();
 
-        self.annotation_type
-            .serialize_type(sc, &mut annotation, page_height)?;
-
-        let AnnotationType::Link(l) = &self.annotation_type;
-        // Only set the print flag when really necessary (only PDF/A). Don't
-        // set it by default, so annotations with color borders will be shown
-        // on a screen but not printed.
-        // TODO: No need to write the print flag even if it is `None`,
-        // only for PDF/A.
-        if l.border.is_none()
-            || sc
-                .serialize_settings()
-                .configuration
-                .validators()
-                .requires_annotation_flags()
-        {
-            annotation.flags(AnnotationFlags::PRINT);
+        self.annotation_type.serialize_type(
+            sc,
+            &mut annotation,
+            &dependencies,
+            root_ref,
+            page_height,
+            self.location,
+            self.alt.as_deref(),
+        )?;
+
+        let requires_annotation_flags = sc
+            .serialize_settings()
+            .configuration
+            .validators()
+            .requires_annotation_flags();
+
+        // Only set the print flag when really necessary. Don't set it by default
+        // for links with a border, so that the border will be shown on a screen
+        // but not printed. PDF/A always requires the print flag to be set and
+        // forbids hiding annotations on screen.
+        let print = requires_annotation_flags
+            || self
+                .print
+                .unwrap_or_else(|| self.annotation_type.prints_by_default());
+        let no_view = self.no_view && !requires_annotation_flags;
+
+        let mut flags = AnnotationFlags::empty();
+
+        if print {
+            flags |= AnnotationFlags::PRINT;
+        }
+
+        if no_view {
+            flags |= AnnotationFlags::NO_VIEW;
+        }
+
+        if !flags.is_empty() {
+            annotation.flags(flags);
         }
 
         if let Some(struct_parent) = self.struct_parent {
@@ -100,9 +236,23 @@ impl Annotation {
 
         if let Some(alt_text) = &self.alt {
             annotation.contents(TextStr(alt_text));
+
+            if matches!(self.annotation_type, AnnotationType::Widget(_)) {
+                annotation.pair(Name(b"TU"), TextStr(alt_text));
+            }
         }
 
-        if self.alt.as_ref().is_none_or(String::is_empty) {
+        if let AnnotationType::Widget(_) = &self.annotation_type {
+            // PDF/UA requires each form field to have a tooltip and to be wrapped
+            // in a `Form` structure element.
+            if self.alt.as_ref().is_none_or(String::is_empty) {
+                sc.register_validation_error(ValidationError::MissingFieldTooltip(self.location));
+            }
+
+            if self.struct_parent.is_none() {
+                sc.register_validation_error(ValidationError::UntaggedFormField(self.location));
+            }
+        } else if self.alt.as_ref().is_none_or(String::is_empty) {
             sc.register_validation_error(ValidationError::MissingAnnotationAltText(self.location));
         }
 
@@ -116,21 +266,206 @@ impl Annotation {
 pub enum AnnotationType {
     /// A link annotation.
     Link(LinkAnnotation),
+    /// A stamp annotation.
+    Stamp(StampAnnotation),
+    /// A watermark annotation.
+    Watermark(WatermarkAnnotation),
+    /// A file attachment annotation.
+    FileAttachment(FileAttachmentAnnotation),
+    /// A screen annotation.
+    Screen(ScreenAnnotation),
+    /// A widget annotation of a form field.
+    Widget(Widget),
 }
 
 impl AnnotationType {
+    pub(crate) fn rect(&self) -> Rect {
+        match self {
+            AnnotationType::Link(l) => l.rect,
+            AnnotationType::Stamp(s) => s.rect,
+            AnnotationType::Watermark(w) => w.rect,
+            AnnotationType::FileAttachment(f) => f.rect,
+            AnnotationType::Screen(s) => s.rect,
+            AnnotationType::Widget(w) => w.rect(),
+        }
+    }
+
+    fn prints_by_default(&self) -> bool {
+        match self {
+            AnnotationType::Link(l) => l.border.is_none(),
+            AnnotationType::Stamp(_)
+            | AnnotationType::Watermark(_)
+            | AnnotationType::FileAttachment(_) => true,
+            AnnotationType::Screen(s) => s.poster.is_some(),
+            AnnotationType::Widget(_) => true,
+        }
+    }
+
+    fn register_dependencies(
+        &self,
+        sc: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+    ) -> Dependencies {
+        match self {
+            AnnotationType::Link(_) => Dependencies::default(),
+            AnnotationType::Stamp(s) => Dependencies {
+                appearance: s
+                    .appearance
+                    .as_ref()
+                    .map(|a| register_appearance(sc, chunk_container, a, s.rect)),
+                ..Dependencies::default()
+            },
+            AnnotationType::Watermark(w) => Dependencies {
+                appearance: Some(register_appearance(
+                    sc,
+                    chunk_container,
+                    &w.appearance,
+                    w.rect,
+                )),
+                ..Dependencies::default()
+            },
+            AnnotationType::FileAttachment(f) => Dependencies {
+                appearance: f
+                    .appearance
+                    .as_ref()
+                    .map(|a| register_appearance(sc, chunk_container, a, f.rect)),
+                file_spec: Some(sc.register_cacheable(chunk_container, f.file.clone())),
+                ..Dependencies::default()
+            },
+            AnnotationType::Screen(s) => {
+                let file_spec = sc.register_cacheable(chunk_container, s.clip.clone());
+
+                Dependencies {
+                    appearance: s
+                        .poster
+                        .as_ref()
+                        .map(|a| register_appearance(sc, chunk_container, a, s.rect)),
+                    file_spec: Some(file_spec),
+                    rendition: Some(s.register_rendition(sc, chunk_container, file_spec)),
+                    ..Dependencies::default()
+                }
+            }
+            AnnotationType::Widget(w) => w.register_dependencies(sc, chunk_container),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn serialize_type(
         &self,
         sc: &mut SerializeContext,
         annotation: &mut pdf_writer::writers::Annotation,
+        dependencies: &Dependencies,
+        root_ref: Ref,
         page_height: f32,
+        location: Option<Location>,
+        alt: Option<&str>,
     ) -> KrillaResult<()> {
         match self {
-            AnnotationType::Link(l) => l.serialize_type(sc, annotation, page_height),
+            AnnotationType::Link(l) => l.serialize_type(sc, annotation, page_height, location),
+            AnnotationType::Stamp(s) => {
+                s.serialize_type(sc, annotation, dependencies, page_height, location);
+                Ok(())
+            }
+            AnnotationType::Watermark(w) => {
+                w.serialize_type(sc, annotation, dependencies, page_height, location);
+                Ok(())
+            }
+            AnnotationType::FileAttachment(f) => {
+                f.serialize_type(sc, annotation, dependencies, page_height, location);
+                Ok(())
+            }
+            AnnotationType::Screen(s) => {
+                s.serialize_type(
+                    sc,
+                    annotation,
+                    dependencies,
+                    root_ref,
+                    page_height,
+                    location,
+                );
+                Ok(())
+            }
+            AnnotationType::Widget(w) => w.serialize_type(
+                sc,
+                annotation,
+                dependencies,
+                root_ref,
+                page_height,
+                location,
+                alt,
+            ),
         }
     }
 }
 
+/// Objects an annotation depends on.
+///
+/// They need to be registered before the annotation itself is written, since
+/// they are written to different chunks.
+#[derive(Default)]
+pub(crate) struct Dependencies {
+    /// The normal appearance stream.
+    pub(crate) appearance: Option<Ref>,
+    /// The normal appearance stream of a button that is turned off.
+    pub(crate) off_appearance: Option<Ref>,
+    /// The file specification of an attached file.
+    pub(crate) file_spec: Option<Ref>,
+    /// The rendition of a media clip.
+    pub(crate) rendition: Option<Ref>,
+    /// The default appearance string of a form field with variable text.
+    pub(crate) default_appearance: Option<String>,
+}
+
+/// Write the rect of an annotation, converting it from krilla's coordinate system.
+pub(crate) fn write_rect(
+    annotation: &mut pdf_writer::writers::Annotation,
+    rect: Rect,
+    page_height: f32,
+) {
+    let actual_rect = rect.transform(page_root_transform(page_height)).unwrap();
+    annotation.rect(actual_rect.to_pdf_rect());
+}
+
+/// Register the normal appearance of an annotation as a form XObject.
+///
+/// The stream is drawn in the coordinate system of the annotation rect, with the
+/// origin in the top-left corner, just like on a page.
+pub(crate) fn register_appearance(
+    sc: &mut SerializeContext,
+    chunk_container: &mut ChunkContainer,
+    appearance: &Stream,
+    rect: Rect,
+) -> Ref {
+    // Appearance streams are mapped to the annotation rect with their origin in the
+    // bottom-left corner, so we need to flip the y-axis.
+    let mut prelude = sc.new_content();
+    prelude.transform(page_root_transform(rect.height()).to_pdf_transform());
+    let prelude = prelude.finish();
+    sc.register_limits(prelude.limits());
+
+    let mut content = prelude.to_vec();
+    content.extend_from_slice(&appearance.content);
+
+    let stream = Stream::new(
+        content,
+        Rect::from_xywh(0.0, 0.0, rect.width(), rect.height()).unwrap(),
+        appearance.validation_errors.clone(),
+        appearance.resource_dictionary.clone(),
+        appearance.uses_mask,
+        false,
+    );
+
+    sc.register_cacheable(chunk_container, XObject::new(stream, false, false, None))
+}
+
+/// Write the normal appearance of an annotation.
+pub(crate) fn write_appearance(annotation: &mut pdf_writer::writers::Annotation, appearance: Ref) {
+    annotation
+        .insert(Name(b"AP"))
+        .dict()
+        .pair(Name(b"N"), appearance);
+}
+
 /// An annotation target.
 pub enum Target {
     /// A destination within the document.
@@ -232,14 +567,11 @@ impl LinkAnnotation {
         sc: &mut SerializeContext,
         annotation: &mut pdf_writer::writers::Annotation,
         page_height: f32,
+        location: Option<Location>,
     ) -> KrillaResult<()> {
         annotation.subtype(pdf_writer::types::AnnotationType::Link);
 
-        let actual_rect = self
-            .rect
-            .transform(page_root_transform(page_height))
-            .unwrap();
-        annotation.rect(actual_rect.to_pdf_rect());
+        write_rect(annotation, self.rect, page_height);
         annotation.border(
             0.0,
             0.0,
@@ -260,6 +592,13 @@ impl LinkAnnotation {
                 crate::color::RegularColor::Luma(gray) => {
                     annotation.color_gray(gray.to_pdf_color());
                 }
+                // Annotation colors only support device color spaces.
+                c @ (crate::color::RegularColor::Lab(_)
+                | crate::color::RegularColor::CalRgb(_)
+                | crate::color::RegularColor::CalGray(_)) => {
+                    let [r, g, b] = c.as_rgb().unwrap_or_default().to_pdf_color();
+                    annotation.color_rgb(r, g, b);
+                }
             }
         }
 
@@ -277,7 +616,525 @@ impl LinkAnnotation {
             Target::Destination(destination) => {
                 destination.serialize(sc, annotation.insert(Name(b"Dest")))
             }
-            Target::Action(action) => action.serialize(sc, annotation.action()),
+            Target::Action(action) => action.serialize(sc, annotation.action(), location),
+        }
+    }
+}
+
+/// The name of a standard stamp, as predefined by the PDF specification.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum StampName {
+    /// An "Approved" stamp.
+    Approved,
+    /// An "Experimental" stamp.
+    Experimental,
+    /// A "Not Approved" stamp.
+    NotApproved,
+    /// An "As Is" stamp.
+    AsIs,
+    /// An "Expired" stamp.
+    Expired,
+    /// A "Not For Public Release" stamp.
+    NotForPublicRelease,
+    /// A "Confidential" stamp.
+    Confidential,
+    /// A "Final" stamp.
+    Final,
+    /// A "Sold" stamp.
+    Sold,
+    /// A "Departmental" stamp.
+    Departmental,
+    /// A "For Comment" stamp.
+    ForComment,
+    /// A "Top Secret" stamp.
+    TopSecret,
+    /// A "Draft" stamp.
+    Draft,
+    /// A "For Public Release" stamp.
+    ForPublicRelease,
+}
+
+impl StampName {
+    fn to_pdf(self) -> Name<'static> {
+        match self {
+            StampName::Approved => Name(b"Approved"),
+            StampName::Experimental => Name(b"Experimental"),
+            StampName::NotApproved => Name(b"NotApproved"),
+            StampName::AsIs => Name(b"AsIs"),
+            StampName::Expired => Name(b"Expired"),
+            StampName::NotForPublicRelease => Name(b"NotForPublicRelease"),
+            StampName::Confidential => Name(b"Confidential"),
+            StampName::Final => Name(b"Final"),
+            StampName::Sold => Name(b"Sold"),
+            StampName::Departmental => Name(b"Departmental"),
+            StampName::ForComment => Name(b"ForComment"),
+            StampName::TopSecret => Name(b"TopSecret"),
+            StampName::Draft => Name(b"Draft"),
+            StampName::ForPublicRelease => Name(b"ForPublicRelease"),
+        }
+    }
+}
+
+/// A stamp annotation.
+///
+/// Displays text or graphics intended to look as if they were stamped on the
+/// page with a rubber stamp.
+pub struct StampAnnotation {
+    pub(crate) rect: Rect,
+    pub(crate) name: Option<StampName>,
+    pub(crate) appearance: Option<Stream>,
+}
+
+impl StampAnnotation {
+    /// Create a new stamp annotation with a standard name.
+    ///
+    /// `rect`: The bounding box of the stamp on the page.
+    /// `name`: The name of the stamp.
+    ///
+    /// Note that without an explicit appearance, it is up to the viewer to decide
+    /// how to draw the stamp. Since PDF/A requires annotations to have an appearance,
+    /// you need to provide one via [`StampAnnotation::with_appearance`] when exporting
+    /// to PDF/A.
+    pub fn new(rect: Rect, name: StampName) -> Self {
+        Self {
+            rect,
+            name: Some(name),
+            appearance: None,
+        }
+    }
+
+    /// Create a new stamp annotation with a custom appearance.
+    ///
+    /// `rect`: The bounding box of the stamp on the page.
+    /// `appearance`: The appearance of the stamp. It is drawn relative to the top-left
+    /// corner of `rect`.
+    pub fn new_custom(rect: Rect, appearance: Stream) -> Self {
+        Self {
+            rect,
+            name: None,
+            appearance: Some(appearance),
+        }
+    }
+
+    /// Set an explicit appearance for this stamp annotation. It is drawn relative
+    /// to the top-left corner of the stamp's bounding box.
+    pub fn with_appearance(self, appearance: Stream) -> Self {
+        Self {
+            appearance: Some(appearance),
+            ..self
+        }
+    }
+
+    fn serialize_type(
+        &self,
+        sc: &mut SerializeContext,
+        annotation: &mut pdf_writer::writers::Annotation,
+        dependencies: &Dependencies,
+        page_height: f32,
+        location: Option<Location>,
+    ) {
+        annotation.pair(Name(b"Subtype"), Name(b"Stamp"));
+        write_rect(annotation, self.rect, page_height);
+
+        if let Some(name) = self.name {
+            annotation.pair(Name(b"Name"), name.to_pdf());
+        }
+
+        if let Some(appearance) = dependencies.appearance {
+            write_appearance(annotation, appearance);
+        } else {
+            sc.register_validation_error(ValidationError::MissingAnnotationAppearance(location));
+        }
+    }
+}
+
+/// Controls how a watermark annotation is placed on the page when printing.
+///
+/// When printed, the watermark will be drawn with the given matrix and translated
+/// by a fraction of the printed page's dimensions, so that it shows up at a fixed
+/// size and position regardless of how the page is scaled.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FixedPrint {
+    pub(crate) matrix: Transform,
+    pub(crate) horizontal: f32,
+    pub(crate) vertical: f32,
+}
+
+impl FixedPrint {
+    /// Create new fixed print settings.
+    ///
+    /// `matrix`: The transform that should be applied to the watermark when printing.
+    /// `horizontal`: The horizontal translation, as a fraction of the width of the
+    /// printed page.
+    /// `vertical`: The vertical translation, as a fraction of the height of the
+    /// printed page.
+    pub fn new(matrix: Transform, horizontal: f32, vertical: f32) -> Self {
+        Self {
+            matrix,
+            horizontal,
+            vertical,
+        }
+    }
+}
+
+/// A watermark annotation.
+///
+/// Displays graphics that should be printed at a fixed size and position,
+/// regardless of the dimensions of the printed page. If you want the watermark
+/// to only show up when printing, you can combine it with
+/// [`Annotation::with_no_view`].
+pub struct WatermarkAnnotation {
+    pub(crate) rect: Rect,
+    pub(crate) appearance: Stream,
+    pub(crate) fixed_print: Option<FixedPrint>,
+}
+
+impl WatermarkAnnotation {
+    /// Create a new watermark annotation.
+    ///
+    /// `rect`: The bounding box of the watermark on the page.
+    /// `appearance`: The appearance of the watermark. It is drawn relative to the top-left
+    /// corner of `rect`.
+    pub fn new(rect: Rect, appearance: Stream) -> Self {
+        Self {
+            rect,
+            appearance,
+            fixed_print: None,
+        }
+    }
+
+    /// Set how the watermark should be placed when printing.
+    pub fn with_fixed_print(self, fixed_print: FixedPrint) -> Self {
+        Self {
+            fixed_print: Some(fixed_print),
+            ..self
+        }
+    }
+
+    fn serialize_type(
+        &self,
+        sc: &mut SerializeContext,
+        annotation: &mut pdf_writer::writers::Annotation,
+        dependencies: &Dependencies,
+        page_height: f32,
+        location: Option<Location>,
+    ) {
+        // Older readers still display the annotation using its appearance stream,
+        // so we write it anyway.
+        if sc.serialize_settings().pdf_version()
+            < VersionedFeature::WatermarkAnnotations.minimum_pdf_version()
+        {
+            sc.register_validation_error(ValidationError::RequiresNewerPdfVersion(
+                VersionedFeature::WatermarkAnnotations,
+                location,
+            ));
+        }
+
+        annotation.pair(Name(b"Subtype"), Name(b"Watermark"));
+        write_rect(annotation, self.rect, page_height);
+
+        if let Some(appearance) = dependencies.appearance {
+            write_appearance(annotation, appearance);
+        }
+
+        if let Some(fixed_print) = &self.fixed_print {
+            let mut dict = annotation.insert(Name(b"FixedPrint")).dict();
+            dict.pair(Name(b"Type"), Name(b"FixedPrint"));
+            dict.insert(Name(b"Matrix"))
+                .array()
+                .items(fixed_print.matrix.to_pdf_transform());
+            dict.pair(Name(b"H"), fixed_print.horizontal);
+            dict.pair(Name(b"V"), fixed_print.vertical);
+            dict.finish();
+        }
+    }
+}
+
+/// The icon of a file attachment annotation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum FileAttachmentIcon {
+    /// A push pin.
+    #[default]
+    PushPin,
+    /// A paperclip.
+    Paperclip,
+    /// A graph.
+    Graph,
+    /// A tag.
+    Tag,
+}
+
+impl FileAttachmentIcon {
+    fn to_pdf(self) -> Name<'static> {
+        match self {
+            FileAttachmentIcon::PushPin => Name(b"PushPin"),
+            FileAttachmentIcon::Paperclip => Name(b"Paperclip"),
+            FileAttachmentIcon::Graph => Name(b"Graph"),
+            FileAttachmentIcon::Tag => Name(b"Tag"),
+        }
+    }
+}
+
+/// A file attachment annotation.
+///
+/// Unlike [`Document::embed_file`], which attaches a file to the document as a whole,
+/// this allows you to pin an embedded file to a specific location on a page. Viewers
+/// will usually display an icon there, which can be used to open the file.
+///
+/// The same validation rules as for files embedded at the document level apply.
+///
+/// [`Document::embed_file`]: crate::Document::embed_file
+pub struct FileAttachmentAnnotation {
+    pub(crate) rect: Rect,
+    pub(crate) file: EmbeddedFile,
+    pub(crate) icon: FileAttachmentIcon,
+    pub(crate) appearance: Option<Stream>,
+}
+
+impl FileAttachmentAnnotation {
+    /// Create a new file attachment annotation.
+    ///
+    /// `rect`: The bounding box of the annotation on the page.
+    /// `file`: The file that should be attached.
+    pub fn new(rect: Rect, file: EmbeddedFile) -> Self {
+        Self {
+            rect,
+            file,
+            icon: FileAttachmentIcon::default(),
+            appearance: None,
+        }
+    }
+
+    /// Set the icon that viewers should use to display the annotation.
+    pub fn with_icon(self, icon: FileAttachmentIcon) -> Self {
+        Self { icon, ..self }
+    }
+
+    /// Set an explicit appearance for this annotation, which will be used instead
+    /// of the icon. It is drawn relative to the top-left corner of the bounding box.
+    ///
+    /// Since PDF/A requires annotations to have an appearance, you need to provide
+    /// one when exporting to PDF/A.
+    pub fn with_appearance(self, appearance: Stream) -> Self {
+        Self {
+            appearance: Some(appearance),
+            ..self
+        }
+    }
+
+    fn serialize_type(
+        &self,
+        sc: &mut SerializeContext,
+        annotation: &mut pdf_writer::writers::Annotation,
+        dependencies: &Dependencies,
+        page_height: f32,
+        location: Option<Location>,
+    ) {
+        annotation.subtype(pdf_writer::types::AnnotationType::FileAttachment);
+        write_rect(annotation, self.rect, page_height);
+        annotation.pair(Name(b"Name"), self.icon.to_pdf());
+
+        if let Some(file_spec) = dependencies.file_spec {
+            annotation.pair(Name(b"FS"), file_spec);
+
+            if sc.serialize_settings().supports_associated_files() {
+                annotation.insert(Name(b"AF")).array().item(file_spec);
+            }
+        }
+
+        if let Some(appearance) = dependencies.appearance {
+            write_appearance(annotation, appearance);
+        } else {
+            sc.register_validation_error(ValidationError::MissingAnnotationAppearance(location));
+        }
+    }
+}
+
+/// The event that triggers an action of a screen annotation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ScreenTrigger {
+    /// The annotation is activated, for example by clicking on it.
+    Activate,
+    /// The page containing the annotation is opened.
+    PageOpen,
+    /// The page containing the annotation is closed.
+    PageClose,
+    /// The page containing the annotation becomes visible.
+    PageVisible,
+    /// The page containing the annotation is no longer visible.
+    PageInvisible,
+}
+
+impl ScreenTrigger {
+    fn to_pdf(self) -> Name<'static> {
+        match self {
+            ScreenTrigger::Activate => Name(b"A"),
+            ScreenTrigger::PageOpen => Name(b"PO"),
+            ScreenTrigger::PageClose => Name(b"PC"),
+            ScreenTrigger::PageVisible => Name(b"PV"),
+            ScreenTrigger::PageInvisible => Name(b"PI"),
+        }
+    }
+}
+
+/// A screen annotation.
+///
+/// Screen annotations define a region of the page in which a media clip, like an
+/// audio or video file, is played. The playback is controlled by [`RenditionAction`]s
+/// attached to the annotation. Until the clip is played, viewers show the poster of the
+/// annotation, if one was provided.
+///
+/// The media clip is embedded in the same way as [`Document::embed_file`], and it needs
+/// a mime type so that viewers know how to play it. Note that multimedia content is
+/// forbidden by PDF/A.
+///
+/// [`Document::embed_file`]: crate::Document::embed_file
+pub struct ScreenAnnotation {
+    pub(crate) rect: Rect,
+    pub(crate) clip: EmbeddedFile,
+    pub(crate) title: Option<String>,
+    pub(crate) poster: Option<Stream>,
+    pub(crate) actions: Vec<(ScreenTrigger, RenditionAction)>,
+}
+
+impl ScreenAnnotation {
+    /// Create a new screen annotation.
+    ///
+    /// `rect`: The region of the page in which the media clip is played.
+    /// `clip`: The media clip that should be played.
+    pub fn new(rect: Rect, clip: EmbeddedFile) -> Self {
+        Self {
+            rect,
+            clip,
+            title: None,
+            poster: None,
+            actions: vec![],
+        }
+    }
+
+    /// Set the title of the annotation.
+    pub fn with_title(self, title: String) -> Self {
+        Self {
+            title: Some(title),
+            ..self
+        }
+    }
+
+    /// Set the poster of the annotation, which is shown while the media clip is not
+    /// playing. It is drawn relative to the top-left corner of the bounding box.
+    pub fn with_poster(self, poster: Stream) -> Self {
+        Self {
+            poster: Some(poster),
+            ..self
+        }
+    }
+
+    /// Run a rendition action when the given event occurs.
+    ///
+    /// Replaces any previous action for the same trigger.
+    pub fn with_action(mut self, trigger: ScreenTrigger, action: RenditionAction) -> Self {
+        self.actions.retain(|(t, _)| *t != trigger);
+        self.actions.push((trigger, action));
+        self
+    }
+
+    fn register_rendition(
+        &self,
+        sc: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+        file_spec: Ref,
+    ) -> Ref {
+        let root_ref = sc.new_ref();
+        let name = self.title.as_deref().unwrap_or(&self.clip.path);
+
+        let chunk = &mut chunk_container.non_stream.annotations;
+        let mut rendition = chunk.indirect(root_ref).dict();
+        rendition.pair(Name(b"Type"), Name(b"Rendition"));
+        rendition.pair(Name(b"S"), Name(b"MR"));
+        rendition.pair(Name(b"N"), TextStr(name));
+
+        let mut clip = rendition.insert(Name(b"C")).dict();
+        clip.pair(Name(b"Type"), Name(b"MediaClip"));
+        clip.pair(Name(b"S"), Name(b"MCD"));
+        clip.pair(Name(b"N"), TextStr(name));
+        clip.pair(Name(b"D"), file_spec);
+
+        if let Some(mime_type) = &self.clip.mime_type {
+            clip.pair(Name(b"CT"), Str(mime_type.as_str().as_bytes()));
+        }
+
+        // Without this, viewers will refuse to write the embedded clip to a
+        // temporary file, which is necessary for playing it.
+        clip.insert(Name(b"P"))
+            .dict()
+            .pair(Name(b"TF"), Str(b"TEMPACCESS"));
+
+        if let Some(description) = &self.clip.description {
+            clip.insert(Name(b"Alt"))
+                .array()
+                .item(Str(b""))
+                .item(TextStr(description));
+        }
+
+        clip.finish();
+        rendition.finish();
+
+        root_ref
+    }
+
+    fn serialize_type(
+        &self,
+        sc: &mut SerializeContext,
+        annotation: &mut pdf_writer::writers::Annotation,
+        dependencies: &Dependencies,
+        root_ref: Ref,
+        page_height: f32,
+        location: Option<Location>,
+    ) {
+        sc.register_validation_error(ValidationError::Multimedia(location));
+
+        annotation.pair(Name(b"Subtype"), Name(b"Screen"));
+        write_rect(annotation, self.rect, page_height);
+
+        if let Some(title) = &self.title {
+            annotation.pair(Name(b"T"), TextStr(title));
+        }
+
+        if let Some(appearance) = dependencies.appearance {
+            write_appearance(annotation, appearance);
+        }
+
+        let Some(rendition) = dependencies.rendition else {
+            return;
+        };
+
+        if let Some((_, action)) = self
+            .actions
+            .iter()
+            .find(|(t, _)| *t == ScreenTrigger::Activate)
+        {
+            action.serialize(annotation.action(), root_ref, rendition);
+        }
+
+        let mut additional_actions = self
+            .actions
+            .iter()
+            .filter(|(t, _)| *t != ScreenTrigger::Activate)
+            .peekable();
+
+        if additional_actions.peek().is_some() {
+            let mut aa = annotation.insert(Name(b"AA")).dict();
+
+            for (trigger, action) in additional_actions {
+                action.serialize(
+                    aa.insert(trigger.to_pdf())
+                        .start::<pdf_writer::writers::Action>(),
+                    root_ref,
+                    rendition,
+                );
+            }
+
+            aa.finish();
         }
     }
 }
@@ -0,0 +1,1839 @@
+//! Interactive forms, allowing users to fill in fields in their PDF viewer.
+//!
+//! PDF has the concept of interactive forms (also called "AcroForms"), which consist of
+//! fields the user can fill in. Each field is shown on a page by means of a widget
+//! annotation, which is why fields are added to a page in the same way as any other
+//! annotation, by calling [`Page::add_annotation`] with an [`Annotation::new_widget`].
+//!
+//! krilla generates the appearance of all fields using the [`Font`] you provide, so that
+//! they are displayed correctly even in viewers that don't support interactive forms.
+//! The font is also referenced in the form's default resources, so that viewers can use
+//! it to render text that is entered by the user. Note that since fonts are subsetted,
+//! krilla only includes the glyphs needed for the initial values of the fields as well as
+//! the glyphs for the printable characters of the Latin-1 range, so that text in most
+//! Western European languages can be entered.
+//!
+//! When exporting to PDF/UA, every field needs a tooltip, and must be wrapped in a
+//! [`Tag::Form`] structure element. To do so, add the field to the page with
+//! [`Page::add_tagged_annotation`] and push the returned identifier into a `Form` tag
+//! group of the tag tree.
+//!
+//! As of right now, the following fields are supported:
+//!
+//! - "Text fields", which allow the user to enter a single or multiple lines of text.
+//! - "Checkboxes", which can be toggled on and off.
+//! - "Radio buttons", of which at most one per [`RadioGroup`] can be selected. The buttons
+//!   of a group can be spread across different pages.
+//! - "Combo boxes", which allow the user to choose one of several options from a drop-down
+//!   list, and optionally to enter a custom value.
+//! - "List boxes", which allow the user to choose one or several options from a scrollable list.
+//! - "Push buttons", which trigger an [`Action`] when clicked, for example to submit the form.
+//!
+//! [`Page::add_annotation`]: crate::page::Page::add_annotation
+//! [`Page::add_tagged_annotation`]: crate::page::Page::add_tagged_annotation
+//! [`Tag::Form`]: crate::tagging::Tag::Form
+//! [`Action`]: crate::action::Action
+
+use std::f32::consts::PI;
+use std::num::NonZeroU32;
+
+use indexmap::{IndexMap, IndexSet};
+use pdf_writer::{Chunk, Finish, Name, Ref, Str, TextStr};
+
+use crate::chunk_container::ChunkContainer;
+use crate::color::{luma, rgb};
+use crate::error::KrillaResult;
+use crate::geom::{Path, PathBuilder, Point, Rect, Transform};
+use crate::graphics::paint::{Fill, LineCap, LineJoin, Stroke};
+use crate::interactive::action::Action;
+use crate::interactive::annotation::{
+    register_appearance, write_appearance, write_rect, Annotation, Dependencies,
+};
+use crate::serialize::SerializeContext;
+use crate::stream::{Stream, StreamBuilder};
+use crate::surface::{Location, Surface};
+use crate::text::{Font, GlyphId, KrillaGlyph};
+
+// Field flags that apply to all field types.
+const READ_ONLY: i32 = 1 << 0;
+const REQUIRED: i32 = 1 << 1;
+
+// Field flags that only apply to text fields.
+const MULTILINE: i32 = 1 << 12;
+const PASSWORD: i32 = 1 << 13;
+const COMB: i32 = 1 << 24;
+
+// Field flags that only apply to button fields.
+const NO_TOGGLE_TO_OFF: i32 = 1 << 14;
+const RADIO: i32 = 1 << 15;
+const PUSH_BUTTON: i32 = 1 << 16;
+const RADIOS_IN_UNISON: i32 = 1 << 25;
+
+// Field flags that only apply to choice fields.
+const COMBO: i32 = 1 << 17;
+const EDIT: i32 = 1 << 18;
+const SORT: i32 = 1 << 19;
+const MULTI_SELECT: i32 = 1 << 21;
+const COMMIT_ON_SEL_CHANGE: i32 = 1 << 26;
+
+/// The name of the appearance state of a button that is turned off.
+const OFF: &[u8] = b"Off";
+
+/// The padding between the border of a field and its text.
+const PADDING: f32 = 2.0;
+
+/// The gray level of the background of push buttons.
+const BUTTON_BACKGROUND: u8 = 191;
+
+/// A widget, i.e. the representation of a form field on a page.
+pub enum Widget {
+    /// A text field.
+    Text(TextField),
+    /// A checkbox.
+    CheckBox(CheckBox),
+    /// A radio button.
+    RadioButton(RadioButton),
+    /// A combo box.
+    ComboBox(ComboBox),
+    /// A list box.
+    ListBox(ListBox),
+    /// A push button.
+    PushButton(PushButton),
+}
+
+impl From<TextField> for Widget {
+    fn from(value: TextField) -> Self {
+        Widget::Text(value)
+    }
+}
+
+impl From<CheckBox> for Widget {
+    fn from(value: CheckBox) -> Self {
+        Widget::CheckBox(value)
+    }
+}
+
+impl From<RadioButton> for Widget {
+    fn from(value: RadioButton) -> Self {
+        Widget::RadioButton(value)
+    }
+}
+
+impl From<ComboBox> for Widget {
+    fn from(value: ComboBox) -> Self {
+        Widget::ComboBox(value)
+    }
+}
+
+impl From<ListBox> for Widget {
+    fn from(value: ListBox) -> Self {
+        Widget::ListBox(value)
+    }
+}
+
+impl From<PushButton> for Widget {
+    fn from(value: PushButton) -> Self {
+        Widget::PushButton(value)
+    }
+}
+
+impl From<TextField> for Annotation {
+    fn from(value: TextField) -> Self {
+        Annotation::new_widget(value.into(), None)
+    }
+}
+
+impl From<CheckBox> for Annotation {
+    fn from(value: CheckBox) -> Self {
+        Annotation::new_widget(value.into(), None)
+    }
+}
+
+impl From<RadioButton> for Annotation {
+    fn from(value: RadioButton) -> Self {
+        Annotation::new_widget(value.into(), None)
+    }
+}
+
+impl From<ComboBox> for Annotation {
+    fn from(value: ComboBox) -> Self {
+        Annotation::new_widget(value.into(), None)
+    }
+}
+
+impl From<ListBox> for Annotation {
+    fn from(value: ListBox) -> Self {
+        Annotation::new_widget(value.into(), None)
+    }
+}
+
+impl From<PushButton> for Annotation {
+    fn from(value: PushButton) -> Self {
+        Annotation::new_widget(value.into(), None)
+    }
+}
+
+impl Widget {
+    pub(crate) fn rect(&self) -> Rect {
+        match self {
+            Widget::Text(t) => t.rect,
+            Widget::CheckBox(c) => c.rect,
+            Widget::RadioButton(r) => r.rect,
+            Widget::ComboBox(c) => c.rect,
+            Widget::ListBox(l) => l.rect,
+            Widget::PushButton(p) => p.rect,
+        }
+    }
+
+    pub(crate) fn register_dependencies(
+        &self,
+        sc: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+    ) -> Dependencies {
+        match self {
+            Widget::Text(t) => t.register_dependencies(sc, chunk_container),
+            Widget::CheckBox(c) => c.appearance.register(sc, chunk_container, c.rect),
+            Widget::RadioButton(r) => r.appearance.register(sc, chunk_container, r.rect),
+            Widget::ComboBox(c) => c.register_dependencies(sc, chunk_container),
+            Widget::ListBox(l) => l.register_dependencies(sc, chunk_container),
+            Widget::PushButton(p) => p.register_dependencies(sc, chunk_container),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn serialize_type(
+        &self,
+        sc: &mut SerializeContext,
+        annotation: &mut pdf_writer::writers::Annotation,
+        dependencies: &Dependencies,
+        root_ref: Ref,
+        page_height: f32,
+        location: Option<Location>,
+        tooltip: Option<&str>,
+    ) -> KrillaResult<()> {
+        annotation.pair(Name(b"Subtype"), Name(b"Widget"));
+
+        match self {
+            Widget::Text(t) => {
+                t.serialize_type(annotation, dependencies, page_height);
+                sc.register_form_field(root_ref);
+            }
+            Widget::CheckBox(c) => {
+                c.serialize_type(annotation, dependencies, page_height);
+                sc.register_form_field(root_ref);
+            }
+            Widget::RadioButton(r) => {
+                // Radio buttons are only the kids of the actual field, which is
+                // written once all of its buttons have been serialized.
+                let parent = sc.register_radio_button(&r.group, root_ref, tooltip);
+                r.serialize_type(annotation, dependencies, parent, page_height);
+            }
+            Widget::ComboBox(c) => {
+                c.serialize_type(annotation, dependencies, page_height);
+                sc.register_form_field(root_ref);
+            }
+            Widget::ListBox(l) => {
+                l.serialize_type(annotation, dependencies, page_height);
+                sc.register_form_field(root_ref);
+            }
+            Widget::PushButton(p) => {
+                p.serialize_type(sc, annotation, dependencies, page_height, location)?;
+                sc.register_form_field(root_ref);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The alignment of the text in a text field.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum TextAlignment {
+    /// Align the text to the left.
+    #[default]
+    Left,
+    /// Center the text.
+    Center,
+    /// Align the text to the right.
+    Right,
+}
+
+impl TextAlignment {
+    fn to_pdf(self) -> i32 {
+        match self {
+            TextAlignment::Left => 0,
+            TextAlignment::Center => 1,
+            TextAlignment::Right => 2,
+        }
+    }
+
+    fn offset(self, available: f32, width: f32) -> f32 {
+        match self {
+            TextAlignment::Left => PADDING,
+            TextAlignment::Center => (available - width) / 2.0,
+            TextAlignment::Right => available - PADDING - width,
+        }
+    }
+}
+
+/// A text field.
+pub struct TextField {
+    pub(crate) name: String,
+    pub(crate) rect: Rect,
+    pub(crate) font: Font,
+    pub(crate) font_size: f32,
+    pub(crate) default_value: Option<String>,
+    pub(crate) max_len: Option<NonZeroU32>,
+    pub(crate) multiline: bool,
+    pub(crate) password: bool,
+    pub(crate) comb: bool,
+    pub(crate) alignment: TextAlignment,
+    pub(crate) read_only: bool,
+    pub(crate) required: bool,
+}
+
+impl TextField {
+    /// Create a new text field.
+    ///
+    /// `name`: The name of the field, which must be unique within the document.
+    /// `rect`: The bounding box of the field on the page.
+    /// `font`: The font that should be used to display the text of the field.
+    /// `font_size`: The font size that should be used to display the text of the field.
+    pub fn new(name: String, rect: Rect, font: Font, font_size: f32) -> Self {
+        Self {
+            name,
+            rect,
+            font,
+            font_size,
+            default_value: None,
+            max_len: None,
+            multiline: false,
+            password: false,
+            comb: false,
+            alignment: TextAlignment::default(),
+            read_only: false,
+            required: false,
+        }
+    }
+
+    /// Set the value the field is initialized with, and which it is reset to
+    /// when resetting the form.
+    pub fn with_default_value(self, default_value: String) -> Self {
+        Self {
+            default_value: Some(default_value),
+            ..self
+        }
+    }
+
+    /// Set the maximum number of characters the field can hold.
+    pub fn with_max_len(self, max_len: NonZeroU32) -> Self {
+        Self {
+            max_len: Some(max_len),
+            ..self
+        }
+    }
+
+    /// Set whether the field can contain multiple lines of text.
+    pub fn with_multiline(self, multiline: bool) -> Self {
+        Self { multiline, ..self }
+    }
+
+    /// Set whether the field is a password field. The text of password fields is
+    /// never displayed, and their value is not stored in the document.
+    pub fn with_password(self, password: bool) -> Self {
+        Self { password, ..self }
+    }
+
+    /// Set whether the field should be divided into as many equally spaced
+    /// positions ("combs") as its maximum number of characters.
+    ///
+    /// This only has an effect if a maximum length is set and the field is
+    /// neither multiline nor a password field.
+    pub fn with_comb(self, comb: bool) -> Self {
+        Self { comb, ..self }
+    }
+
+    /// Set the alignment of the text.
+    pub fn with_alignment(self, alignment: TextAlignment) -> Self {
+        Self { alignment, ..self }
+    }
+
+    /// Set whether the user is prevented from changing the value of the field.
+    pub fn with_read_only(self, read_only: bool) -> Self {
+        Self { read_only, ..self }
+    }
+
+    /// Set whether the field must have a value when the form is submitted.
+    pub fn with_required(self, required: bool) -> Self {
+        Self { required, ..self }
+    }
+
+    fn is_comb(&self) -> bool {
+        self.comb && self.max_len.is_some() && !self.multiline && !self.password
+    }
+
+    fn value(&self) -> Option<&str> {
+        // The value of password fields should never be stored in the document.
+        self.default_value.as_deref().filter(|_| !self.password)
+    }
+
+    fn register_dependencies(
+        &self,
+        sc: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+    ) -> Dependencies {
+        register_text_appearance(
+            sc,
+            chunk_container,
+            self.rect,
+            &self.font,
+            self.font_size,
+            true,
+            |surface| self.draw_appearance(surface),
+        )
+    }
+
+    fn draw_appearance(&self, surface: &mut Surface) {
+        let Some(value) = self.value() else {
+            return;
+        };
+
+        let (ascent, line_height) = line_metrics(&self.font, self.font_size);
+        let width = self.rect.width();
+
+        if let Some(max_len) = self.max_len.filter(|_| self.is_comb()) {
+            let cell_width = width / max_len.get() as f32;
+            let baseline = (self.rect.height() - line_height) / 2.0 + ascent;
+
+            for (i, (index, c)) in value
+                .char_indices()
+                .take(max_len.get() as usize)
+                .enumerate()
+            {
+                let text = &value[index..index + c.len_utf8()];
+                let (_, advance) = layout(&self.font, self.font_size, text);
+                let x = i as f32 * cell_width + (cell_width - advance) / 2.0;
+                draw_line(surface, &self.font, self.font_size, text, x, baseline);
+            }
+        } else if self.multiline {
+            let mut baseline = PADDING + ascent;
+
+            for line in wrap(&self.font, self.font_size, value, width - 2.0 * PADDING) {
+                let (_, advance) = layout(&self.font, self.font_size, line);
+                let x = self.alignment.offset(width, advance);
+                draw_line(surface, &self.font, self.font_size, line, x, baseline);
+                baseline += line_height;
+            }
+        } else {
+            let (_, advance) = layout(&self.font, self.font_size, value);
+            let x = self.alignment.offset(width, advance);
+            let baseline = (self.rect.height() - line_height) / 2.0 + ascent;
+            draw_line(surface, &self.font, self.font_size, value, x, baseline);
+        }
+    }
+
+    fn serialize_type(
+        &self,
+        annotation: &mut pdf_writer::writers::Annotation,
+        dependencies: &Dependencies,
+        page_height: f32,
+    ) {
+        write_rect(annotation, self.rect, page_height);
+
+        if let Some(appearance) = dependencies.appearance {
+            write_appearance(annotation, appearance);
+        }
+
+        annotation.pair(Name(b"FT"), Name(b"Tx"));
+        annotation.pair(Name(b"T"), TextStr(&self.name));
+
+        let mut flags = field_flags(self.read_only, self.required);
+
+        if self.multiline {
+            flags |= MULTILINE;
+        }
+
+        if self.password {
+            flags |= PASSWORD;
+        }
+
+        if self.is_comb() {
+            flags |= COMB;
+        }
+
+        if flags != 0 {
+            annotation.pair(Name(b"Ff"), flags);
+        }
+
+        if let Some(value) = self.value() {
+            annotation.pair(Name(b"V"), TextStr(value));
+            annotation.pair(Name(b"DV"), TextStr(value));
+        }
+
+        if let Some(max_len) = self.max_len {
+            annotation.pair(Name(b"MaxLen"), max_len.get() as i32);
+        }
+
+        if let Some(default_appearance) = &dependencies.default_appearance {
+            annotation.pair(Name(b"DA"), Str(default_appearance.as_bytes()));
+        }
+
+        if self.alignment != TextAlignment::Left {
+            annotation.pair(Name(b"Q"), self.alignment.to_pdf());
+        }
+    }
+}
+
+/// A symbol that is used to generate the appearance of a checkbox or radio button
+/// that is turned on.
+///
+/// The symbols are modeled after the corresponding glyphs of the ZapfDingbats font,
+/// which viewers use when regenerating the appearance of a button.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ToggleSymbol {
+    /// A check mark.
+    Check,
+    /// A filled circle.
+    Circle,
+    /// A cross.
+    Cross,
+    /// A filled square.
+    Square,
+    /// A filled diamond.
+    Diamond,
+    /// A filled five-pointed star.
+    Star,
+}
+
+impl ToggleSymbol {
+    /// The character of the symbol in the ZapfDingbats font.
+    fn to_char(self) -> &'static str {
+        match self {
+            ToggleSymbol::Check => "4",
+            ToggleSymbol::Circle => "l",
+            ToggleSymbol::Cross => "8",
+            ToggleSymbol::Square => "n",
+            ToggleSymbol::Diamond => "u",
+            ToggleSymbol::Star => "H",
+        }
+    }
+
+    /// Draw the symbol centered in a box with the given width and height.
+    fn draw(self, surface: &mut Surface, width: f32, height: f32) {
+        let size = width.min(height);
+        let transform = Transform::from_row(
+            size,
+            0.0,
+            0.0,
+            size,
+            (width - size) / 2.0,
+            (height - size) / 2.0,
+        );
+
+        let Some(path) = self.path().and_then(|p| p.transform(transform)) else {
+            return;
+        };
+
+        match self {
+            ToggleSymbol::Check | ToggleSymbol::Cross => {
+                surface.set_fill(None);
+                surface.set_stroke(Some(Stroke {
+                    width: 0.12 * size,
+                    line_cap: LineCap::Round,
+                    line_join: LineJoin::Round,
+                    ..Stroke::default()
+                }));
+            }
+            _ => {
+                surface.set_stroke(None);
+                surface.set_fill(Some(Fill::default()));
+            }
+        }
+
+        surface.draw_path(&path);
+    }
+
+    /// The outline of the symbol within the unit square.
+    fn path(self) -> Option<Path> {
+        let mut builder = PathBuilder::new();
+
+        match self {
+            ToggleSymbol::Check => {
+                builder.move_to(0.2, 0.55);
+                builder.line_to(0.42, 0.77);
+                builder.line_to(0.8, 0.25);
+            }
+            ToggleSymbol::Cross => {
+                builder.move_to(0.25, 0.25);
+                builder.line_to(0.75, 0.75);
+                builder.move_to(0.75, 0.25);
+                builder.line_to(0.25, 0.75);
+            }
+            ToggleSymbol::Circle => {
+                // The usual approximation of a quarter circle with a cubic curve.
+                let r = 0.3;
+                let k = 0.5523 * r;
+                builder.move_to(0.5 + r, 0.5);
+                builder.cubic_to(0.5 + r, 0.5 + k, 0.5 + k, 0.5 + r, 0.5, 0.5 + r);
+                builder.cubic_to(0.5 - k, 0.5 + r, 0.5 - r, 0.5 + k, 0.5 - r, 0.5);
+                builder.cubic_to(0.5 - r, 0.5 - k, 0.5 - k, 0.5 - r, 0.5, 0.5 - r);
+                builder.cubic_to(0.5 + k, 0.5 - r, 0.5 + r, 0.5 - k, 0.5 + r, 0.5);
+                builder.close();
+            }
+            ToggleSymbol::Square => {
+                builder.push_rect(Rect::from_xywh(0.25, 0.25, 0.5, 0.5)?);
+            }
+            ToggleSymbol::Diamond => {
+                builder.move_to(0.5, 0.15);
+                builder.line_to(0.85, 0.5);
+                builder.line_to(0.5, 0.85);
+                builder.line_to(0.15, 0.5);
+                builder.close();
+            }
+            ToggleSymbol::Star => {
+                for i in 0..10 {
+                    let r = if i % 2 == 0 { 0.38 } else { 0.16 };
+                    let angle = -PI / 2.0 + i as f32 * PI / 5.0;
+                    let (x, y) = (0.5 + r * angle.cos(), 0.53 + r * angle.sin());
+
+                    if i == 0 {
+                        builder.move_to(x, y);
+                    } else {
+                        builder.line_to(x, y);
+                    }
+                }
+                builder.close();
+            }
+        }
+
+        builder.finish()
+    }
+}
+
+/// The on and off appearances of a checkbox or radio button.
+enum ToggleAppearance {
+    Symbol(ToggleSymbol),
+    Custom {
+        on: Box<Stream>,
+        off: Option<Box<Stream>>,
+    },
+}
+
+impl ToggleAppearance {
+    fn register(
+        &self,
+        sc: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+        rect: Rect,
+    ) -> Dependencies {
+        let (on, off) = match self {
+            ToggleAppearance::Symbol(symbol) => {
+                let mut stream_builder = StreamBuilder::new(sc, chunk_container);
+                let mut surface = stream_builder.surface();
+                symbol.draw(&mut surface, rect.width(), rect.height());
+                surface.finish();
+                let on = stream_builder.finish();
+
+                (
+                    register_appearance(sc, chunk_container, &on, rect),
+                    register_appearance(sc, chunk_container, &Stream::empty(), rect),
+                )
+            }
+            ToggleAppearance::Custom { on, off } => (
+                register_appearance(sc, chunk_container, on, rect),
+                register_appearance(
+                    sc,
+                    chunk_container,
+                    off.as_deref().unwrap_or(&Stream::empty()),
+                    rect,
+                ),
+            ),
+        };
+
+        Dependencies {
+            appearance: Some(on),
+            off_appearance: Some(off),
+            ..Dependencies::default()
+        }
+    }
+
+    /// Write the appearance dictionary with the states `on_state` and `Off`, and the
+    /// state the button is initially in.
+    fn write(
+        &self,
+        annotation: &mut pdf_writer::writers::Annotation,
+        dependencies: &Dependencies,
+        on_state: &str,
+        is_on: bool,
+    ) {
+        if let (Some(on), Some(off)) = (dependencies.appearance, dependencies.off_appearance) {
+            let mut appearance = annotation.insert(Name(b"AP")).dict();
+            let mut normal = appearance.insert(Name(b"N")).dict();
+            normal.pair(Name(on_state.as_bytes()), on);
+            normal.pair(Name(OFF), off);
+            normal.finish();
+            appearance.finish();
+        }
+
+        let state = if is_on {
+            Name(on_state.as_bytes())
+        } else {
+            Name(OFF)
+        };
+        annotation.pair(Name(b"AS"), state);
+
+        if let ToggleAppearance::Symbol(symbol) = self {
+            annotation
+                .insert(Name(b"MK"))
+                .dict()
+                .pair(Name(b"CA"), Str(symbol.to_char().as_bytes()));
+        }
+    }
+}
+
+/// A checkbox.
+pub struct CheckBox {
+    pub(crate) name: String,
+    pub(crate) rect: Rect,
+    pub(crate) export_value: String,
+    pub(crate) checked: bool,
+    appearance: ToggleAppearance,
+    pub(crate) read_only: bool,
+    pub(crate) required: bool,
+}
+
+impl CheckBox {
+    /// Create a new checkbox that is unchecked and uses the export value `Yes`.
+    ///
+    /// `name`: The name of the field, which must be unique within the document.
+    /// `rect`: The bounding box of the checkbox on the page.
+    pub fn new(name: String, rect: Rect) -> Self {
+        Self {
+            name,
+            rect,
+            export_value: "Yes".to_string(),
+            checked: false,
+            appearance: ToggleAppearance::Symbol(ToggleSymbol::Check),
+            read_only: false,
+            required: false,
+        }
+    }
+
+    /// Set the value of the field when the checkbox is checked.
+    ///
+    /// Returns `None` if the export value is `Off`, which is reserved for the
+    /// unchecked state.
+    pub fn with_export_value(self, export_value: String) -> Option<Self> {
+        if export_value.as_bytes() == OFF {
+            return None;
+        }
+
+        Some(Self {
+            export_value,
+            ..self
+        })
+    }
+
+    /// Set whether the checkbox is initially checked.
+    pub fn with_checked(self, checked: bool) -> Self {
+        Self { checked, ..self }
+    }
+
+    /// Set the symbol that is drawn when the checkbox is checked. By default,
+    /// a check mark is drawn.
+    pub fn with_symbol(self, symbol: ToggleSymbol) -> Self {
+        Self {
+            appearance: ToggleAppearance::Symbol(symbol),
+            ..self
+        }
+    }
+
+    /// Set custom appearances for the checked state and, optionally, for the
+    /// unchecked state of the checkbox.
+    ///
+    /// The streams are drawn in the coordinate system of the rect of the checkbox,
+    /// with the origin in its top-left corner.
+    pub fn with_appearance(self, on: Stream, off: Option<Stream>) -> Self {
+        Self {
+            appearance: ToggleAppearance::Custom {
+                on: Box::new(on),
+                off: off.map(Box::new),
+            },
+            ..self
+        }
+    }
+
+    /// Set whether the user is prevented from toggling the checkbox.
+    pub fn with_read_only(self, read_only: bool) -> Self {
+        Self { read_only, ..self }
+    }
+
+    /// Set whether the checkbox must be checked when the form is submitted.
+    pub fn with_required(self, required: bool) -> Self {
+        Self { required, ..self }
+    }
+
+    fn serialize_type(
+        &self,
+        annotation: &mut pdf_writer::writers::Annotation,
+        dependencies: &Dependencies,
+        page_height: f32,
+    ) {
+        write_rect(annotation, self.rect, page_height);
+        self.appearance
+            .write(annotation, dependencies, &self.export_value, self.checked);
+
+        annotation.pair(Name(b"FT"), Name(b"Btn"));
+        annotation.pair(Name(b"T"), TextStr(&self.name));
+
+        let flags = field_flags(self.read_only, self.required);
+
+        if flags != 0 {
+            annotation.pair(Name(b"Ff"), flags);
+        }
+
+        let value = if self.checked {
+            Name(self.export_value.as_bytes())
+        } else {
+            Name(OFF)
+        };
+        annotation.pair(Name(b"V"), value);
+        annotation.pair(Name(b"DV"), value);
+    }
+}
+
+/// A group of radio buttons, of which at most one can be selected at a time.
+///
+/// Each [`RadioButton`] of the group holds a copy of it. The group is identified
+/// by its name, so the settings of all copies should be the same. If they are not,
+/// the settings of the first button that is added to the document are used.
+/// Similarly, the tooltip of the group is the alt text of the first button that
+/// has one.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct RadioGroup {
+    pub(crate) name: String,
+    pub(crate) value: Option<String>,
+    pub(crate) no_toggle_to_off: bool,
+    pub(crate) radios_in_unison: bool,
+    pub(crate) read_only: bool,
+    pub(crate) required: bool,
+}
+
+impl RadioGroup {
+    /// Create a new radio group where no button is selected.
+    ///
+    /// `name`: The name of the field, which must be unique within the document.
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            value: None,
+            no_toggle_to_off: false,
+            radios_in_unison: false,
+            read_only: false,
+            required: false,
+        }
+    }
+
+    /// Set the export value of the button that is initially selected.
+    pub fn with_value(self, value: String) -> Self {
+        Self {
+            value: Some(value),
+            ..self
+        }
+    }
+
+    /// Set whether exactly one button must be selected at all times, i.e. whether
+    /// clicking the selected button does not deselect it.
+    pub fn with_no_toggle_to_off(self, no_toggle_to_off: bool) -> Self {
+        Self {
+            no_toggle_to_off,
+            ..self
+        }
+    }
+
+    /// Set whether buttons with the same export value are turned on and off in
+    /// unison, instead of being mutually exclusive.
+    pub fn with_radios_in_unison(self, radios_in_unison: bool) -> Self {
+        Self {
+            radios_in_unison,
+            ..self
+        }
+    }
+
+    /// Set whether the user is prevented from changing the selected button.
+    pub fn with_read_only(self, read_only: bool) -> Self {
+        Self { read_only, ..self }
+    }
+
+    /// Set whether a button must be selected when the form is submitted.
+    pub fn with_required(self, required: bool) -> Self {
+        Self { required, ..self }
+    }
+
+    fn serialize(&self, chunk: &mut Chunk, root_ref: Ref, kids: &[Ref], tooltip: Option<&str>) {
+        let mut field = chunk.indirect(root_ref).dict();
+        field.pair(Name(b"FT"), Name(b"Btn"));
+        field.pair(Name(b"T"), TextStr(&self.name));
+
+        if let Some(tooltip) = tooltip {
+            field.pair(Name(b"TU"), TextStr(tooltip));
+        }
+
+        let mut flags = field_flags(self.read_only, self.required) | RADIO;
+
+        if self.no_toggle_to_off {
+            flags |= NO_TOGGLE_TO_OFF;
+        }
+
+        if self.radios_in_unison {
+            flags |= RADIOS_IN_UNISON;
+        }
+
+        field.pair(Name(b"Ff"), flags);
+
+        let value = self
+            .value
+            .as_ref()
+            .map(|v| Name(v.as_bytes()))
+            .unwrap_or(Name(OFF));
+        field.pair(Name(b"V"), value);
+        field.pair(Name(b"DV"), value);
+
+        field
+            .insert(Name(b"Kids"))
+            .array()
+            .items(kids.iter().copied());
+        field.finish();
+    }
+}
+
+/// A single radio button of a [`RadioGroup`].
+pub struct RadioButton {
+    pub(crate) group: RadioGroup,
+    pub(crate) export_value: String,
+    pub(crate) rect: Rect,
+    appearance: ToggleAppearance,
+}
+
+impl RadioButton {
+    /// Create a new radio button.
+    ///
+    /// `group`: The group the button belongs to.
+    /// `export_value`: The value of the group when the button is selected.
+    /// `rect`: The bounding box of the button on the page.
+    ///
+    /// Returns `None` if the export value is `Off`, which is reserved for the
+    /// unselected state.
+    pub fn new(group: RadioGroup, export_value: String, rect: Rect) -> Option<Self> {
+        if export_value.as_bytes() == OFF {
+            return None;
+        }
+
+        Some(Self {
+            group,
+            export_value,
+            rect,
+            appearance: ToggleAppearance::Symbol(ToggleSymbol::Circle),
+        })
+    }
+
+    /// Set the symbol that is drawn when the button is selected. By default,
+    /// a filled circle is drawn.
+    pub fn with_symbol(self, symbol: ToggleSymbol) -> Self {
+        Self {
+            appearance: ToggleAppearance::Symbol(symbol),
+            ..self
+        }
+    }
+
+    /// Set custom appearances for the selected state and, optionally, for the
+    /// unselected state of the button.
+    ///
+    /// The streams are drawn in the coordinate system of the rect of the button,
+    /// with the origin in its top-left corner.
+    pub fn with_appearance(self, on: Stream, off: Option<Stream>) -> Self {
+        Self {
+            appearance: ToggleAppearance::Custom {
+                on: Box::new(on),
+                off: off.map(Box::new),
+            },
+            ..self
+        }
+    }
+
+    fn serialize_type(
+        &self,
+        annotation: &mut pdf_writer::writers::Annotation,
+        dependencies: &Dependencies,
+        parent: Ref,
+        page_height: f32,
+    ) {
+        write_rect(annotation, self.rect, page_height);
+
+        let is_on = self.group.value.as_ref() == Some(&self.export_value);
+        self.appearance
+            .write(annotation, dependencies, &self.export_value, is_on);
+
+        annotation.pair(Name(b"Parent"), parent);
+    }
+}
+
+/// An option of a combo box or list box.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ChoiceOption {
+    pub(crate) export_value: String,
+    pub(crate) display_value: Option<String>,
+}
+
+impl ChoiceOption {
+    /// Create a new option with the value of the field when it is chosen.
+    pub fn new(export_value: String) -> Self {
+        Self {
+            export_value,
+            display_value: None,
+        }
+    }
+
+    /// Set the text that is displayed for the option, if it should differ from
+    /// its export value.
+    pub fn with_display_value(self, display_value: String) -> Self {
+        Self {
+            display_value: Some(display_value),
+            ..self
+        }
+    }
+
+    fn display(&self) -> &str {
+        self.display_value.as_deref().unwrap_or(&self.export_value)
+    }
+}
+
+/// The options of a choice field, in the order in which they are displayed.
+fn ordered_options(options: &[ChoiceOption], sort: bool) -> Vec<&ChoiceOption> {
+    let mut options = options.iter().collect::<Vec<_>>();
+
+    // Viewers don't sort the options themselves, the flag only records that
+    // they were sorted when creating the document.
+    if sort {
+        options.sort_by(|a, b| a.display().cmp(b.display()));
+    }
+
+    options
+}
+
+fn write_options(annotation: &mut pdf_writer::writers::Annotation, options: &[&ChoiceOption]) {
+    let mut array = annotation.insert(Name(b"Opt")).array();
+
+    for option in options {
+        match &option.display_value {
+            Some(display_value) => {
+                array
+                    .push()
+                    .array()
+                    .item(TextStr(&option.export_value))
+                    .item(TextStr(display_value));
+            }
+            None => {
+                array.item(TextStr(&option.export_value));
+            }
+        }
+    }
+
+    array.finish();
+}
+
+fn choice_flags(field_flags: i32, sort: bool, commit_on_change: bool) -> i32 {
+    let mut flags = field_flags;
+
+    if sort {
+        flags |= SORT;
+    }
+
+    if commit_on_change {
+        flags |= COMMIT_ON_SEL_CHANGE;
+    }
+
+    flags
+}
+
+/// A combo box, which shows its options in a drop-down list.
+pub struct ComboBox {
+    pub(crate) name: String,
+    pub(crate) rect: Rect,
+    pub(crate) font: Font,
+    pub(crate) font_size: f32,
+    pub(crate) options: Vec<ChoiceOption>,
+    pub(crate) value: Option<String>,
+    pub(crate) editable: bool,
+    pub(crate) sort: bool,
+    pub(crate) commit_on_change: bool,
+    pub(crate) read_only: bool,
+    pub(crate) required: bool,
+}
+
+impl ComboBox {
+    /// Create a new combo box.
+    ///
+    /// `name`: The name of the field, which must be unique within the document.
+    /// `rect`: The bounding box of the field on the page.
+    /// `font`: The font that should be used to display the text of the field.
+    /// `font_size`: The font size that should be used to display the text of the field.
+    /// `options`: The options the user can choose from.
+    pub fn new(
+        name: String,
+        rect: Rect,
+        font: Font,
+        font_size: f32,
+        options: Vec<ChoiceOption>,
+    ) -> Self {
+        Self {
+            name,
+            rect,
+            font,
+            font_size,
+            options,
+            value: None,
+            editable: false,
+            sort: false,
+            commit_on_change: false,
+            read_only: false,
+            required: false,
+        }
+    }
+
+    /// Set the export value of the option the field is initialized with, and which
+    /// it is reset to when resetting the form. If the combo box is editable, this can
+    /// also be a custom value.
+    pub fn with_value(self, value: String) -> Self {
+        Self {
+            value: Some(value),
+            ..self
+        }
+    }
+
+    /// Set whether the user can enter a custom value in addition to choosing one
+    /// of the options.
+    pub fn with_editable(self, editable: bool) -> Self {
+        Self { editable, ..self }
+    }
+
+    /// Set whether the options should be sorted alphabetically by their displayed text.
+    pub fn with_sort(self, sort: bool) -> Self {
+        Self { sort, ..self }
+    }
+
+    /// Set whether the new value is committed as soon as the user chooses an option,
+    /// instead of when the user leaves the field.
+    pub fn with_commit_on_change(self, commit_on_change: bool) -> Self {
+        Self {
+            commit_on_change,
+            ..self
+        }
+    }
+
+    /// Set whether the user is prevented from changing the value of the field.
+    pub fn with_read_only(self, read_only: bool) -> Self {
+        Self { read_only, ..self }
+    }
+
+    /// Set whether the field must have a value when the form is submitted.
+    pub fn with_required(self, required: bool) -> Self {
+        Self { required, ..self }
+    }
+
+    /// The text that is displayed for the current value.
+    fn display(&self) -> Option<&str> {
+        let value = self.value.as_deref()?;
+
+        Some(
+            self.options
+                .iter()
+                .find(|o| o.export_value == value)
+                .map(ChoiceOption::display)
+                .unwrap_or(value),
+        )
+    }
+
+    fn register_dependencies(
+        &self,
+        sc: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+    ) -> Dependencies {
+        register_text_appearance(
+            sc,
+            chunk_container,
+            self.rect,
+            &self.font,
+            self.font_size,
+            true,
+            |surface| {
+                if let Some(text) = self.display() {
+                    let (ascent, line_height) = line_metrics(&self.font, self.font_size);
+                    let baseline = (self.rect.height() - line_height) / 2.0 + ascent;
+                    draw_line(surface, &self.font, self.font_size, text, PADDING, baseline);
+                }
+            },
+        )
+    }
+
+    fn serialize_type(
+        &self,
+        annotation: &mut pdf_writer::writers::Annotation,
+        dependencies: &Dependencies,
+        page_height: f32,
+    ) {
+        write_rect(annotation, self.rect, page_height);
+
+        if let Some(appearance) = dependencies.appearance {
+            write_appearance(annotation, appearance);
+        }
+
+        annotation.pair(Name(b"FT"), Name(b"Ch"));
+        annotation.pair(Name(b"T"), TextStr(&self.name));
+
+        let mut flags = choice_flags(
+            field_flags(self.read_only, self.required),
+            self.sort,
+            self.commit_on_change,
+        ) | COMBO;
+
+        if self.editable {
+            flags |= EDIT;
+        }
+
+        annotation.pair(Name(b"Ff"), flags);
+        write_options(annotation, &ordered_options(&self.options, self.sort));
+
+        if let Some(value) = &self.value {
+            annotation.pair(Name(b"V"), TextStr(value));
+            annotation.pair(Name(b"DV"), TextStr(value));
+        }
+
+        if let Some(default_appearance) = &dependencies.default_appearance {
+            annotation.pair(Name(b"DA"), Str(default_appearance.as_bytes()));
+        }
+    }
+}
+
+/// A list box, which shows its options in a scrollable list.
+pub struct ListBox {
+    pub(crate) name: String,
+    pub(crate) rect: Rect,
+    pub(crate) font: Font,
+    pub(crate) font_size: f32,
+    pub(crate) options: Vec<ChoiceOption>,
+    pub(crate) selected: Vec<String>,
+    pub(crate) multi_select: bool,
+    pub(crate) sort: bool,
+    pub(crate) commit_on_change: bool,
+    pub(crate) read_only: bool,
+    pub(crate) required: bool,
+}
+
+impl ListBox {
+    /// Create a new list box.
+    ///
+    /// `name`: The name of the field, which must be unique within the document.
+    /// `rect`: The bounding box of the field on the page.
+    /// `font`: The font that should be used to display the text of the field.
+    /// `font_size`: The font size that should be used to display the text of the field.
+    /// `options`: The options the user can choose from.
+    pub fn new(
+        name: String,
+        rect: Rect,
+        font: Font,
+        font_size: f32,
+        options: Vec<ChoiceOption>,
+    ) -> Self {
+        Self {
+            name,
+            rect,
+            font,
+            font_size,
+            options,
+            selected: vec![],
+            multi_select: false,
+            sort: false,
+            commit_on_change: false,
+            read_only: false,
+            required: false,
+        }
+    }
+
+    /// Set the export values of the options that are initially selected, and which
+    /// the field is reset to when resetting the form. Unless multiple options can be
+    /// selected, only the first value is used.
+    pub fn with_selected(self, selected: Vec<String>) -> Self {
+        Self { selected, ..self }
+    }
+
+    /// Set whether the user can select multiple options at once.
+    pub fn with_multi_select(self, multi_select: bool) -> Self {
+        Self {
+            multi_select,
+            ..self
+        }
+    }
+
+    /// Set whether the options should be sorted alphabetically by their displayed text.
+    pub fn with_sort(self, sort: bool) -> Self {
+        Self { sort, ..self }
+    }
+
+    /// Set whether the new value is committed as soon as the user selects an option,
+    /// instead of when the user leaves the field.
+    pub fn with_commit_on_change(self, commit_on_change: bool) -> Self {
+        Self {
+            commit_on_change,
+            ..self
+        }
+    }
+
+    /// Set whether the user is prevented from changing the value of the field.
+    pub fn with_read_only(self, read_only: bool) -> Self {
+        Self { read_only, ..self }
+    }
+
+    /// Set whether the field must have a value when the form is submitted.
+    pub fn with_required(self, required: bool) -> Self {
+        Self { required, ..self }
+    }
+
+    fn selected(&self) -> &[String] {
+        if self.multi_select {
+            &self.selected
+        } else {
+            &self.selected[..self.selected.len().min(1)]
+        }
+    }
+
+    fn is_selected(&self, option: &ChoiceOption) -> bool {
+        self.selected().contains(&option.export_value)
+    }
+
+    fn register_dependencies(
+        &self,
+        sc: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+    ) -> Dependencies {
+        register_text_appearance(
+            sc,
+            chunk_container,
+            self.rect,
+            &self.font,
+            self.font_size,
+            true,
+            |surface| self.draw_appearance(surface),
+        )
+    }
+
+    fn draw_appearance(&self, surface: &mut Surface) {
+        let (ascent, line_height) = line_metrics(&self.font, self.font_size);
+        let mut top = 0.0;
+
+        for option in ordered_options(&self.options, self.sort) {
+            if top >= self.rect.height() {
+                break;
+            }
+
+            if self.is_selected(option) {
+                // The same highlight color that is commonly used by viewers.
+                let highlight = Fill {
+                    paint: rgb::Color::new(153, 193, 218).into(),
+                    ..Fill::default()
+                };
+
+                let mut builder = PathBuilder::new();
+                if let Some(rect) = Rect::from_xywh(0.0, top, self.rect.width(), line_height) {
+                    builder.push_rect(rect);
+                }
+
+                if let Some(path) = builder.finish() {
+                    surface.set_stroke(None);
+                    surface.set_fill(Some(highlight));
+                    surface.draw_path(&path);
+                    surface.set_fill(Some(Fill::default()));
+                }
+            }
+
+            draw_line(
+                surface,
+                &self.font,
+                self.font_size,
+                option.display(),
+                PADDING,
+                top + ascent,
+            );
+            top += line_height;
+        }
+    }
+
+    fn serialize_type(
+        &self,
+        annotation: &mut pdf_writer::writers::Annotation,
+        dependencies: &Dependencies,
+        page_height: f32,
+    ) {
+        write_rect(annotation, self.rect, page_height);
+
+        if let Some(appearance) = dependencies.appearance {
+            write_appearance(annotation, appearance);
+        }
+
+        annotation.pair(Name(b"FT"), Name(b"Ch"));
+        annotation.pair(Name(b"T"), TextStr(&self.name));
+
+        let mut flags = choice_flags(
+            field_flags(self.read_only, self.required),
+            self.sort,
+            self.commit_on_change,
+        );
+
+        if self.multi_select {
+            flags |= MULTI_SELECT;
+        }
+
+        if flags != 0 {
+            annotation.pair(Name(b"Ff"), flags);
+        }
+
+        let options = ordered_options(&self.options, self.sort);
+        write_options(annotation, &options);
+
+        match self.selected() {
+            [] => {}
+            [value] => {
+                annotation.pair(Name(b"V"), TextStr(value));
+                annotation.pair(Name(b"DV"), TextStr(value));
+            }
+            values => {
+                for key in [Name(b"V"), Name(b"DV")] {
+                    annotation
+                        .insert(key)
+                        .array()
+                        .items(values.iter().map(|v| TextStr(v)));
+                }
+            }
+        }
+
+        // The indices of the selected options, which are needed to distinguish
+        // between options with the same export value.
+        let indices = options
+            .iter()
+            .enumerate()
+            .filter(|(_, o)| self.is_selected(o))
+            .map(|(i, _)| i as i32)
+            .collect::<Vec<_>>();
+
+        if !indices.is_empty() {
+            annotation.insert(Name(b"I")).array().items(indices);
+        }
+
+        if let Some(default_appearance) = &dependencies.default_appearance {
+            annotation.pair(Name(b"DA"), Str(default_appearance.as_bytes()));
+        }
+    }
+}
+
+/// A push button, which triggers an action when clicked.
+pub struct PushButton {
+    pub(crate) name: String,
+    pub(crate) rect: Rect,
+    pub(crate) action: Action,
+    pub(crate) caption: Option<(String, Font, f32)>,
+}
+
+impl PushButton {
+    /// Create a new push button without a caption.
+    ///
+    /// `name`: The name of the field, which must be unique within the document.
+    /// `rect`: The bounding box of the button on the page.
+    /// `action`: The action that is triggered when the button is clicked.
+    pub fn new(name: String, rect: Rect, action: Action) -> Self {
+        Self {
+            name,
+            rect,
+            action,
+            caption: None,
+        }
+    }
+
+    /// Set the caption of the button, which is displayed centered on the button
+    /// in the given font and font size.
+    pub fn with_caption(self, caption: String, font: Font, font_size: f32) -> Self {
+        Self {
+            caption: Some((caption, font, font_size)),
+            ..self
+        }
+    }
+
+    fn register_dependencies(
+        &self,
+        sc: &mut SerializeContext,
+        chunk_container: &mut ChunkContainer,
+    ) -> Dependencies {
+        match &self.caption {
+            Some((caption, font, font_size)) => register_text_appearance(
+                sc,
+                chunk_container,
+                self.rect,
+                font,
+                *font_size,
+                false,
+                |surface| {
+                    self.draw_background(surface);
+
+                    let (ascent, line_height) = line_metrics(font, *font_size);
+                    let (_, advance) = layout(font, *font_size, caption);
+                    let x = TextAlignment::Center.offset(self.rect.width(), advance);
+                    let baseline = (self.rect.height() - line_height) / 2.0 + ascent;
+                    draw_line(surface, font, *font_size, caption, x, baseline);
+                },
+            ),
+            None => {
+                let mut stream_builder = StreamBuilder::new(sc, chunk_container);
+                let mut surface = stream_builder.surface();
+                self.draw_background(&mut surface);
+                surface.finish();
+                let appearance = stream_builder.finish();
+
+                Dependencies {
+                    appearance: Some(register_appearance(
+                        sc,
+                        chunk_container,
+                        &appearance,
+                        self.rect,
+                    )),
+                    ..Dependencies::default()
+                }
+            }
+        }
+    }
+
+    fn draw_background(&self, surface: &mut Surface) {
+        let mut builder = PathBuilder::new();
+        if let Some(rect) = Rect::from_xywh(0.0, 0.0, self.rect.width(), self.rect.height()) {
+            builder.push_rect(rect);
+        }
+
+        if let Some(path) = builder.finish() {
+            surface.set_fill(Some(Fill {
+                paint: luma::Color::new(BUTTON_BACKGROUND).into(),
+                ..Fill::default()
+            }));
+            surface.draw_path(&path);
+            surface.set_fill(Some(Fill::default()));
+        }
+    }
+
+    fn serialize_type(
+        &self,
+        sc: &mut SerializeContext,
+        annotation: &mut pdf_writer::writers::Annotation,
+        dependencies: &Dependencies,
+        page_height: f32,
+        location: Option<Location>,
+    ) -> KrillaResult<()> {
+        write_rect(annotation, self.rect, page_height);
+
+        if let Some(appearance) = dependencies.appearance {
+            write_appearance(annotation, appearance);
+        }
+
+        annotation.pair(Name(b"FT"), Name(b"Btn"));
+        annotation.pair(Name(b"T"), TextStr(&self.name));
+        annotation.pair(Name(b"Ff"), PUSH_BUTTON);
+
+        let mut appearance_characteristics = annotation.insert(Name(b"MK")).dict();
+        appearance_characteristics
+            .insert(Name(b"BG"))
+            .array()
+            .item(BUTTON_BACKGROUND as f32 / 255.0);
+
+        if let Some((caption, ..)) = &self.caption {
+            appearance_characteristics.pair(Name(b"CA"), TextStr(caption));
+        }
+
+        appearance_characteristics.finish();
+
+        if let Some(default_appearance) = &dependencies.default_appearance {
+            annotation.pair(Name(b"DA"), Str(default_appearance.as_bytes()));
+        }
+
+        self.action.serialize(sc, annotation.action(), location)
+    }
+}
+
+/// A radio group whose field is written once all of its buttons have been serialized.
+struct RadioField {
+    group: RadioGroup,
+    root_ref: Ref,
+    kids: Vec<Ref>,
+    /// The tooltip of the field, taken from the first button with an alt text.
+    tooltip: Option<String>,
+}
+
+fn field_flags(read_only: bool, required: bool) -> i32 {
+    let mut flags = 0;
+
+    if read_only {
+        flags |= READ_ONLY;
+    }
+
+    if required {
+        flags |= REQUIRED;
+    }
+
+    flags
+}
+
+/// The interactive form of the document.
+///
+/// Populated while serializing the widgets of each page, and written to the
+/// document catalog in the very end.
+#[derive(Default)]
+pub(crate) struct AcroForm {
+    /// The root fields of the form.
+    fields: Vec<Ref>,
+    /// The radio groups of the form, by name.
+    radio_fields: IndexMap<String, RadioField>,
+    /// The fonts that can be referenced in the default appearance of fields.
+    fonts: IndexSet<Font>,
+}
+
+impl AcroForm {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub(crate) fn fields(&self) -> &[Ref] {
+        &self.fields
+    }
+
+    pub(crate) fn register_field(&mut self, field: Ref) {
+        self.fields.push(field);
+    }
+
+    /// Register the widget of a radio button and return the reference of the field
+    /// of its group, which is allocated using `new_ref` if the group is new.
+    ///
+    /// `tooltip` is the alt text of the button, which is also used for the field.
+    pub(crate) fn register_radio_button(
+        &mut self,
+        group: &RadioGroup,
+        widget: Ref,
+        tooltip: Option<&str>,
+        new_ref: impl FnOnce() -> Ref,
+    ) -> Ref {
+        let field = self
+            .radio_fields
+            .entry(group.name.clone())
+            .or_insert_with(|| {
+                let root_ref = new_ref();
+                self.fields.push(root_ref);
+
+                RadioField {
+                    group: group.clone(),
+                    root_ref,
+                    kids: vec![],
+                    tooltip: None,
+                }
+            });
+        field.kids.push(widget);
+
+        if field.tooltip.is_none() {
+            field.tooltip = tooltip.filter(|t| !t.is_empty()).map(str::to_string);
+        }
+
+        field.root_ref
+    }
+
+    /// Write the fields of all radio groups.
+    pub(crate) fn serialize_radio_fields(&self, chunk: &mut Chunk) {
+        for field in self.radio_fields.values() {
+            field
+                .group
+                .serialize(chunk, field.root_ref, &field.kids, field.tooltip.as_deref());
+        }
+    }
+
+    /// Register a font for the default resources of the form and return its name.
+    pub(crate) fn register_font(&mut self, font: Font) -> String {
+        let (index, _) = self.fonts.insert_full(font);
+        font_name(index)
+    }
+
+    /// The fonts of the default resources of the form, alongside their names.
+    pub(crate) fn fonts(&self) -> impl Iterator<Item = (String, &Font)> {
+        self.fonts
+            .iter()
+            .enumerate()
+            .map(|(index, font)| (font_name(index), font))
+    }
+}
+
+fn font_name(index: usize) -> String {
+    format!("f{index}")
+}
+
+/// Register the appearance of a field that displays text, as well as the font
+/// for its default appearance string.
+///
+/// If `variable_text` is set, the content is marked as variable text.
+fn register_text_appearance(
+    sc: &mut SerializeContext,
+    chunk_container: &mut ChunkContainer,
+    rect: Rect,
+    font: &Font,
+    font_size: f32,
+    variable_text: bool,
+    draw: impl FnOnce(&mut Surface),
+) -> Dependencies {
+    let mut stream_builder = StreamBuilder::new(sc, chunk_container);
+    let mut surface = stream_builder.surface();
+    draw(&mut surface);
+    surface.finish();
+    let mut appearance = stream_builder.finish();
+
+    if variable_text {
+        appearance = mark_variable_text(appearance);
+    }
+
+    let default_appearance = sc
+        .register_form_font(font.clone())
+        .map(|name| format!("/{} {} Tf 0 g", name, font_size));
+
+    Dependencies {
+        appearance: Some(register_appearance(sc, chunk_container, &appearance, rect)),
+        default_appearance,
+        ..Dependencies::default()
+    }
+}
+
+/// Mark the content of an appearance stream as variable text, so that viewers
+/// know which part to replace when regenerating the appearance.
+fn mark_variable_text(mut stream: Stream) -> Stream {
+    let mut content = b"/Tx BMC\n".to_vec();
+    content.extend_from_slice(&stream.content);
+    content.extend_from_slice(b"\nEMC");
+    stream.content = content;
+
+    stream
+}
+
+/// Lay out a single line of text without shaping, in the same way viewers do when
+/// rendering the text of form fields. Returns the glyphs and the width of the line.
+fn layout(font: &Font, font_size: f32, text: &str) -> (Vec<KrillaGlyph>, f32) {
+    let mut glyphs = vec![];
+    let mut width = 0.0;
+
+    for (index, c) in text.char_indices() {
+        let glyph_id = font.glyph_id(c).unwrap_or(GlyphId::new(0));
+        let x_advance = font.advance_width(glyph_id).unwrap_or(0.0) / font.units_per_em();
+        width += x_advance * font_size;
+        glyphs.push(KrillaGlyph::new(
+            glyph_id,
+            x_advance,
+            0.0,
+            0.0,
+            0.0,
+            index..index + c.len_utf8(),
+            None,
+        ));
+    }
+
+    (glyphs, width)
+}
+
+/// The ascent and the line height of a font at the given size.
+fn line_metrics(font: &Font, font_size: f32) -> (f32, f32) {
+    let units_per_em = font.units_per_em();
+    let ascent = font.ascent() / units_per_em * font_size;
+    let descent = font.descent() / units_per_em * font_size;
+
+    (ascent, ascent - descent)
+}
+
+/// Draw a single line of text with its baseline starting at the given position.
+fn draw_line(
+    surface: &mut Surface,
+    font: &Font,
+    font_size: f32,
+    text: &str,
+    x: f32,
+    baseline: f32,
+) {
+    let (glyphs, _) = layout(font, font_size, text);
+    surface.draw_glyphs(
+        Point::from_xy(x, baseline),
+        &glyphs,
+        font.clone(),
+        text,
+        font_size,
+        false,
+    );
+}
+
+/// Break text into lines at explicit line breaks and at spaces so that no line
+/// exceeds the given width. Words that are wider than a line on their own are
+/// broken between characters.
+fn wrap<'a>(font: &Font, font_size: f32, text: &'a str, max_width: f32) -> Vec<&'a str> {
+    let mut lines = vec![];
+
+    for paragraph in text.split('\n') {
+        let mut start = 0;
+        let mut width = 0.0;
+        // The byte index of the last space in the current line, as well as the
+        // width of the line after it.
+        let mut last_space: Option<(usize, f32)> = None;
+
+        for (index, c) in paragraph.char_indices() {
+            let glyph_id = font.glyph_id(c).unwrap_or(GlyphId::new(0));
+            let advance =
+                font.advance_width(glyph_id).unwrap_or(0.0) / font.units_per_em() * font_size;
+
+            if width + advance > max_width && index > start {
+                if c == ' ' {
+                    lines.push(&paragraph[start..index]);
+                    start = index + 1;
+                    width = 0.0;
+                    last_space = None;
+                    continue;
+                }
+
+                match last_space.take().filter(|(space, _)| *space > start) {
+                    Some((space, rest)) => {
+                        lines.push(&paragraph[start..space]);
+                        start = space + 1;
+                        width = rest;
+                    }
+                    None => {
+                        lines.push(&paragraph[start..index]);
+                        start = index;
+                        width = 0.0;
+                    }
+                }
+
+                // The rest of the line after a space can still be too wide, in which
+                // case the word needs to be broken up as well.
+                if width + advance > max_width && index > start {
+                    lines.push(&paragraph[start..index]);
+                    start = index;
+                    width = 0.0;
+                }
+            }
+
+            width += advance;
+
+            if c == ' ' {
+                last_space = Some((index, 0.0));
+            } else if let Some((_, rest)) = &mut last_space {
+                *rest += advance;
+            }
+        }
+
+        lines.push(&paragraph[start..]);
+    }
+
+    lines
+}
@@ -1,3 +1,4 @@
 pub mod action;
 pub mod annotation;
 pub mod destination;
+pub mod form;
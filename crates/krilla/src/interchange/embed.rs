@@ -179,6 +179,10 @@ impl MimeType {
             None
         }
     }
+
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
 fn valid_mime_type(mime_type: &str) -> bool {
@@ -254,7 +254,7 @@ impl Metadata {
             return;
         }
 
-        if self.has_document_info() {
+        if self.has_document_info() || config.validators().requires_info_dict() {
             let ref_ = ref_.bump();
             let mut document_info = LazyCell::new(|| pdf.document_info(ref_));
 
@@ -292,6 +292,10 @@ impl Metadata {
                 document_info.modified_date(pdf_date(date_time));
                 document_info.creation_date(pdf_date(date_time));
             }
+
+            if config.validators().requires_info_dict() {
+                config.validators().write_document_info(&mut document_info);
+            }
         }
     }
 }
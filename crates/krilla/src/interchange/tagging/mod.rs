@@ -1085,6 +1085,7 @@ impl TagGroup {
         serialize_children(
             sc,
             elem_ref,
+            matches!(self.tag, TagKind::Form(_)),
             children_refs,
             parent_tree_map,
             &mut struct_elem,
@@ -1198,6 +1199,7 @@ impl TagTree {
         serialize_children(
             sc,
             root_ref,
+            false,
             children_refs,
             parent_tree_map,
             &mut struct_elem,
@@ -1219,9 +1221,14 @@ impl TagTree {
     }
 }
 
+/// Write the children of a structure element.
+///
+/// `is_form` indicates whether the structure element is a `Form` element, which
+/// PDF/UA requires as the parent of each form field.
 fn serialize_children(
     sc: &mut SerializeContext,
     parent_ref: Ref,
+    is_form: bool,
     children_refs: Vec<Reference>,
     parent_tree_map: &mut HashMap<IdentifierType, Ref>,
     struct_elem: &mut StructElement,
@@ -1269,6 +1276,14 @@ fn serialize_children(
                     }
                 }
                 IdentifierType::AnnotationIdentifier(ai) => {
+                    if !is_form {
+                        if let Some(&location) = sc.global_objects.tagged_form_fields.get(&ai) {
+                            sc.register_validation_error(ValidationError::UntaggedFormField(
+                                location,
+                            ));
+                        }
+                    }
+
                     let Some(page_info) = sc.page_infos_mut().get_mut(ai.page_index) else {
                         panic!(
                             "tag tree contains identifier from page {}, but document only has {} pages",
@@ -402,6 +402,30 @@ impl From<NaiveRgbColor> for crate::graphics::color::rgb::Color {
     }
 }
 
+impl From<crate::graphics::color::rgb::Color> for NaiveRgbColor {
+    fn from(color: crate::graphics::color::rgb::Color) -> Self {
+        Self::new(color.red(), color.green(), color.blue())
+    }
+}
+
+impl From<crate::graphics::color::lab::Color> for NaiveRgbColor {
+    fn from(color: crate::graphics::color::lab::Color) -> Self {
+        color.to_rgb().into()
+    }
+}
+
+impl From<crate::graphics::color::cal_rgb::Color> for NaiveRgbColor {
+    fn from(color: crate::graphics::color::cal_rgb::Color) -> Self {
+        color.to_rgb().into()
+    }
+}
+
+impl From<crate::graphics::color::cal_gray::Color> for NaiveRgbColor {
+    fn from(color: crate::graphics::color::cal_gray::Color) -> Self {
+        color.to_rgb().into()
+    }
+}
+
 impl From<NaiveRgbColor> for [f32; 3] {
     fn from(color: NaiveRgbColor) -> Self {
         color.into_f32_array()
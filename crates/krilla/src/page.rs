@@ -6,7 +6,7 @@ use std::ops::DerefMut;
 
 use pdf_writer::types::TabOrder;
 use pdf_writer::writers::NumberTree;
-use pdf_writer::{Chunk, Finish, Ref, TextStr};
+use pdf_writer::{Chunk, Finish, Name, Ref, TextStr};
 
 use crate::chunk_container::ChunkContainer;
 use crate::configure::validate::VersionedFeature;
@@ -14,7 +14,8 @@ use crate::configure::ValidationError;
 use crate::content::ContentBuilder;
 use crate::error::KrillaResult;
 use crate::geom::{Rect, Size, Transform};
-use crate::interactive::annotation::Annotation;
+use crate::graphics::icc::OutputIntent;
+use crate::interactive::annotation::{Annotation, AnnotationType};
 use crate::interchange::tagging::{Identifier, PageTagIdentifier};
 use crate::resource::ResourceDictionary;
 use crate::serialize::{PageInfo, SerializeContext};
@@ -40,6 +41,8 @@ pub struct PageSettings {
     trim_box: Option<Rect>,
     /// The actual content boundaries
     art_box: Option<Rect>,
+    /// The output intents of the page
+    output_intents: Vec<OutputIntent>,
 }
 
 impl PageSettings {
@@ -158,6 +161,23 @@ impl PageSettings {
     pub(crate) fn art_box(&self) -> Option<Rect> {
         self.art_box
     }
+
+    /// Change the output intents.
+    ///
+    /// Page-level output intents override the output intents of the document
+    /// for this page. They are only supported starting with PDF 2.0 and will be
+    /// ignored for earlier versions. In this case, a
+    /// [`ValidationError::RequiresNewerPdfVersion`] is registered, which is
+    /// only reported if one of the validators prohibits it.
+    pub fn with_output_intents(mut self, output_intents: Vec<OutputIntent>) -> PageSettings {
+        self.output_intents = output_intents;
+        self
+    }
+
+    /// The current output intents.
+    pub(crate) fn output_intents(&self) -> &[OutputIntent] {
+        &self.output_intents
+    }
 }
 
 impl Default for PageSettings {
@@ -174,6 +194,7 @@ impl Default for PageSettings {
             bleed_box: None,
             trim_box: None,
             art_box: None,
+            output_intents: vec![],
         }
     }
 }
@@ -230,6 +251,15 @@ impl<'a> Page<'a> {
         let ai = AnnotationIdentifier::new(self.page_index, annot_index);
         let struct_parent = self.sc.register_annotation_parent(ai);
         annotation.struct_parent = struct_parent;
+
+        if struct_parent.is_some()
+            && matches!(annotation.annotation_type, AnnotationType::Widget(_))
+        {
+            self.sc
+                .global_objects
+                .tagged_form_fields
+                .insert(ai, annotation.location);
+        }
         self.add_annotation(annotation);
 
         match struct_parent {
@@ -388,9 +418,23 @@ impl InternalPage {
         let mut annotation_refs = vec![];
 
         if !self.annotations.is_empty() {
+            // PDF/X only allows trap network and printer's mark annotations, which
+            // krilla doesn't support, within the printed area of the page.
+            let printed_area = self
+                .page_settings
+                .bleed_box()
+                .or(self.page_settings.crop_box())
+                .unwrap_or(self.page_settings.media_box().unwrap_or(self.bbox));
+
             for annotation in &self.annotations {
                 let annot_ref = sc.new_ref();
 
+                if annotation.annotation_type.rect().intersects(&printed_area) {
+                    sc.register_validation_error(ValidationError::AnnotationInBleedBox(
+                        annotation.location,
+                    ));
+                }
+
                 annotation.serialize(
                     sc,
                     chunk_container,
@@ -401,6 +445,25 @@ impl InternalPage {
             }
         }
 
+        let output_intents = if sc.serialize_settings().pdf_version()
+            >= VersionedFeature::PageOutputIntents.minimum_pdf_version()
+        {
+            self.page_settings
+                .output_intents()
+                .iter()
+                .map(|oi| sc.register_output_intent(chunk_container, oi.clone()))
+                .collect()
+        } else {
+            if !self.page_settings.output_intents().is_empty() {
+                sc.register_validation_error(ValidationError::RequiresNewerPdfVersion(
+                    VersionedFeature::PageOutputIntents,
+                    None,
+                ));
+            }
+
+            vec![]
+        };
+
         let chunk = &mut chunk_container.non_stream.pages;
         let mut page = chunk.page(root_ref);
         self.stream_resources.to_pdf_resources(
@@ -420,6 +483,10 @@ impl InternalPage {
         let media_box = transform_rect(self.page_settings.media_box().unwrap_or(self.bbox));
         page.media_box(media_box.to_pdf_rect());
 
+        if self.page_settings.trim_box().is_none() && self.page_settings.art_box().is_none() {
+            sc.register_validation_error(ValidationError::MissingTrimBox(self.page_index));
+        }
+
         // the remaining type of box are not mandatory, so we only set them if they are present
         if let Some(crop_box) = self.page_settings.crop_box() {
             let crop_box = transform_rect(crop_box);
@@ -441,6 +508,12 @@ impl InternalPage {
             page.art_box(art_box.to_pdf_rect());
         }
 
+        if !output_intents.is_empty() {
+            page.insert(Name(b"OutputIntents"))
+                .array()
+                .items(output_intents);
+        }
+
         if let Some(struct_parent) = self.struct_parent {
             page.struct_parents(struct_parent);
         }
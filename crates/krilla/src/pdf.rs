@@ -181,7 +181,7 @@ impl PdfSerializerContext {
             // future, but it keeps the code simpler.
             let xobject_group_color_space = sc.register_colorspace(
                 chunk_container,
-                rgb::color_space(sc.serialize_settings().no_device_cs).into(),
+                rgb::color_space(&sc.serialize_settings()).into(),
             );
             let chunk_settings = sc.chunk_settings();
 
@@ -7,7 +7,7 @@ use std::sync::Arc;
 
 use indexmap::IndexMap;
 use pdf_writer::types::{StructRole, StructRole2};
-use pdf_writer::writers::{OutputIntent, StructTreeRoot};
+use pdf_writer::writers::StructTreeRoot;
 use pdf_writer::{Chunk, Content, Finish, Limits, Name, Pdf, Ref, Settings, Str, TextStr};
 
 use crate::chunk_container::ChunkContainer;
@@ -17,11 +17,13 @@ use crate::configure::{Configuration, PdfVersion, ValidationError, Validators};
 use crate::error::{KrillaError, KrillaResult, LimitError};
 use crate::geom::Size;
 use crate::graphics::color::{rgb, ColorSpace};
-use crate::graphics::icc::{ICCBasedColorSpace, ICCProfile};
+use crate::graphics::devicen::DeviceNColorSpace;
+use crate::graphics::icc::{ICCBasedColorSpace, ICCProfile, OutputIntent, OutputIntentSubtype};
 #[cfg(feature = "raster-images")]
-use crate::graphics::image::Image;
+use crate::graphics::image::{Image, StencilMask};
 use crate::graphics::separation::SeparationColorSpace;
 use crate::interactive::destination::{NamedDestination, XyzDestination};
+use crate::interactive::form::{AcroForm, RadioGroup};
 use crate::interchange::embed::EmbeddedFile;
 use crate::interchange::metadata::PdfSig;
 use crate::interchange::outline::Outline;
@@ -117,6 +119,24 @@ pub struct SerializeSettings {
     /// just use the default function which doesn't render them at all. If you do want this, it
     /// is recommended that you use the function provided by the `krilla-svg` crate.
     pub render_svg_glyph_fn: RenderSvgGlyphFn,
+    /// If set, the colors of linear, radial and sweep gradients are baked into sampled
+    /// functions with the given number of samples per dimension, instead of being
+    /// written as exponential, stitching or PostScript functions.
+    ///
+    /// Sampled functions are rendered consistently across viewers and never contain
+    /// PostScript code, at the cost of larger files and a loss of precision if the
+    /// number of samples is too low. Note that sweep gradients are sampled in two
+    /// dimensions, so the number of samples is squared for them.
+    pub gradient_samples: Option<u32>,
+    /// The output intents of the document, which describe the output devices or
+    /// production conditions (like a specific printing condition) the document
+    /// is intended for.
+    ///
+    /// If a validator requires an output intent, like PDF/A, and none of the given
+    /// output intents can satisfy it, krilla will automatically add an sRGB output
+    /// intent. Note that PDF/A requires all output intents with a profile to use
+    /// the same profile.
+    pub output_intents: Vec<OutputIntent>,
 }
 
 pub type RenderSvgGlyphFn = fn(&[u8], rgb::Color, GlyphId, (f32, f32), &mut Surface) -> Option<()>;
@@ -149,6 +169,8 @@ impl Default for SerializeSettings {
             configuration: Configuration::default(),
             enable_tagging: true,
             render_svg_glyph_fn: |_, _, _, _, _| None,
+            gradient_samples: None,
+            output_intents: vec![],
         }
     }
 }
@@ -462,8 +484,15 @@ impl SerializeContext {
         self.serialize_destination_profiles(&mut chunk_container);
         self.serialize_page_label_tree(&mut chunk_container);
         self.serialize_outline(&mut chunk_container);
-        self.serialize_fonts(&mut chunk_container)?;
         self.serialize_pages(&mut chunk_container)?;
+        // The fields of radio groups can only be written once all of their
+        // buttons have been serialized.
+        self.global_objects
+            .acro_form
+            .serialize_radio_fields(&mut chunk_container.non_stream.annotations);
+        // Fonts need to be serialized after the pages, since generating the appearance
+        // of form fields can add new glyphs.
+        self.serialize_fonts(&mut chunk_container)?;
         self.serialize_page_tree(&mut chunk_container);
         #[cfg(feature = "pdf")]
         self.serialize_embedded_pdfs(&mut chunk_container)?;
@@ -551,6 +580,38 @@ impl SerializeContext {
         }
     }
 
+    pub(crate) fn register_form_field(&mut self, field: Ref) {
+        self.global_objects.acro_form.register_field(field);
+    }
+
+    /// Register the widget of a radio button and return the reference of the
+    /// field of its group.
+    pub(crate) fn register_radio_button(
+        &mut self,
+        group: &RadioGroup,
+        widget: Ref,
+        tooltip: Option<&str>,
+    ) -> Ref {
+        let cur_ref = &mut self.cur_ref;
+        self.global_objects
+            .acro_form
+            .register_radio_button(group, widget, tooltip, || cur_ref.bump())
+    }
+
+    /// Register a font that is used for the variable text of a form field and return
+    /// its name in the default resources of the form.
+    ///
+    /// Returns `None` if the font cannot be referenced by form fields.
+    pub(crate) fn register_form_font(&mut self, font: Font) -> Option<String> {
+        let font_container = self.register_font_container(font.clone());
+
+        if !font_container.borrow_mut().add_form_glyphs() {
+            return None;
+        }
+
+        Some(self.global_objects.acro_form.register_font(font))
+    }
+
     pub(crate) fn register_named_destination(&mut self, nd: NamedDestination) -> Option<Ref> {
         if let Some((dest_ref, existing)) =
             self.global_objects.named_destinations.get(nd.name.as_ref())
@@ -606,6 +667,21 @@ impl SerializeContext {
         })
     }
 
+    /// Register an output intent, choosing the subtype required by the validators
+    /// if none was set explicitly.
+    pub(crate) fn register_output_intent(
+        &mut self,
+        chunk_container: &mut ChunkContainer,
+        output_intent: OutputIntent,
+    ) -> Ref {
+        let output_intent = match self.serialize_settings.validators().output_intents().next() {
+            Some(subtype) => output_intent.with_default_subtype(subtype),
+            None => output_intent,
+        };
+
+        self.register_cacheable(chunk_container, output_intent)
+    }
+
     pub(crate) fn register_resourceable<T>(
         &mut self,
         chunk_container: &mut ChunkContainer,
@@ -628,6 +704,17 @@ impl SerializeContext {
         })
     }
 
+    #[cfg(feature = "raster-images")]
+    pub(crate) fn register_stencil_mask(
+        &mut self,
+        chunk_container: &mut ChunkContainer,
+        mask: StencilMask,
+    ) -> Ref {
+        self.register_cached(mask, |sc, object, root_ref| {
+            object.serialize(sc, chunk_container, root_ref);
+        })
+    }
+
     pub(crate) fn register_xyz_destination(&mut self, dest: XyzDestination) -> Ref {
         self.register_cached(dest, |sc, dest, root_ref| {
             sc.global_objects.xyz_destinations.push((root_ref, dest));
@@ -660,6 +747,14 @@ impl SerializeContext {
         chunk_container: &mut ChunkContainer,
         cs: ColorSpace,
     ) -> MaybeDeviceColorSpace {
+        if !matches!(
+            cs,
+            ColorSpace::Device(DeviceColorSpace::Gray | DeviceColorSpace::Cmyk)
+                | ColorSpace::Special(_)
+        ) {
+            self.register_validation_error(ValidationError::NonCmykColorSpace(self.location));
+        }
+
         match cs {
             ColorSpace::CieBased(CieBasedColorSpace::Srgb) => {
                 MaybeDeviceColorSpace::ColorSpace(self.register_resourceable(
@@ -676,6 +771,27 @@ impl SerializeContext {
             ColorSpace::CieBased(CieBasedColorSpace::Cmyk(cs)) => {
                 MaybeDeviceColorSpace::ColorSpace(self.register_resourceable(chunk_container, cs))
             }
+            ColorSpace::CieBased(CieBasedColorSpace::Rgb(cs)) => {
+                MaybeDeviceColorSpace::ColorSpace(self.register_resourceable(chunk_container, cs))
+            }
+            ColorSpace::CieBased(CieBasedColorSpace::Gray(cs)) => {
+                MaybeDeviceColorSpace::ColorSpace(self.register_resourceable(chunk_container, cs))
+            }
+            ColorSpace::CieBased(CieBasedColorSpace::Lab(space)) => {
+                MaybeDeviceColorSpace::ColorSpace(
+                    self.register_resourceable(chunk_container, space),
+                )
+            }
+            ColorSpace::CieBased(CieBasedColorSpace::CalRgb(space)) => {
+                MaybeDeviceColorSpace::ColorSpace(
+                    self.register_resourceable(chunk_container, space),
+                )
+            }
+            ColorSpace::CieBased(CieBasedColorSpace::CalGray(space)) => {
+                MaybeDeviceColorSpace::ColorSpace(
+                    self.register_resourceable(chunk_container, space),
+                )
+            }
             ColorSpace::Device(DeviceColorSpace::Gray) => MaybeDeviceColorSpace::DeviceGray,
             ColorSpace::Device(DeviceColorSpace::Rgb) => MaybeDeviceColorSpace::DeviceRgb,
             ColorSpace::Device(DeviceColorSpace::Cmyk) => MaybeDeviceColorSpace::DeviceCMYK,
@@ -684,6 +800,11 @@ impl SerializeContext {
                     self.register_resourceable(chunk_container, SeparationColorSpace::new(s)),
                 )
             }
+            ColorSpace::Special(SpecialColorSpace::DeviceN(s)) => {
+                MaybeDeviceColorSpace::ColorSpace(
+                    self.register_resourceable(chunk_container, DeviceNColorSpace::new(s)),
+                )
+            }
         }
     }
 }
@@ -692,39 +813,82 @@ impl SerializeContext {
 /// All methods are supposed to only be called once in `SerializeContext::finish`!
 impl SerializeContext {
     fn serialize_destination_profiles(&mut self, chunk_container: &mut ChunkContainer) {
-        let validators = self.serialize_settings.validators();
-        chunk_container.non_stream.destination_profiles =
-            validators.output_intent().map(|subtype| {
-                let root_ref = self.new_ref();
-                let mut chunk = self.new_chunk();
-
-                let oi_ref = self.new_ref();
-                let mut oi = chunk.indirect(oi_ref).start::<OutputIntent>();
-                let icc_profile = self.serialize_settings.pdf_version().rgb_icc();
-
-                oi.dest_output_profile(
-                    self.register_cacheable(chunk_container, icc_profile.clone()),
-                )
-                .subtype(subtype)
-                .output_condition_identifier(TextStr("Custom"))
-                .output_condition(TextStr("sRGB"))
-                .registry_name(TextStr(""))
-                .info(TextStr(
-                    format!(
+        let mut output_intents = self.serialize_settings.output_intents.clone();
+        let required = self
+            .serialize_settings
+            .validators()
+            .output_intents()
+            .collect::<Vec<_>>();
+
+        let pdf_version = self.serialize_settings.pdf_version();
+
+        for subtype in required.iter().cloned() {
+            let matches_subtype =
+                |oi: &OutputIntent| oi.subtype().or(required.first()) == Some(&subtype);
+            let has_profile = |oi: &OutputIntent| {
+                oi.profile()
+                    .is_some_and(|p| pdf_version.supports_icc(p.metadata()))
+            };
+
+            // PDF/A requires the output intent to contain a destination profile.
+            let has_required = output_intents.iter().any(|oi| {
+                matches_subtype(oi) && (subtype != OutputIntentSubtype::PdfA || has_profile(oi))
+            });
+
+            if has_required {
+                // Some standards, like PDF/X-4, always require a destination profile.
+                if !output_intents
+                    .iter()
+                    .any(|oi| matches_subtype(oi) && has_profile(oi))
+                {
+                    self.register_validation_error(ValidationError::MissingOutputIntentProfile);
+                }
+
+                continue;
+            }
+
+            match subtype {
+                // Fall back to an sRGB output intent if the user didn't provide one
+                // that satisfies the validator.
+                OutputIntentSubtype::PdfA => {
+                    let icc_profile = self.serialize_settings.pdf_version().rgb_icc();
+                    let info = format!(
                         "sRGB v{}.{}",
                         icc_profile.metadata().major,
                         icc_profile.metadata().minor
-                    )
-                    .as_str(),
-                ));
-                oi.finish();
+                    );
+
+                    output_intents.insert(
+                        0,
+                        OutputIntent::new("Custom")
+                            .with_subtype(subtype)
+                            .with_output_condition("sRGB")
+                            .with_registry_name("")
+                            .with_info(info)
+                            .with_profile(icc_profile),
+                    );
+                }
+                // There is no sensible default for the printing condition.
+                OutputIntentSubtype::PdfX | OutputIntentSubtype::Custom(_) => {
+                    self.register_validation_error(ValidationError::MissingOutputIntent)
+                }
+            }
+        }
+
+        if output_intents.is_empty() {
+            return;
+        }
 
-                let mut array = chunk.indirect(root_ref).array();
-                array.item(oi_ref);
-                array.finish();
+        let oi_refs = output_intents
+            .into_iter()
+            .map(|oi| self.register_output_intent(chunk_container, oi))
+            .collect::<Vec<_>>();
 
-                (root_ref, chunk)
-            });
+        let root_ref = self.new_ref();
+        let mut chunk = self.new_chunk();
+        chunk.indirect(root_ref).array().items(oi_refs);
+
+        chunk_container.non_stream.destination_profiles = Some((root_ref, chunk));
     }
 
     fn serialize_page_label_tree(&mut self, chunk_container: &mut ChunkContainer) {
@@ -772,11 +936,11 @@ impl SerializeContext {
                 }
             }
 
-            if !borrowed.cid_font().is_empty() {
-                let f = self.register_font_identifier(borrowed.cid_font().identifier());
-                borrowed
-                    .cid_font()
-                    .serialize(self, chunk_container, f.get_ref())?;
+            for cid_font in [borrowed.cid_font(), borrowed.vertical_cid_font()] {
+                if !cid_font.is_empty() {
+                    let f = self.register_font_identifier(cid_font.identifier());
+                    cid_font.serialize(self, chunk_container, f.get_ref())?;
+                }
             }
         }
 
@@ -1076,6 +1240,11 @@ pub(crate) struct GlobalObjects {
     /// Stores the association of the names of embedded files to their refs,
     /// for the catalog dictionary.
     pub(crate) embedded_files: MaybeTaken<BTreeMap<String, Ref>>,
+    /// Stores the interactive form of the document.
+    pub(crate) acro_form: MaybeTaken<AcroForm>,
+    /// The tagged widget annotations of form fields, alongside their locations,
+    /// so that their parent in the tag tree can be validated.
+    pub(crate) tagged_form_fields: HashMap<AnnotationIdentifier, Option<Location>>,
     /// A list of custom headings numbers used in the document.
     pub(crate) custom_heading_roles: BTreeSet<NonZeroU16>,
     /// The context tracking all of the pdfs and their pages that have been inserted.
@@ -1093,6 +1262,7 @@ impl GlobalObjects {
         assert!(self.outline.is_taken());
         assert!(self.tag_tree.is_taken());
         assert!(self.embedded_files.is_taken());
+        assert!(self.acro_form.is_taken());
         #[cfg(feature = "pdf")]
         assert!(self.pdf_ctx.is_taken());
     }
@@ -55,6 +55,9 @@ pub struct Stream {
     pub(crate) validation_errors: Vec<ValidationError>,
     pub(crate) resource_dictionary: ResourceDictionary,
     pub(crate) uses_mask: bool,
+    /// Whether the stream was drawn without any colors, so that it can be used
+    /// for uncolored patterns.
+    pub(crate) uncolored: bool,
 }
 
 impl Stream {
@@ -64,6 +67,7 @@ impl Stream {
         validation_errors: Vec<ValidationError>,
         resource_dictionary: ResourceDictionary,
         uses_mask: bool,
+        uncolored: bool,
     ) -> Self {
         Self {
             content,
@@ -71,6 +75,7 @@ impl Stream {
             validation_errors,
             resource_dictionary,
             uses_mask,
+            uncolored,
         }
     }
 
@@ -85,6 +90,7 @@ impl Stream {
             validation_errors: vec![],
             resource_dictionary: ResourceDictionaryBuilder::new().finish(),
             uses_mask: false,
+            uncolored: false,
         }
     }
 }
@@ -125,6 +131,27 @@ impl<'a> StreamBuilder<'a> {
         )
     }
 
+    /// Get a surface of the stream builder that doesn't draw any colors.
+    ///
+    /// All fills and strokes only paint the shape of what is drawn, while their
+    /// paints are ignored. Images and shadings are left out entirely. This is what
+    /// the stream of an [`UncoloredPattern`] needs to be drawn with.
+    ///
+    /// [`UncoloredPattern`]: crate::graphics::paint::UncoloredPattern
+    pub fn uncolored_surface(&mut self) -> Surface<'_> {
+        let finish_fn = Box::new(|stream, _| {
+            self.stream = stream;
+        });
+
+        Surface::new(
+            self.sc,
+            self.chunk_container,
+            ContentBuilder::new(Transform::identity(), true, self.sc).uncolored(true),
+            None,
+            finish_fn,
+        )
+    }
+
     /// Turn the stream builder into a stream.
     pub fn finish(self) -> Stream {
         self.stream
@@ -16,10 +16,11 @@ use crate::geom::Path;
 use crate::geom::Size;
 use crate::geom::{Point, Transform};
 use crate::graphic::Graphic;
-use crate::graphics::blend::BlendMode;
+use crate::graphics::blend::{BlendMode, OverprintMode, TransparencyGroup};
+use crate::graphics::color::RenderingIntent;
 use crate::graphics::graphics_state::ExtGState;
 #[cfg(feature = "raster-images")]
-use crate::graphics::image::Image;
+use crate::graphics::image::{Image, StencilMask};
 use crate::graphics::mask::Mask;
 use crate::graphics::paint::{Fill, FillRule, Stroke};
 use crate::graphics::shading_function::ShadingFunction;
@@ -31,8 +32,7 @@ use crate::pdf::PdfDocument;
 use crate::serialize::SerializeContext;
 use crate::stream::{Stream, StreamBuilder};
 use crate::tagging::ArtifactType;
-use crate::text::Font;
-use crate::text::{draw_glyph, Glyph};
+use crate::text::{draw_glyph, Font, Glyph, TextState, WritingMode};
 #[cfg(feature = "simple-text")]
 use crate::text::{shape::naive_shape, TextDirection};
 
@@ -74,6 +74,9 @@ pub struct Surface<'a> {
     pub(crate) chunk_container: &'a mut ChunkContainer,
     fill: Option<Fill>,
     stroke: Option<Stroke>,
+    invisible_text: bool,
+    text_state: TextState,
+    writing_mode: WritingMode,
     bd: Builders,
     push_instructions: Vec<PushInstruction>,
     page_identifier: Option<PageTagIdentifier>,
@@ -95,6 +98,9 @@ impl<'a> Surface<'a> {
             page_identifier,
             fill: None,
             stroke: None,
+            invisible_text: false,
+            text_state: TextState::default(),
+            writing_mode: WritingMode::default(),
             push_instructions: vec![],
             finish_fn,
         }
@@ -131,6 +137,63 @@ impl<'a> Surface<'a> {
         self.stroke.as_ref()
     }
 
+    /// Set whether text should be drawn invisibly.
+    ///
+    /// Invisible text is not painted, but can still be selected, searched and
+    /// extracted, which is for example useful for placing the result of OCR on top
+    /// of a scanned page. Since it is still regular text, it needs to be tagged
+    /// like any other text when exporting tagged PDFs. The currently active fill
+    /// and stroke, as well as the `outlined` argument of [`Surface::draw_glyphs`],
+    /// are ignored while this is enabled.
+    pub fn set_invisible_text(&mut self, invisible: bool) {
+        self.invisible_text = invisible;
+    }
+
+    /// Get whether text is currently drawn invisibly.
+    pub fn get_invisible_text(&self) -> bool {
+        self.invisible_text
+    }
+
+    /// Set the text state that should be used for drawing text.
+    ///
+    /// It is taken into account both for text drawn as text and for outlined text.
+    /// In [`WritingMode::Vertical`], the text state is ignored and glyphs are
+    /// always placed with the default text state.
+    pub fn set_text_state(&mut self, text_state: TextState) {
+        self.text_state = text_state;
+    }
+
+    /// Get the currently active text state.
+    pub fn get_text_state(&self) -> TextState {
+        self.text_state
+    }
+
+    /// Set the writing mode that should be used for drawing glyphs.
+    ///
+    /// In vertical writing mode, the glyphs need to be positioned for vertical
+    /// layout, i.e. with the vertical advances and offsets a shaper produces for
+    /// top-to-bottom text. They are then embedded with vertical metrics, so that
+    /// PDF viewers can select and extract the text in the right order.
+    ///
+    /// Note that the text state set via [`Surface::set_text_state`] only applies
+    /// to horizontal writing mode, it has no effect on vertical text.
+    pub fn set_writing_mode(&mut self, writing_mode: WritingMode) {
+        self.writing_mode = writing_mode;
+    }
+
+    /// Get the currently active writing mode.
+    pub fn get_writing_mode(&self) -> WritingMode {
+        self.writing_mode
+    }
+
+    /// The text state that applies to the current writing mode.
+    fn effective_text_state(&self) -> TextState {
+        match self.writing_mode {
+            WritingMode::Horizontal => self.text_state,
+            WritingMode::Vertical => TextState::default(),
+        }
+    }
+
     /// Draw a path using the currently active fill and/or stroke.
     pub fn draw_path(&mut self, path: &Path) {
         if self.fill.is_some() || self.stroke.is_some() {
@@ -243,16 +306,19 @@ impl<'a> Surface<'a> {
         start: Point,
         font: Font,
         font_size: f32,
+        text: &str,
     ) {
-        let (mut cur_x, y) = (start.x, start.y);
+        let text_state = self.effective_text_state();
+        let scaling = text_state.horizontal_scaling;
+        let (mut cur_x, mut cur_y) = (start.x, start.y - text_state.rise);
 
         for glyph in glyphs {
             let mut base_transform = tiny_skia_path::Transform::from_translate(
-                cur_x + glyph.x_offset(font_size),
-                y - glyph.y_offset(font_size),
+                cur_x + glyph.x_offset(font_size) * scaling,
+                cur_y - glyph.y_offset(font_size),
             );
             base_transform = base_transform.pre_concat(tiny_skia_path::Transform::from_scale(
-                font_size / font.units_per_em(),
+                scaling * font_size / font.units_per_em(),
                 -font_size / font.units_per_em(),
             ));
             draw_glyph(
@@ -263,7 +329,8 @@ impl<'a> Surface<'a> {
                 self,
             );
 
-            cur_x += glyph.x_advance(font_size);
+            cur_x += text_state.advance(glyph, text, font_size);
+            cur_y -= glyph.y_advance(font_size);
         }
     }
 
@@ -282,8 +349,24 @@ impl<'a> Surface<'a> {
         outlined: bool,
     ) {
         let context_color = self.context_color();
-        if outlined {
-            self.outline_glyphs(glyphs, context_color, start, font, font_size);
+        let text_state = self.effective_text_state();
+        let writing_mode = self.writing_mode;
+
+        if self.invisible_text {
+            self.bd.get_mut().draw_invisible_glyphs(
+                start,
+                self.sc,
+                self.chunk_container,
+                context_color,
+                glyphs,
+                font,
+                text,
+                font_size,
+                text_state,
+                writing_mode,
+            );
+        } else if outlined {
+            self.outline_glyphs(glyphs, context_color, start, font, font_size, text);
         } else {
             match (self.fill.as_ref(), self.stroke.as_ref()) {
                 (Some(f), Some(s)) => {
@@ -301,9 +384,11 @@ impl<'a> Surface<'a> {
                             font.clone(),
                             text,
                             font_size,
+                            text_state,
+                            writing_mode,
                         );
 
-                        self.outline_glyphs(glyphs, context_color, start, font, font_size);
+                        self.outline_glyphs(glyphs, context_color, start, font, font_size, text);
                     } else {
                         self.bd.get_mut().draw_glyphs(
                             start,
@@ -316,6 +401,8 @@ impl<'a> Surface<'a> {
                             font,
                             text,
                             font_size,
+                            text_state,
+                            writing_mode,
                         );
                     }
                 }
@@ -331,6 +418,8 @@ impl<'a> Surface<'a> {
                         font,
                         text,
                         font_size,
+                        text_state,
+                        writing_mode,
                     );
                 }
                 (Some(f), None) => {
@@ -345,6 +434,8 @@ impl<'a> Surface<'a> {
                         font,
                         text,
                         font_size,
+                        text_state,
+                        writing_mode,
                     );
                 }
                 (None, None) => {
@@ -360,6 +451,8 @@ impl<'a> Surface<'a> {
                         font,
                         text,
                         font_size,
+                        text_state,
+                        writing_mode,
                     );
                 }
             }
@@ -376,6 +469,10 @@ impl<'a> Surface<'a> {
     /// - It will only use the single font you provided to draw the text, no font fallback will
     ///   be performed.
     ///
+    /// The text is drawn in the currently active writing mode. In order to lay out
+    /// vertical text, set [`WritingMode::Vertical`] and use [`TextDirection::TopToBottom`],
+    /// in which case the vertical alternates of the glyphs are used as well.
+    ///
     /// If you need more advanced control over how your text looks,
     /// you can use the `fill_glyphs` method.
     #[cfg(feature = "simple-text")]
@@ -388,7 +485,7 @@ impl<'a> Surface<'a> {
         outlined: bool,
         direction: TextDirection,
     ) {
-        let glyphs = naive_shape(text, font.clone(), direction);
+        let glyphs = naive_shape(text, font.clone(), direction, self.writing_mode);
 
         self.draw_glyphs(start, &glyphs, font, text, font_size, outlined);
     }
@@ -422,12 +519,80 @@ impl<'a> Surface<'a> {
         self.bd.get_mut().set_blend_mode(blend_mode.to_pdf());
     }
 
+    /// Push new overprint settings.
+    ///
+    /// `fill` and `stroke` control whether overprinting is enabled for filling
+    /// and stroking (including text), respectively. Overprinting only has an effect
+    /// on devices that produce separations, like printing presses, and is mostly
+    /// useful in combination with CMYK and Separation colors.
+    ///
+    /// Note that PDF/A forbids the [`OverprintMode::Nonzero`] mode when overprinting
+    /// is enabled.
+    pub fn push_overprint(&mut self, fill: bool, stroke: bool, mode: OverprintMode) {
+        self.push_instructions.push(PushInstruction::Overprint);
+        self.bd.get_mut().save_graphics_state();
+        self.bd.get_mut().set_overprint(fill, stroke, mode);
+    }
+
+    /// Push a new rendering intent.
+    ///
+    /// The rendering intent determines how colors are mapped to the color space of
+    /// the output device for all subsequent content. To set the rendering intent of
+    /// an image, use `Image::with_rendering_intent` instead.
+    pub fn push_rendering_intent(&mut self, rendering_intent: RenderingIntent) {
+        self.push_instructions
+            .push(PushInstruction::RenderingIntent);
+        self.bd.get_mut().save_graphics_state();
+        self.bd.get_mut().set_rendering_intent(rendering_intent);
+    }
+
     /// Push a new clip path.
     pub fn push_clip_path(&mut self, path: &Path, clip_rule: &FillRule) {
         self.push_instructions.push(PushInstruction::ClipPath);
         self.bd.get_mut().push_clip_path(&path.0, clip_rule);
     }
 
+    /// Push a new clip path consisting of text.
+    ///
+    /// The glyphs are placed in the same way as in [`Surface::draw_glyphs`], and they are
+    /// also filled and/or stroked with the currently active fill and stroke. If there is
+    /// neither an active fill nor stroke, or text is drawn invisibly, the glyphs are only
+    /// used for clipping. In contrast to converting the outlines of the glyphs into a
+    /// clip path, the text remains selectable.
+    pub fn push_text_clip(
+        &mut self,
+        start: Point,
+        glyphs: &[impl Glyph],
+        font: Font,
+        text: &str,
+        font_size: f32,
+    ) {
+        let context_color = self.context_color();
+        let text_state = self.effective_text_state();
+        let writing_mode = self.writing_mode;
+        let (fill, stroke) = if self.invisible_text {
+            (None, None)
+        } else {
+            (self.fill.as_ref(), self.stroke.as_ref())
+        };
+
+        self.push_instructions.push(PushInstruction::ClipPath);
+        self.bd.get_mut().push_text_clip(
+            start,
+            self.sc,
+            self.chunk_container,
+            fill,
+            stroke,
+            context_color,
+            glyphs,
+            font,
+            text,
+            font_size,
+            text_state,
+            writing_mode,
+        );
+    }
+
     /// Push a new mask.
     pub fn push_mask(&mut self, mask: Mask) {
         self.push_instructions
@@ -478,18 +643,34 @@ impl<'a> Surface<'a> {
             .push(PushInstruction::Opacity(opacity));
 
         if opacity != NormalizedF32::ONE {
-            self.bd
-                .sub_builders
-                .push(ContentBuilder::new(Transform::identity(), true, self.sc));
+            self.push_sub_builder();
         }
     }
 
     /// Push a new isolated layer.
     pub fn push_isolated(&mut self) {
         self.push_instructions.push(PushInstruction::Isolated);
+        self.push_sub_builder();
+    }
+
+    /// Push a new transparency group with the given properties.
+    ///
+    /// This is a more flexible version of [`Surface::push_isolated`], which
+    /// additionally allows creating knockout and non-isolated groups, as well
+    /// as choosing the color space in which the contents of the group are blended.
+    pub fn push_transparency_group(&mut self, group: TransparencyGroup) {
+        self.push_instructions
+            .push(PushInstruction::TransparencyGroup(group));
+        self.push_sub_builder();
+    }
+
+    /// Push a new content builder for the contents of a group, which inherits
+    /// whether colors are left out from the current one.
+    fn push_sub_builder(&mut self) {
+        let uncolored = self.bd.get().is_uncolored();
         self.bd
             .sub_builders
-            .push(ContentBuilder::new(Transform::identity(), true, self.sc));
+            .push(ContentBuilder::new(Transform::identity(), true, self.sc).uncolored(uncolored));
     }
 
     /// Pop the last `push` instruction.
@@ -509,6 +690,8 @@ impl<'a> Surface<'a> {
             }
             PushInstruction::ClipPath => self.bd.get_mut().pop_clip_path(),
             PushInstruction::BlendMode => self.bd.get_mut().restore_graphics_state(),
+            PushInstruction::Overprint => self.bd.get_mut().restore_graphics_state(),
+            PushInstruction::RenderingIntent => self.bd.get_mut().restore_graphics_state(),
             PushInstruction::Mask(mask) => {
                 let stream = self.bd.sub_builders.pop().unwrap().finish(self.sc);
                 self.bd
@@ -521,6 +704,15 @@ impl<'a> Surface<'a> {
                     .get_mut()
                     .draw_isolated(self.sc, self.chunk_container, stream);
             }
+            PushInstruction::TransparencyGroup(group) => {
+                let stream = self.bd.sub_builders.pop().unwrap().finish(self.sc);
+                self.bd.get_mut().draw_transparency_group(
+                    self.sc,
+                    self.chunk_container,
+                    group,
+                    stream,
+                );
+            }
         }
     }
 
@@ -532,6 +724,20 @@ impl<'a> Surface<'a> {
             .draw_image(image, size, self.sc, self.chunk_container);
     }
 
+    /// Draw a stencil mask using the currently active fill.
+    ///
+    /// The mask is scaled to the given size, just like images are. If there is no
+    /// active fill, the mask is painted in black.
+    #[cfg(feature = "raster-images")]
+    pub fn draw_stencil_mask(&mut self, mask: StencilMask, size: Size) {
+        let default = Fill::default();
+        let fill = self.fill.as_ref().unwrap_or(&default);
+
+        self.bd
+            .get_mut()
+            .draw_stencil_mask(mask, size, fill, self.sc, self.chunk_container);
+    }
+
     /// Draw a new graphic.
     ///
     /// Drawing the same graphic multiple times is very cheap in terms of
@@ -594,7 +800,12 @@ impl<'a> Surface<'a> {
                 r.stops.iter().any(|s| s.opacity != NormalizedF32::ONE)
             }
             InnerPaint::SweepGradient(r) => r.stops.iter().any(|s| s.opacity != NormalizedF32::ONE),
-            InnerPaint::Pattern(_) => false,
+            InnerPaint::MeshGradient(m) => m
+                .mesh
+                .colors()
+                .iter()
+                .any(|(_, o)| *o != NormalizedF32::ONE),
+            InnerPaint::Pattern(_) | InnerPaint::UncoloredPattern(_) => false,
         };
 
         let complex_stroke = self
@@ -659,6 +870,9 @@ pub(crate) enum PushInstruction {
     Opacity(NormalizedF32),
     ClipPath,
     BlendMode,
+    Overprint,
+    RenderingIntent,
     Mask(Box<Mask>),
     Isolated,
+    TransparencyGroup(TransparencyGroup),
 }
@@ -12,7 +12,7 @@ use std::ops::DerefMut;
 use std::sync::Arc;
 use subsetter::GlyphRemapper;
 
-use super::{CIDIdentifier, FontIdentifier, PDF_UNITS_PER_EM};
+use super::{CIDIdentifier, FontIdentifier, WritingMode, PDF_UNITS_PER_EM};
 use crate::chunk_container::ChunkContainer;
 use crate::configure::ValidationError;
 use crate::error::{KrillaError, KrillaResult};
@@ -27,6 +27,7 @@ use crate::util::{stable_hash128, SliceExt};
 
 const SUBSET_TAG_LEN: usize = 6;
 pub(crate) const IDENTITY_H: &str = "Identity-H";
+pub(crate) const IDENTITY_V: &str = "Identity-V";
 pub(crate) const CMAP_NAME: Name = Name(b"Custom");
 pub(crate) const SYSTEM_INFO: SystemInfo = SystemInfo {
     registry: Str(b"Adobe"),
@@ -113,20 +114,30 @@ pub(crate) struct CIDFont {
     cmap_entries: FxHashMap<u16, (String, Option<Location>)>,
     /// The widths of the glyphs, _indexed by their CID_.
     widths: Vec<f32>,
+    /// The vertical advances and the x and y coordinates of the vertical origins
+    /// of the glyphs, _indexed by their CID_. Only populated for vertical fonts.
+    vertical_metrics: Vec<(f32, f32, f32)>,
+    writing_mode: WritingMode,
     is_empty: bool,
 }
 
 impl CIDFont {
     /// Create a new CID-keyed font.
-    pub(crate) fn new(font: Font) -> CIDFont {
+    pub(crate) fn new(font: Font, writing_mode: WritingMode) -> CIDFont {
         // Always include the .notdef glyph. Will also always be included by the subsetter in
         // the glyph remapper.
         let widths = vec![font.advance_width(GlyphId::new(0)).unwrap_or(0.0)];
+        let vertical_metrics = match writing_mode {
+            WritingMode::Horizontal => vec![],
+            WritingMode::Vertical => vec![vertical_metrics(&font, GlyphId::new(0))],
+        };
 
         Self {
             glyph_remapper: GlyphRemapper::new(),
             cmap_entries: FxHashMap::default(),
             widths,
+            vertical_metrics,
+            writing_mode,
             font,
             is_empty: true,
         }
@@ -164,6 +175,11 @@ impl CIDFont {
         if new_id as usize >= self.widths.len() {
             self.widths
                 .push(self.font.advance_width(glyph_id).unwrap_or(0.0));
+
+            if self.writing_mode == WritingMode::Vertical {
+                self.vertical_metrics
+                    .push(vertical_metrics(&self.font, glyph_id));
+            }
         }
 
         new_id
@@ -181,7 +197,7 @@ impl CIDFont {
 
     #[inline]
     pub(crate) fn identifier(&self) -> FontIdentifier {
-        FontIdentifier::Cid(CIDIdentifier(self.font.clone()))
+        FontIdentifier::Cid(CIDIdentifier(self.font.clone(), self.writing_mode))
     }
 
     pub(crate) fn serialize(
@@ -253,9 +269,13 @@ impl CIDFont {
             FilterStreamBuilder::new_from_binary_data(data).finish(&sc.serialize_settings())
         };
 
+        let encoding = match self.writing_mode {
+            WritingMode::Horizontal => IDENTITY_H,
+            WritingMode::Vertical => IDENTITY_V,
+        };
         let base_font = base_font_name(&self.font, &self.glyph_remapper);
         let base_font_type0 = if is_cff {
-            format!("{base_font}-{IDENTITY_H}")
+            format!("{base_font}-{encoding}")
         } else {
             base_font.clone()
         };
@@ -263,7 +283,7 @@ impl CIDFont {
         chunk
             .type0_font(root_ref)
             .base_font(Name(base_font_type0.as_bytes()))
-            .encoding_predefined(Name(IDENTITY_H.as_bytes()))
+            .encoding_predefined(Name(encoding.as_bytes()))
             .descendant_font(cid_ref)
             .to_unicode(cmap_ref);
 
@@ -297,6 +317,39 @@ impl CIDFont {
         }
 
         width_writer.finish();
+
+        if self.writing_mode == WritingMode::Vertical {
+            // The default position vector is only used for glyphs that we don't list
+            // explicitly, and its x component is always half of the glyph width.
+            let default_origin = to_pdf_units(self.font.ascent());
+            let default_advance = -to_pdf_units(self.font.ascent() - self.font.descent());
+            cid.insert(Name(b"DW2"))
+                .array()
+                .items([default_origin, default_advance]);
+
+            let mut first = 0;
+            let mut vertical_writer = cid.insert(Name(b"W2")).array();
+            for (metrics, group) in self.vertical_metrics.group_by_key(|&m| m) {
+                let end = first + group.len();
+                let (advance, origin_x, origin_y) = metrics;
+                let advance = -to_pdf_units(advance);
+                let origin_x = to_pdf_units(origin_x);
+                let origin_y = to_pdf_units(origin_y);
+
+                if advance != default_advance
+                    || origin_y != default_origin
+                    || origin_x != to_pdf_units(self.widths[first]) / 2.0
+                {
+                    let last = end - 1;
+                    vertical_writer.items([first as i32, last as i32]);
+                    vertical_writer.items([advance, origin_x, origin_y]);
+                }
+                first = end;
+            }
+
+            vertical_writer.finish();
+        }
+
         cid.finish();
 
         // The only reason we write this in the first place is that PDF/A-1b requires
@@ -394,7 +447,10 @@ impl CIDFont {
                 .finish(&sc.serialize_settings());
         let mut cmap = stream_chunk.cmap(cmap_ref, cmap_stream.encoded_data());
         cmap_stream.write_filters(cmap.deref_mut().deref_mut());
-        cmap.writing_mode(WMode::Horizontal);
+        cmap.writing_mode(match self.writing_mode {
+            WritingMode::Horizontal => WMode::Horizontal,
+            WritingMode::Vertical => WMode::Vertical,
+        });
         cmap.finish();
 
         let mut stream = stream_chunk.stream(data_ref, font_stream.encoded_data());
@@ -410,6 +466,18 @@ impl CIDFont {
     }
 }
 
+/// The vertical advance and vertical origin of a glyph, in font units.
+///
+/// The x coordinate of the vertical origin is always in the horizontal center of
+/// the glyph, which matches what shapers assume.
+pub(crate) fn vertical_metrics(font: &Font, glyph_id: GlyphId) -> (f32, f32, f32) {
+    (
+        font.advance_height(glyph_id),
+        font.advance_width(glyph_id).unwrap_or(0.0) / 2.0,
+        font.vertical_origin_y(glyph_id),
+    )
+}
+
 /// Create a tag for a font subset.
 pub(crate) fn subset_tag<T: Hash>(data: &T) -> String {
     const BASE: u128 = 26;
@@ -153,6 +153,50 @@ impl Font {
     pub(crate) fn advance_width(&self, glyph_id: GlyphId) -> Option<f32> {
         self.glyph_metrics().advance_width(glyph_id.to_skrifa())
     }
+
+    /// The vertical advance of the glyph, in font units. Falls back to the
+    /// distance between ascender and descender if the font has no `vmtx` table.
+    pub(crate) fn advance_height(&self, glyph_id: GlyphId) -> f32 {
+        self.font_ref()
+            .vmtx()
+            .ok()
+            .and_then(|vmtx| vmtx.advance(glyph_id.to_skrifa()))
+            .map(f32::from)
+            .unwrap_or(self.ascent() - self.descent())
+    }
+
+    /// The y coordinate of the vertical origin of the glyph, in font units.
+    ///
+    /// Uses the `VORG` table if present, then the top side bearing from `vmtx`
+    /// and finally the ascender of the font.
+    pub(crate) fn vertical_origin_y(&self, glyph_id: GlyphId) -> f32 {
+        if let Ok(vorg) = self.font_ref().vorg() {
+            return f32::from(vorg.vertical_origin_y(glyph_id.to_skrifa()));
+        }
+
+        let tsb = self
+            .font_ref()
+            .vmtx()
+            .ok()
+            .and_then(|vmtx| vmtx.side_bearing(glyph_id.to_skrifa()));
+        let y_max = self
+            .glyph_metrics()
+            .bounds(glyph_id.to_skrifa())
+            .map(|b| b.y_max);
+
+        match (tsb, y_max) {
+            (Some(tsb), Some(y_max)) => f32::from(tsb) + y_max,
+            _ => self.ascent(),
+        }
+    }
+
+    /// The glyph the font maps the character to, if any.
+    pub(crate) fn glyph_id(&self, c: char) -> Option<GlyphId> {
+        self.font_ref()
+            .charmap()
+            .map(c)
+            .map(|glyph_id| GlyphId::new(glyph_id.to_u32()))
+    }
 }
 
 /// A 4-byte OpenType tag.
@@ -5,7 +5,7 @@ use std::rc::Rc;
 use crate::color::rgb;
 use crate::text::type3::ColoredGlyph;
 use crate::text::Glyph;
-use crate::text::{FontContainer, FontIdentifier};
+use crate::text::{FontContainer, FontIdentifier, WritingMode};
 
 pub(crate) enum GlyphSpan<'a, T>
 where
@@ -60,6 +60,7 @@ where
     reversed: bool,
     forbid_invalid_codepoints: bool,
     font_container: Rc<RefCell<FontContainer>>,
+    writing_mode: WritingMode,
     text: &'a str,
 }
 
@@ -74,6 +75,7 @@ where
         forbid_invalid_codepoints: bool,
         context_color: rgb::Color,
         font_container: Rc<RefCell<FontContainer>>,
+        writing_mode: WritingMode,
     ) -> Self {
         Self {
             slice,
@@ -82,6 +84,7 @@ where
             forbid_invalid_codepoints,
             text,
             font_container,
+            writing_mode,
         }
     }
 }
@@ -100,13 +103,14 @@ where
             previous_range: Option<Range<usize>>,
             forbid_invalid_codepoints: bool,
             mut font_container: RefMut<FontContainer>,
+            writing_mode: WritingMode,
             text: &str,
         ) -> (Range<usize>, bool)
         where
             U: Glyph,
         {
-            let (identifier, pdf_glyph) =
-                font_container.add_glyph(ColoredGlyph::new(g.glyph_id(), context_color));
+            let (identifier, pdf_glyph) = font_container
+                .add_glyph(ColoredGlyph::new(g.glyph_id(), context_color), writing_mode);
             let pdf_font = font_container
                 .get_from_identifier_mut(identifier.clone())
                 .unwrap();
@@ -155,6 +159,7 @@ where
             None,
             self.forbid_invalid_codepoints,
             self.font_container.borrow_mut(),
+            self.writing_mode,
             self.text,
         );
 
@@ -167,6 +172,7 @@ where
                 Some(prev_range.clone()),
                 self.forbid_invalid_codepoints,
                 self.font_container.borrow_mut(),
+                self.writing_mode,
                 self.text,
             );
 
@@ -261,6 +267,11 @@ where
 {
     pub(crate) font_identifier: FontIdentifier,
     pub(crate) glyphs: &'a [T],
+    // This will be stored in normalized form (i.e. at a font size of 1). For vertical
+    // fonts, this is the x coordinate of the vertical origin instead.
+    pub(crate) x_offset: f32,
+    // This will be stored in normalized form (i.e. at a font size of 1).
+    pub(crate) x_advance: f32,
     // This will be stored in normalized form (i.e. at a font size of 1).
     pub(crate) y_offset: f32,
     // This will be stored in normalized form (i.e. at a font size of 1).
@@ -275,6 +286,8 @@ where
         GlyphGroup {
             font_identifier: props.font_identifier,
             glyphs,
+            x_offset: props.x_offset,
+            x_advance: props.x_advance,
             y_offset: props.y_offset,
             y_advance: props.y_advance,
         }
@@ -288,13 +301,14 @@ where
 // have to be switched if the glyph maps to a different Type3 font.
 // - The glyph contains a y_offset/y_advance, which cannot be expressed as an adjustment
 // and requires us to start a new run with a transformation matrix that takes this
-// adjustment into account.
+// adjustment into account. For vertical fonts, the same applies to x_offset/x_advance.
 pub(crate) struct GlyphGrouper<'a, T>
 where
     T: Glyph,
 {
     font_container: Rc<RefCell<FontContainer>>,
     context_color: rgb::Color,
+    writing_mode: WritingMode,
     slice: &'a [T],
 }
 
@@ -305,11 +319,13 @@ where
     pub fn new(
         font_container: Rc<RefCell<FontContainer>>,
         context_color: rgb::Color,
+        writing_mode: WritingMode,
         slice: &'a [T],
     ) -> Self {
         Self {
             font_container,
             context_color,
+            writing_mode,
             slice,
         }
     }
@@ -332,6 +348,7 @@ where
             let first = get_glyph_props(
                 iter.next()?,
                 self.context_color,
+                self.writing_mode,
                 &mut self.font_container.borrow_mut(),
             );
 
@@ -339,15 +356,22 @@ where
                 let temp_glyph = get_glyph_props(
                     next,
                     self.context_color,
+                    self.writing_mode,
                     &mut self.font_container.borrow_mut(),
                 );
 
                 // If either of those is different, we need to start a new subrun.
-                if first.font_identifier != temp_glyph.font_identifier
-                    || first.y_offset != temp_glyph.y_offset
-                    || first.y_advance != 0.0
-                    || temp_glyph.y_advance != 0.0
-                {
+                let incompatible = if first.font_identifier.is_vertical() {
+                    first.x_offset != temp_glyph.x_offset
+                        || first.x_advance != 0.0
+                        || temp_glyph.x_advance != 0.0
+                } else {
+                    first.y_offset != temp_glyph.y_offset
+                        || first.y_advance != 0.0
+                        || temp_glyph.y_advance != 0.0
+                };
+
+                if first.font_identifier != temp_glyph.font_identifier || incompatible {
                     break;
                 }
 
@@ -368,6 +392,8 @@ where
 
 pub(crate) struct GlyphProps {
     font_identifier: FontIdentifier,
+    x_offset: f32,
+    x_advance: f32,
     y_offset: f32,
     y_advance: f32,
 }
@@ -375,6 +401,7 @@ pub(crate) struct GlyphProps {
 pub(crate) fn get_glyph_props<U>(
     g: &U,
     context_color: rgb::Color,
+    writing_mode: WritingMode,
     font_container: &mut FontContainer,
 ) -> GlyphProps
 where
@@ -382,11 +409,23 @@ where
 {
     // Safe because we've already added all glyphs in the text spanner.
     let font_identifier = font_container
-        .font_identifier(ColoredGlyph::new(g.glyph_id(), context_color))
+        .font_identifier(ColoredGlyph::new(g.glyph_id(), context_color), writing_mode)
         .unwrap();
 
+    // In vertical fonts, glyphs are positioned relative to their vertical origin, which
+    // lies in the horizontal center of the glyph.
+    let x_offset = if font_identifier.is_vertical() {
+        let font = font_container.font();
+        g.x_offset(1.0)
+            + font.advance_width(g.glyph_id()).unwrap_or(0.0) / font.units_per_em() / 2.0
+    } else {
+        g.x_offset(1.0)
+    };
+
     GlyphProps {
         font_identifier,
+        x_offset,
+        x_advance: g.x_advance(1.0),
         y_offset: g.y_offset(1.0),
         y_advance: g.y_advance(1.0),
     }
@@ -396,6 +435,7 @@ pub fn use_text_spanner(
     glyphs: &[impl Glyph],
     text: &str,
     context_color: rgb::Color,
+    writing_mode: WritingMode,
     font_container: &mut FontContainer,
 ) -> bool {
     if glyphs.is_empty() {
@@ -416,7 +456,14 @@ pub fn use_text_spanner(
         // The only reason we keep going and don't early abort is in order to fully
         // check the `do_glyph_grouping` property.
         if !*do_text_span {
-            check_text_span_prop(glyph, text, context_color, font_container, do_text_span);
+            check_text_span_prop(
+                glyph,
+                text,
+                context_color,
+                writing_mode,
+                font_container,
+                do_text_span,
+            );
         }
     };
 
@@ -440,11 +487,14 @@ fn check_text_span_prop(
     glyph: &impl Glyph,
     text: &str,
     context_color: rgb::Color,
+    writing_mode: WritingMode,
     font_container: &mut FontContainer,
     do_text_span: &mut bool,
 ) {
-    let (identifier, pdf_glyph) =
-        font_container.add_glyph(ColoredGlyph::new(glyph.glyph_id(), context_color));
+    let (identifier, pdf_glyph) = font_container.add_glyph(
+        ColoredGlyph::new(glyph.glyph_id(), context_color),
+        writing_mode,
+    );
     let pdf_font = font_container
         .get_from_identifier_mut(identifier.clone())
         .unwrap();
@@ -33,9 +33,70 @@ pub use shape::TextDirection;
 
 pub(crate) const PDF_UNITS_PER_EM: f32 = 1000.0;
 
-/// A unique CID identifier.
+/// The writing mode used for placing glyphs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum WritingMode {
+    /// Glyphs are placed next to each other along the baseline.
+    #[default]
+    Horizontal,
+    /// Glyphs are placed below each other, using the vertical metrics of the
+    /// font. Only applies to glyphs that are embedded as outlines, color glyphs
+    /// are always placed individually.
+    Vertical,
+}
+
+/// Text state parameters that affect how glyphs are placed.
+///
+/// All lengths are in user space units, meaning that they don't depend on the font size.
+/// The text state only applies to horizontal writing and is ignored for
+/// [`WritingMode::Vertical`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextState {
+    /// Extra space that is added after each glyph.
+    pub character_spacing: f32,
+    /// Extra space that is added after each glyph that represents a single
+    /// space character (U+0020), in addition to the character spacing.
+    pub word_spacing: f32,
+    /// The horizontal scaling of the text, where `1.0` stands for the normal width.
+    /// It stretches the glyphs as well as the spacing between them.
+    pub horizontal_scaling: f32,
+    /// The distance by which the baseline is raised.
+    pub rise: f32,
+}
+
+impl Default for TextState {
+    fn default() -> Self {
+        Self {
+            character_spacing: 0.0,
+            word_spacing: 0.0,
+            horizontal_scaling: 1.0,
+            rise: 0.0,
+        }
+    }
+}
+
+impl TextState {
+    /// The word spacing that applies after the given glyph.
+    pub(crate) fn word_spacing_after(&self, glyph: &impl Glyph, text: &str) -> f32 {
+        if text.get(glyph.text_range()) == Some(" ") {
+            self.word_spacing
+        } else {
+            0.0
+        }
+    }
+
+    /// The horizontal distance from the start of the given glyph to the start of the
+    /// next one.
+    pub(crate) fn advance(&self, glyph: &impl Glyph, text: &str, size: f32) -> f32 {
+        (glyph.x_advance(size) + self.character_spacing + self.word_spacing_after(glyph, text))
+            * self.horizontal_scaling
+    }
+}
+
+/// A unique CID identifier. Each font can have one horizontal and one vertical
+/// CID font.
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
-pub(crate) struct CIDIdentifier(pub Font);
+pub(crate) struct CIDIdentifier(pub Font, pub WritingMode);
 
 /// A unique Type3 font identifier. Type3 fonts can only hold 256 glyphs, which
 /// means that we might have to create more than one Type3 font. This is why we
@@ -51,12 +112,24 @@ pub(crate) enum FontIdentifier {
     Type3(Type3Identifier),
 }
 
+impl FontIdentifier {
+    /// Whether the identifier refers to a CID font with vertical writing mode.
+    pub(crate) fn is_vertical(&self) -> bool {
+        matches!(
+            self,
+            FontIdentifier::Cid(CIDIdentifier(_, WritingMode::Vertical))
+        )
+    }
+}
+
 /// A container that holds all PDF fonts belonging to an OTF font.
 pub(crate) struct FontContainer {
     font: Font,
     type3_mapper: Type3FontMapper,
     cid_font: CIDFont,
+    vertical_cid_font: CIDFont,
     cid_cache: FxHashMap<u32, (FontIdentifier, PDFGlyph)>,
+    vertical_cid_cache: FxHashMap<u32, (FontIdentifier, PDFGlyph)>,
     type3_cache: HashMap<ColoredGlyph, (FontIdentifier, PDFGlyph)>,
 }
 
@@ -65,12 +138,18 @@ impl FontContainer {
         Self {
             font: font.clone(),
             type3_mapper: Type3FontMapper::new(font.clone()),
-            cid_font: CIDFont::new(font.clone()),
+            cid_font: CIDFont::new(font.clone(), WritingMode::Horizontal),
+            vertical_cid_font: CIDFont::new(font.clone(), WritingMode::Vertical),
             cid_cache: Default::default(),
+            vertical_cid_cache: Default::default(),
             type3_cache: Default::default(),
         }
     }
 
+    pub(crate) fn font(&self) -> &Font {
+        &self.font
+    }
+
     pub(crate) fn type3_mapper(&self) -> &Type3FontMapper {
         &self.type3_mapper
     }
@@ -79,10 +158,25 @@ impl FontContainer {
         &self.cid_font
     }
 
+    pub(crate) fn vertical_cid_font(&self) -> &CIDFont {
+        &self.vertical_cid_font
+    }
+
+    fn cid_cache(&self, writing_mode: WritingMode) -> &FxHashMap<u32, (FontIdentifier, PDFGlyph)> {
+        match writing_mode {
+            WritingMode::Horizontal => &self.cid_cache,
+            WritingMode::Vertical => &self.vertical_cid_cache,
+        }
+    }
+
     #[inline]
-    pub(crate) fn font_identifier(&self, glyph: ColoredGlyph) -> Option<FontIdentifier> {
+    pub(crate) fn font_identifier(
+        &self,
+        glyph: ColoredGlyph,
+        writing_mode: WritingMode,
+    ) -> Option<FontIdentifier> {
         let (id, _) = self
-            .cid_cache
+            .cid_cache(writing_mode)
             .get(&glyph.glyph_id.to_u32())
             .or_else(|| self.type3_cache.get(&glyph.to_owned()))?;
         Some(id.clone())
@@ -95,6 +189,8 @@ impl FontContainer {
     ) -> Option<&mut dyn PdfFont> {
         if self.cid_font.identifier() == font_identifier {
             Some(&mut self.cid_font)
+        } else if self.vertical_cid_font.identifier() == font_identifier {
+            Some(&mut self.vertical_cid_font)
         } else {
             // If the identifier doesn't match either of CID or Type3, this will
             // return `None`.
@@ -109,6 +205,8 @@ impl FontContainer {
     ) -> Option<&dyn PdfFont> {
         if self.cid_font.identifier() == font_identifier {
             Some(&self.cid_font)
+        } else if self.vertical_cid_font.identifier() == font_identifier {
+            Some(&self.vertical_cid_font)
         } else {
             // If the identifier doesn't match either of CID or Type3, this will
             // return `None`.
@@ -117,18 +215,29 @@ impl FontContainer {
     }
 
     #[inline]
-    pub(crate) fn add_glyph(&mut self, glyph: ColoredGlyph) -> (FontIdentifier, PDFGlyph) {
+    pub(crate) fn add_glyph(
+        &mut self,
+        glyph: ColoredGlyph,
+        writing_mode: WritingMode,
+    ) -> (FontIdentifier, PDFGlyph) {
         if let Some(e) = self
-            .cid_cache
+            .cid_cache(writing_mode)
             .get(&glyph.glyph_id.to_u32())
             .or_else(|| self.type3_cache.get(&glyph.to_owned()))
         {
             // We already know whether this glyph uses a CID or Type3 glyph.
             e.clone()
         } else if should_outline(&self.font, glyph.glyph_id) {
-            let cid = self.cid_font.add_glyph(glyph.glyph_id);
-            let res = (self.cid_font.identifier(), PDFGlyph::Cid(cid));
-            self.cid_cache.insert(glyph.glyph_id.to_u32(), res.clone());
+            let (cid_font, cid_cache) = match writing_mode {
+                WritingMode::Horizontal => (&mut self.cid_font, &mut self.cid_cache),
+                WritingMode::Vertical => {
+                    (&mut self.vertical_cid_font, &mut self.vertical_cid_cache)
+                }
+            };
+
+            let cid = cid_font.add_glyph(glyph.glyph_id);
+            let res = (cid_font.identifier(), PDFGlyph::Cid(cid));
+            cid_cache.insert(glyph.glyph_id.to_u32(), res.clone());
             res
         } else {
             let (identifier, gid) = self.type3_mapper.add_glyph(glyph.to_owned());
@@ -137,6 +246,37 @@ impl FontContainer {
             res
         }
     }
+
+    /// Add the glyphs of all printable Latin-1 characters to the CID font.
+    ///
+    /// Form fields reference the font in their default appearance, which viewers use
+    /// to render text entered by the user. Since fonts are subsetted, this ensures that
+    /// text in most Western European languages can be entered. Returns `false` if the
+    /// CID font is still empty afterwards, e.g. because the font only contains color
+    /// glyphs.
+    pub(crate) fn add_form_glyphs(&mut self) -> bool {
+        for c in (' '..='~').chain('\u{a0}'..='\u{ff}') {
+            let Some(glyph_id) = self.font.glyph_id(c) else {
+                continue;
+            };
+
+            if !should_outline(&self.font, glyph_id) {
+                continue;
+            }
+
+            let cid = self.cid_font.add_glyph(glyph_id);
+            self.cid_cache.insert(
+                glyph_id.to_u32(),
+                (self.cid_font.identifier(), PDFGlyph::Cid(cid)),
+            );
+
+            if self.cid_font.get_codepoints(cid).is_none() {
+                self.cid_font.set_codepoints(cid, c.to_string(), None);
+            }
+        }
+
+        !self.cid_font.is_empty()
+    }
 }
 
 pub(crate) trait PdfFont {
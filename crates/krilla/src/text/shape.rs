@@ -1,10 +1,15 @@
-use crate::text::Font;
+use crate::text::{Font, WritingMode};
 use crate::text::{GlyphId, KrillaGlyph};
 use rustybuzz::ttf_parser::Tag;
-use rustybuzz::{Direction, UnicodeBuffer};
+use rustybuzz::{Direction, Feature, UnicodeBuffer};
 
 /// Naively shape some text with a single font.
-pub(crate) fn naive_shape(text: &str, font: Font, direction: TextDirection) -> Vec<KrillaGlyph> {
+pub(crate) fn naive_shape(
+    text: &str,
+    font: Font,
+    direction: TextDirection,
+    writing_mode: WritingMode,
+) -> Vec<KrillaGlyph> {
     let data = font.font_data();
     let mut rb_font = rustybuzz::Face::from_slice(data.as_ref(), font.index()).unwrap();
 
@@ -26,7 +31,15 @@ pub(crate) fn naive_shape(text: &str, font: Font, direction: TextDirection) -> V
 
     let dir = buffer.direction();
 
-    let output = rustybuzz::shape(&rb_font, &[], buffer);
+    // Use the vertical alternates of glyphs (e.g. for punctuation) when the text
+    // is actually drawn in vertical writing mode.
+    let features = if dir == Direction::TopToBottom && writing_mode == WritingMode::Vertical {
+        vec![Feature::new(Tag::from_bytes(b"vert"), 1, ..)]
+    } else {
+        vec![]
+    };
+
+    let output = rustybuzz::shape(&rb_font, &features, buffer);
 
     let positions = output.glyph_positions();
     let infos = output.glyph_infos();
@@ -11,7 +11,7 @@ use crate::resource::Resource;
 use crate::serialize::MaybeDeviceColorSpace;
 use base64::Engine;
 pub(crate) use deferred::*;
-use pdf_writer::{Dict, Name};
+use pdf_writer::{Dict, Name, Obj};
 use siphasher::sip128::{Hasher128, SipHasher13};
 use tiny_skia_path::Path;
 
@@ -180,8 +180,10 @@ pub(crate) fn stable_hash128<T: Hash + ?Sized>(value: &T) -> u128 {
 }
 
 pub(crate) fn set_colorspace(cs: MaybeDeviceColorSpace, target: &mut Dict) {
-    let pdf_cs = target.insert(Name(b"ColorSpace"));
+    write_colorspace(cs, target.insert(Name(b"ColorSpace")));
+}
 
+pub(crate) fn write_colorspace(cs: MaybeDeviceColorSpace, pdf_cs: Obj) {
     match cs {
         MaybeDeviceColorSpace::DeviceGray => pdf_cs.primitive(DEVICE_GRAY.to_pdf_name()),
         MaybeDeviceColorSpace::DeviceRgb => pdf_cs.primitive(DEVICE_RGB.to_pdf_name()),
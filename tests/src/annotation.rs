@@ -1,12 +1,23 @@
-use krilla::annotation::LinkBorder;
+use krilla::action::{RenditionAction, RenditionOperation};
+use krilla::annotation::{
+    FileAttachmentAnnotation, FileAttachmentIcon, FixedPrint, LinkBorder, ScreenAnnotation,
+    ScreenTrigger, StampAnnotation, StampName, WatermarkAnnotation,
+};
+use krilla::configure::validate::VersionedFeature;
+use krilla::configure::ValidationError;
 use krilla::destination::XyzDestination;
-use krilla::geom::{Point, Quadrilateral, Rect, Size};
+use krilla::embed::MimeType;
+use krilla::geom::{Point, Quadrilateral, Rect, Size, Transform};
 use krilla::page::{Page, PageSettings};
+use krilla::stream::Stream;
+use krilla::surface::Surface;
 use krilla::Document;
 use krilla_macros::{snapshot, visreg};
 
+use crate::embed::file_1;
 use crate::{green_fill, load_pdf, rect_to_path, red_fill};
-use crate::{settings_1, LinkAction};
+use crate::{loc, settings_1, settings_10, settings_19, settings_7, validation_errors};
+use crate::{Annotation, LinkAction};
 use crate::{LinkAnnotation, Target};
 
 #[snapshot]
@@ -130,3 +141,133 @@ fn annotation_to_embedded_pdf_page(document: &mut Document) {
     surface.finish();
     page.finish();
 }
+
+fn appearance_stream(surface: &mut Surface) -> Stream {
+    let mut stream_builder = surface.stream_builder();
+    let mut sub_surface = stream_builder.surface();
+    sub_surface.set_fill(Some(red_fill(1.0)));
+    sub_surface.draw_path(&rect_to_path(0.0, 0.0, 100.0, 50.0));
+    sub_surface.finish();
+
+    stream_builder.finish()
+}
+
+fn watermark(surface: &mut Surface) -> WatermarkAnnotation {
+    WatermarkAnnotation::new(
+        Rect::from_xywh(50.0, 100.0, 100.0, 50.0).unwrap(),
+        appearance_stream(surface),
+    )
+    .with_fixed_print(FixedPrint::new(Transform::identity(), 0.25, 0.5))
+}
+
+fn stamp_and_watermark_impl(d: &mut Document) {
+    let mut page = d.start_page_with(PageSettings::from_wh(200.0, 200.0).unwrap());
+    let mut surface = page.surface();
+    let stamp_appearance = appearance_stream(&mut surface);
+    let watermark = watermark(&mut surface);
+    surface.finish();
+
+    page.add_annotation(Annotation::new_stamp(
+        StampAnnotation::new(
+            Rect::from_xywh(50.0, 50.0, 100.0, 50.0).unwrap(),
+            StampName::Draft,
+        )
+        .with_appearance(stamp_appearance),
+        Some("Draft".to_string()),
+    ));
+    page.add_annotation(
+        Annotation::new_watermark(watermark, Some("Watermark".to_string()))
+            .with_print(true)
+            .with_no_view(true),
+    );
+    page.finish();
+}
+
+// PDF/A forbids hiding annotations on screen, so the flag is dropped
+// instead of causing a validation error.
+#[visreg(document, settings_7)]
+fn annotation_stamp_and_watermark_pdf_a(d: &mut Document) {
+    stamp_and_watermark_impl(d);
+}
+
+#[snapshot(document, settings_7)]
+fn annotation_stamp_and_watermark_pdf_a(d: &mut Document) {
+    stamp_and_watermark_impl(d);
+}
+
+#[test]
+fn annotation_watermark_pdf_a1() {
+    let mut d = Document::new_with(settings_19());
+    let mut page = d.start_page();
+    let mut surface = page.surface();
+    let watermark = watermark(&mut surface);
+    surface.finish();
+
+    page.add_annotation(
+        Annotation::new_watermark(watermark, Some("Watermark".to_string()))
+            .with_location(Some(loc(1))),
+    );
+    page.finish();
+
+    // Watermark annotations were only introduced in PDF 1.6.
+    assert!(
+        validation_errors(d.finish()).contains(&ValidationError::RequiresNewerPdfVersion(
+            VersionedFeature::WatermarkAnnotations,
+            Some(loc(1))
+        ))
+    );
+}
+
+#[test]
+fn annotation_file_attachment_pdf_a3() {
+    let mut d = Document::new_with(settings_10());
+    let mut page = d.start_page_with(PageSettings::from_wh(200.0, 200.0).unwrap());
+    let mut surface = page.surface();
+    let appearance = appearance_stream(&mut surface);
+    surface.finish();
+
+    page.add_annotation(Annotation::new_file_attachment(
+        FileAttachmentAnnotation::new(Rect::from_xywh(50.0, 50.0, 20.0, 20.0).unwrap(), file_1())
+            .with_icon(FileAttachmentIcon::Paperclip)
+            .with_appearance(appearance),
+        Some("Attached file".to_string()),
+    ));
+    page.finish();
+
+    assert!(d.finish().is_ok());
+}
+
+#[test]
+fn annotation_screen_with_rendition_actions() {
+    let mut d = Document::new_with(settings_1());
+    let mut page = d.start_page_with(PageSettings::from_wh(200.0, 200.0).unwrap());
+    let mut surface = page.surface();
+    let poster = appearance_stream(&mut surface);
+    surface.finish();
+
+    let mut clip = file_1();
+    clip.path = "clip.mp4".to_string();
+    clip.mime_type = MimeType::new("video/mp4");
+
+    page.add_annotation(Annotation::new_screen(
+        ScreenAnnotation::new(Rect::from_xywh(50.0, 50.0, 100.0, 100.0).unwrap(), clip)
+            .with_title("Training clip".to_string())
+            .with_poster(poster)
+            .with_action(
+                ScreenTrigger::Activate,
+                RenditionAction::new(RenditionOperation::Play),
+            )
+            .with_action(
+                ScreenTrigger::PageInvisible,
+                RenditionAction::new(RenditionOperation::Pause),
+            )
+            .with_action(
+                ScreenTrigger::PageClose,
+                RenditionAction::new(RenditionOperation::Stop),
+            ),
+        Some("Training clip".to_string()),
+    ));
+    page.finish();
+
+    assert!(d.finish().is_ok());
+}
@@ -0,0 +1,532 @@
+use std::sync::LazyLock;
+
+use krilla::color::devicen::{DeviceNSpace, NChannelAttributes, ProcessColorants};
+use krilla::color::separation::SeparationColorant;
+use krilla::color::{cal_gray, cal_rgb, cmyk, devicen, lab, luma, rgb, Color};
+use krilla::configure::ValidationError;
+use krilla::icc::{ICCProfile, OutputIntentSubtype};
+use krilla::num::NormalizedF32;
+use krilla::page::Page;
+use krilla::paint::{Fill, LinearGradient, SpreadMethod, Stop, Stroke};
+use krilla::surface::Surface;
+use krilla::tagging::NaiveRgbColor;
+use krilla::{Document, SerializeSettings};
+use krilla_macros::{snapshot, visreg};
+
+use crate::{
+    contains, count, page_pdf, rect_to_path, settings_1, settings_19, settings_35, settings_36,
+    settings_37, settings_38, settings_7, swop, validation_errors,
+};
+
+fn fill(color: impl Into<Color>) -> Fill {
+    Fill {
+        paint: color.into().into(),
+        opacity: NormalizedF32::ONE,
+        rule: Default::default(),
+    }
+}
+
+fn fill_impl(surface: &mut Surface, fill: Fill, stroke: Option<Stroke>) {
+    surface.set_fill(Some(fill));
+    surface.set_stroke(stroke);
+    surface.draw_path(&rect_to_path(20.0, 20.0, 180.0, 180.0));
+}
+
+fn fill_page(page: &mut Page, fill: Fill, stroke: Option<Stroke>) {
+    let mut surface = page.surface();
+    fill_impl(&mut surface, fill, stroke);
+}
+
+fn gradient(stops: Vec<Stop>) -> Fill {
+    Fill {
+        paint: LinearGradient {
+            x1: 20.0,
+            y1: 0.0,
+            x2: 180.0,
+            y2: 0.0,
+            transform: Default::default(),
+            spread_method: SpreadMethod::Pad,
+            stops,
+            anti_alias: false,
+        }
+        .into(),
+        opacity: NormalizedF32::ONE,
+        rule: Default::default(),
+    }
+}
+
+fn lab_fill() -> Fill {
+    let space = lab::LabSpace::default().with_range([-128.0, 127.0, -128.0, 127.0]);
+    fill(lab::Color::new_with_space(54.0, 81.0, 70.0, space))
+}
+
+#[visreg]
+fn color_lab(surface: &mut Surface) {
+    fill_impl(surface, lab_fill(), None);
+}
+
+#[snapshot]
+fn color_lab(page: &mut Page) {
+    fill_page(page, lab_fill(), None);
+}
+
+#[test]
+fn color_lab_contents() {
+    let pdf = page_pdf(settings_1(), color_lab_snapshot_impl);
+
+    assert!(contains(&pdf, b"/Lab"));
+    assert!(contains(&pdf, b"/WhitePoint [0.9642 1 0.8249]"));
+    assert!(contains(&pdf, b"/Range [-128 127 -128 127]"));
+    assert!(contains(&pdf, b"54 81 70 sc"));
+}
+
+fn cal_stroke() -> Stroke {
+    Stroke {
+        paint: Color::from(cal_gray::Color::new(0.5)).into(),
+        width: 10.0,
+        ..Default::default()
+    }
+}
+
+#[visreg]
+fn color_cal_rgb_and_cal_gray(surface: &mut Surface) {
+    fill_impl(
+        surface,
+        fill(cal_rgb::Color::new(1.0, 0.0, 0.0)),
+        Some(cal_stroke()),
+    );
+}
+
+#[snapshot]
+fn color_cal_rgb_and_cal_gray(page: &mut Page) {
+    fill_page(
+        page,
+        fill(cal_rgb::Color::new(1.0, 0.0, 0.0)),
+        Some(cal_stroke()),
+    );
+}
+
+#[test]
+fn color_cal_rgb_and_cal_gray_contents() {
+    let pdf = page_pdf(settings_1(), color_cal_rgb_and_cal_gray_snapshot_impl);
+
+    assert!(contains(&pdf, b"/CalRGB"));
+    assert!(contains(&pdf, b"/Gamma [2.2 2.2 2.2]"));
+    assert!(contains(&pdf, b"/CalGray"));
+    assert!(contains(&pdf, b"/Gamma 2.2"));
+}
+
+fn lab_gradient() -> Fill {
+    let stops = vec![
+        Stop {
+            offset: NormalizedF32::ZERO,
+            color: lab::Color::new(30.0, 20.0, -40.0).into(),
+            opacity: NormalizedF32::ONE,
+        },
+        Stop {
+            offset: NormalizedF32::ONE,
+            color: lab::Color::new(90.0, -10.0, 60.0).into(),
+            opacity: NormalizedF32::ONE,
+        },
+    ];
+    gradient(stops)
+}
+
+#[visreg]
+fn color_lab_gradient(surface: &mut Surface) {
+    fill_impl(surface, lab_gradient(), None);
+}
+
+#[snapshot]
+fn color_lab_gradient(page: &mut Page) {
+    fill_page(page, lab_gradient(), None);
+}
+
+#[test]
+fn color_lab_gradient_contents() {
+    let pdf = page_pdf(settings_1(), color_lab_gradient_snapshot_impl);
+
+    assert!(contains(&pdf, b"/Lab"));
+    assert!(contains(&pdf, b"/Range [0 100 -100 100 -100 100]"));
+}
+
+#[snapshot(settings_7)]
+fn color_lab_pdf_a(page: &mut Page) {
+    fill_page(page, fill(lab::Color::new(54.0, 81.0, 70.0)), None);
+}
+
+#[test]
+fn color_lab_pdf_a() {
+    let mut document = Document::new_with(settings_7());
+    let mut page = document.start_page();
+    color_lab_pdf_a_snapshot_impl(&mut page);
+    page.finish();
+
+    assert!(document.finish().is_ok());
+}
+
+#[test]
+fn color_cie_to_naive_rgb() {
+    assert_eq!(
+        NaiveRgbColor::from(lab::Color::new(100.0, 0.0, 0.0)),
+        NaiveRgbColor::new(255, 255, 255)
+    );
+    assert_eq!(
+        NaiveRgbColor::from(cal_gray::Color::new(0.0)),
+        NaiveRgbColor::new(0, 0, 0)
+    );
+    assert_eq!(
+        NaiveRgbColor::from(cal_rgb::Color::new(1.0, 1.0, 1.0)),
+        NaiveRgbColor::new(255, 255, 255)
+    );
+}
+
+fn custom(name: &str) -> SeparationColorant {
+    SeparationColorant::Custom(name.to_string())
+}
+
+fn packaging_space() -> DeviceNSpace {
+    DeviceNSpace::new(vec![
+        (custom("Cyan"), cmyk::Color::new(255, 0, 0, 0).into()),
+        (custom("Orange"), cmyk::Color::new(0, 153, 255, 0).into()),
+        (custom("Varnish"), cmyk::Color::new(0, 0, 0, 0).into()),
+    ])
+    .unwrap()
+}
+
+fn devicen_fill() -> Fill {
+    fill(devicen::Color::new(vec![255, 128, 255], packaging_space()))
+}
+
+#[visreg]
+fn color_devicen(surface: &mut Surface) {
+    fill_impl(surface, devicen_fill(), None);
+}
+
+#[snapshot]
+fn color_devicen(page: &mut Page) {
+    fill_page(page, devicen_fill(), None);
+}
+
+#[test]
+fn color_devicen_contents() {
+    let pdf = page_pdf(settings_1(), color_devicen_snapshot_impl);
+
+    assert!(contains(
+        &pdf,
+        b"/DeviceN [/Cyan /Orange /Varnish] /DeviceCMYK"
+    ));
+    assert!(contains(&pdf, b"/Colorants <<"));
+    assert!(contains(&pdf, b"/Separation /Orange /DeviceCMYK"));
+    assert!(!contains(&pdf, b"/Separation /Cyan"));
+    assert!(contains(&pdf, b"/Size [2 2 2]"));
+    assert!(!contains(&pdf, b"/NChannel"));
+}
+
+fn nchannel_fill() -> Fill {
+    let space = packaging_space().with_nchannel(
+        NChannelAttributes::new()
+            .with_process(ProcessColorants::Cmyk)
+            .with_solidity(custom("Varnish"), NormalizedF32::ONE),
+    );
+    fill(devicen::Color::new(vec![255, 128, 255], space))
+}
+
+#[visreg]
+fn color_devicen_nchannel(surface: &mut Surface) {
+    fill_impl(surface, nchannel_fill(), None);
+}
+
+#[snapshot]
+fn color_devicen_nchannel(page: &mut Page) {
+    fill_page(page, nchannel_fill(), None);
+}
+
+#[test]
+fn color_devicen_nchannel_contents() {
+    let pdf = page_pdf(settings_1(), color_devicen_nchannel_snapshot_impl);
+
+    assert!(contains(&pdf, b"/Subtype /NChannel"));
+    assert!(contains(
+        &pdf,
+        b"/Components [/Cyan /Magenta /Yellow /Black]"
+    ));
+    assert!(contains(&pdf, b"/Solidities <<"));
+    assert!(contains(&pdf, b"/PrintingOrder [/Cyan /Orange /Varnish]"));
+}
+
+// The solidity of `Gold` is dropped, the last solidity of `Varnish` wins and the incomplete
+// printing order is replaced by the order of the space.
+#[snapshot]
+fn color_devicen_nchannel_unknown_colorants(page: &mut Page) {
+    let space = packaging_space().with_nchannel(
+        NChannelAttributes::new()
+            .with_solidity(custom("Gold"), NormalizedF32::ONE)
+            .with_solidity(custom("Varnish"), NormalizedF32::ZERO)
+            .with_solidity(custom("Varnish"), NormalizedF32::ONE)
+            .with_printing_order(vec![custom("Varnish"), custom("Cyan")]),
+    );
+    fill_page(
+        page,
+        fill(devicen::Color::new(vec![255, 128, 255], space)),
+        None,
+    );
+}
+
+#[test]
+fn color_devicen_nchannel_unknown_colorants() {
+    let pdf = page_pdf(
+        settings_1(),
+        color_devicen_nchannel_unknown_colorants_snapshot_impl,
+    );
+
+    assert!(contains(&pdf, b"/Varnish 1"));
+    assert!(!contains(&pdf, b"/Varnish 0"));
+    assert!(!contains(&pdf, b"/Gold"));
+    assert!(contains(&pdf, b"/PrintingOrder [/Cyan /Orange /Varnish]"));
+}
+
+// More than 8 colorants can't be represented with a sampled function, so a PostScript
+// function is used instead.
+#[snapshot]
+fn color_devicen_many_colorants(page: &mut Page) {
+    let colorants = (0..13)
+        .map(|i| {
+            (
+                custom(&format!("Spot{i}")),
+                rgb::Color::new(255, 0, 0).into(),
+            )
+        })
+        .collect();
+    let space = DeviceNSpace::new(colorants).unwrap();
+    fill_page(page, fill(devicen::Color::new(vec![255], space)), None);
+}
+
+#[test]
+fn color_devicen_many_colorants() {
+    let pdf = page_pdf(settings_1(), color_devicen_many_colorants_snapshot_impl);
+
+    assert!(contains(&pdf, b"/FunctionType 4"));
+}
+
+#[test]
+fn color_devicen_invalid_space() {
+    assert!(DeviceNSpace::new(vec![]).is_none());
+    assert!(DeviceNSpace::new(vec![
+        (custom("Orange"), rgb::Color::new(255, 128, 0).into()),
+        (custom("Orange"), rgb::Color::new(255, 128, 0).into()),
+    ])
+    .is_none());
+    assert!(DeviceNSpace::new(vec![
+        (custom("Orange"), rgb::Color::new(255, 128, 0).into()),
+        (custom("Cyan"), cmyk::Color::new(255, 0, 0, 0).into()),
+    ])
+    .is_none());
+    assert!(DeviceNSpace::new(vec![(
+        SeparationColorant::AllColorants,
+        rgb::Color::new(0, 0, 0).into()
+    )])
+    .is_none());
+}
+
+static RGB_PROFILE: LazyLock<ICCProfile<3>> = LazyLock::new(|| {
+    ICCProfile::new(include_bytes!("../../crates/krilla/icc/sRGB-v2-magic.icc")).unwrap()
+});
+
+static GRAY_PROFILE: LazyLock<ICCProfile<1>> = LazyLock::new(|| {
+    ICCProfile::new(include_bytes!("../../crates/krilla/icc/sGrey-v2-magic.icc")).unwrap()
+});
+
+fn rgb_profile_stroke() -> Stroke {
+    Stroke {
+        paint: rgb::Color::new_with_profile(0, 0, 255, RGB_PROFILE.clone()).into(),
+        width: 10.0,
+        ..Default::default()
+    }
+}
+
+#[visreg]
+fn color_rgb_with_profile(surface: &mut Surface) {
+    fill_impl(
+        surface,
+        fill(rgb::Color::new_with_profile(255, 0, 0, RGB_PROFILE.clone())),
+        Some(rgb_profile_stroke()),
+    );
+}
+
+#[snapshot]
+fn color_rgb_with_profile(page: &mut Page) {
+    fill_page(
+        page,
+        fill(rgb::Color::new_with_profile(255, 0, 0, RGB_PROFILE.clone())),
+        Some(rgb_profile_stroke()),
+    );
+}
+
+#[test]
+fn color_rgb_with_profile_contents() {
+    let pdf = page_pdf(settings_1(), color_rgb_with_profile_snapshot_impl);
+
+    // Both colors share the same color space.
+    assert_eq!(count(&pdf, b"/ICCBased"), 1);
+    assert!(contains(&pdf, b"/N 3"));
+}
+
+#[test]
+fn color_rgb_with_unsupported_profile() {
+    let profile =
+        ICCProfile::<3>::new(include_bytes!("../../crates/krilla/icc/sRGB-v4.icc")).unwrap();
+    let mut document = Document::new_with(settings_19());
+    let mut page = document.start_page();
+    let mut surface = page.surface();
+    surface.set_fill(Some(fill(rgb::Color::new_with_profile(255, 0, 0, profile))));
+    surface.draw_path(&rect_to_path(20.0, 20.0, 180.0, 180.0));
+    surface.finish();
+    page.finish();
+
+    // PDF 1.4 doesn't support ICC profiles of version 4.
+    assert!(validation_errors(document.finish())
+        .contains(&ValidationError::UnsupportedColorProfile(None)));
+}
+
+#[snapshot]
+fn color_luma_with_profile(page: &mut Page) {
+    fill_page(
+        page,
+        fill(luma::Color::new_with_profile(128, GRAY_PROFILE.clone())),
+        None,
+    );
+}
+
+#[test]
+fn color_luma_with_profile() {
+    let pdf = page_pdf(settings_1(), color_luma_with_profile_snapshot_impl);
+
+    assert_eq!(count(&pdf, b"/ICCBased"), 1);
+    assert!(contains(&pdf, b"/N 1"));
+}
+
+fn rgb_profile_gradient() -> Fill {
+    let stops = [0, 255]
+        .into_iter()
+        .enumerate()
+        .map(|(i, c)| Stop {
+            offset: NormalizedF32::new(i as f32).unwrap(),
+            color: rgb::Color::new_with_profile(c, 0, 255 - c, RGB_PROFILE.clone()).into(),
+            opacity: NormalizedF32::ONE,
+        })
+        .collect();
+    gradient(stops)
+}
+
+#[visreg]
+fn color_rgb_with_profile_gradient(surface: &mut Surface) {
+    fill_impl(surface, rgb_profile_gradient(), None);
+}
+
+#[snapshot]
+fn color_rgb_with_profile_gradient(page: &mut Page) {
+    fill_page(page, rgb_profile_gradient(), None);
+}
+
+#[test]
+fn color_rgb_with_profile_gradient_contents() {
+    let pdf = page_pdf(settings_1(), color_rgb_with_profile_gradient_snapshot_impl);
+
+    assert_eq!(count(&pdf, b"/ICCBased"), 1);
+    assert!(!contains(&pdf, b"/ColorSpace /DeviceRGB"));
+}
+
+fn output_intent_page(page: &mut Page) {
+    fill_page(page, fill(cmyk::Color::new(0, 255, 255, 0)), None);
+}
+
+#[test]
+fn output_intent_none() {
+    let pdf = page_pdf(settings_1(), output_intent_page);
+
+    assert!(!contains(&pdf, b"/OutputIntents"));
+}
+
+#[snapshot(settings_35)]
+fn output_intent_custom(page: &mut Page) {
+    output_intent_page(page);
+}
+
+#[test]
+fn output_intent_custom() {
+    let pdf = page_pdf(settings_35(), output_intent_page);
+
+    assert!(contains(&pdf, b"/OutputIntents"));
+    assert!(contains(&pdf, b"/S /GTS_PDFX"));
+    assert!(contains(&pdf, b"/OutputConditionIdentifier (CGATS TR 001)"));
+    assert!(contains(&pdf, b"/RegistryName (http://www.color.org)"));
+    assert!(contains(&pdf, b"/N 4"));
+    assert!(!contains(&pdf, b"/Info"));
+}
+
+#[snapshot(settings_36)]
+fn output_intent_pdf_a(page: &mut Page) {
+    output_intent_page(page);
+}
+
+#[test]
+fn output_intent_pdf_a() {
+    let pdf = page_pdf(settings_36(), output_intent_page);
+
+    assert_eq!(count(&pdf, b"/Type /OutputIntent"), 1);
+    assert!(contains(&pdf, b"/S /GTS_PDFA1"));
+    assert!(contains(&pdf, b"/OutputConditionIdentifier (CGATS TR 001)"));
+    // The output intent and the color space share the same profile.
+    assert_eq!(count(&pdf, b"/N 4"), 1);
+}
+
+fn output_intent_multiple_page(page: &mut Page) {
+    fill_page(page, fill(rgb::Color::new(255, 0, 0)), None);
+}
+
+#[snapshot(settings_37)]
+fn output_intent_multiple(page: &mut Page) {
+    output_intent_multiple_page(page);
+}
+
+#[test]
+fn output_intent_multiple() {
+    let pdf = page_pdf(settings_37(), output_intent_multiple_page);
+
+    // An sRGB output intent is added for PDF/A.
+    assert_eq!(count(&pdf, b"/Type /OutputIntent"), 2);
+    assert!(contains(&pdf, b"/S /GTS_PDFA1"));
+    assert!(contains(&pdf, b"/OutputCondition (sRGB)"));
+    assert!(contains(&pdf, b"/S /GTS_PDFX"));
+}
+
+#[test]
+fn output_intent_inconsistent_profiles() {
+    let mut document = Document::new_with(SerializeSettings {
+        output_intents: vec![swop().with_subtype(OutputIntentSubtype::PdfX)],
+        ..settings_7()
+    });
+    document.start_page().finish();
+
+    // The fallback sRGB output intent doesn't share the profile of the PDF/X one.
+    assert_eq!(
+        validation_errors(document.finish()),
+        vec![ValidationError::InconsistentOutputIntentProfiles]
+    );
+}
+
+#[snapshot(settings_38)]
+fn output_intent_pdf_a_without_profile(page: &mut Page) {
+    output_intent_multiple_page(page);
+}
+
+#[test]
+fn output_intent_pdf_a_without_profile() {
+    let pdf = page_pdf(settings_38(), output_intent_multiple_page);
+
+    // A PDF/A output intent without a profile doesn't satisfy the validator,
+    // so the sRGB one is added as well.
+    assert_eq!(count(&pdf, b"/Type /OutputIntent"), 2);
+    assert!(contains(&pdf, b"/OutputCondition (sRGB)"));
+}
@@ -0,0 +1,473 @@
+use std::num::NonZeroU32;
+
+use krilla::action::{FieldSelection, LinkAction, ResetFormAction, SubmitFormAction, SubmitFormat};
+use krilla::annotation::Annotation;
+use krilla::configure::ValidationError;
+use krilla::form::{
+    CheckBox, ChoiceOption, ComboBox, ListBox, PushButton, RadioButton, RadioGroup, TextAlignment,
+    TextField, ToggleSymbol,
+};
+use krilla::geom::Rect;
+use krilla::page::PageSettings;
+use krilla::stream::Stream;
+use krilla::surface::Surface;
+use krilla::text::Font;
+use krilla::Document;
+use krilla_macros::{snapshot, visreg};
+
+use crate::{
+    contains, loc, rect_to_path, red_fill, settings_1, settings_7, validation_errors, NOTO_SANS,
+};
+
+fn text_field(name: &str, y: f32) -> TextField {
+    let font = Font::new(NOTO_SANS.clone(), 0).unwrap();
+    TextField::new(
+        name.to_string(),
+        Rect::from_xywh(20.0, y, 160.0, 30.0).unwrap(),
+        font,
+        12.0,
+    )
+}
+
+fn form_text_fields_impl(document: &mut Document) {
+    let mut page = document.start_page_with(PageSettings::from_wh(200.0, 300.0).unwrap());
+
+    page.add_annotation(Annotation::new_widget(
+        text_field("name", 20.0)
+            .with_default_value("John Doe".to_string())
+            .with_alignment(TextAlignment::Center)
+            .with_required(true)
+            .into(),
+        Some("Your name".to_string()),
+    ));
+    page.add_annotation(
+        text_field("comment", 60.0)
+            .with_default_value(
+                "A first line\nand a second line that is long enough to wrap".to_string(),
+            )
+            .with_multiline(true)
+            .into(),
+    );
+    page.add_annotation(
+        text_field("password", 100.0)
+            .with_default_value("secret".to_string())
+            .with_password(true)
+            .into(),
+    );
+    page.add_annotation(
+        text_field("zip", 140.0)
+            .with_default_value("12345".to_string())
+            .with_max_len(NonZeroU32::new(5).unwrap())
+            .with_comb(true)
+            .into(),
+    );
+    page.add_annotation(
+        text_field("empty", 180.0)
+            .with_alignment(TextAlignment::Right)
+            .with_read_only(true)
+            .into(),
+    );
+    page.finish();
+}
+
+#[visreg(document)]
+fn form_text_fields(document: &mut Document) {
+    form_text_fields_impl(document);
+}
+
+#[snapshot(document)]
+fn form_text_fields(document: &mut Document) {
+    form_text_fields_impl(document);
+}
+
+#[test]
+fn form_text_fields_contents() {
+    let mut document = Document::new_with(settings_1());
+    form_text_fields_impl(&mut document);
+    let pdf = document.finish().unwrap();
+
+    assert!(contains(&pdf, b"/AcroForm"));
+    assert!(contains(&pdf, b"/DR"));
+    assert!(contains(&pdf, b"/FT /Tx"));
+    assert!(contains(&pdf, b"/DA (/f0 12 Tf 0 g)"));
+    assert!(contains(&pdf, b"/MaxLen 5"));
+    assert!(!contains(&pdf, b"secret"));
+    // The font in the default resources can render Latin-1 text entered by the user.
+    assert!(contains(&pdf, b"<00E9>"));
+}
+
+fn form_text_field_long_word_impl(document: &mut Document) {
+    let mut page = document.start_page();
+    page.add_annotation(
+        text_field("long_word", 20.0)
+            .with_default_value(
+                "Short words and a pneumonoultramicroscopicsilicovolcanoconiosis".to_string(),
+            )
+            .with_multiline(true)
+            .into(),
+    );
+    page.finish();
+}
+
+#[visreg(document)]
+fn form_text_field_long_word(document: &mut Document) {
+    form_text_field_long_word_impl(document);
+}
+
+#[snapshot(document)]
+fn form_text_field_long_word(document: &mut Document) {
+    form_text_field_long_word_impl(document);
+}
+
+fn form_text_field_pdf_a_impl(document: &mut Document) {
+    let mut page = document.start_page();
+    page.add_annotation(Annotation::new_widget(
+        text_field("name", 20.0)
+            .with_default_value("John Doe".to_string())
+            .into(),
+        Some("Your name".to_string()),
+    ));
+    page.finish();
+}
+
+#[snapshot(document, settings_7)]
+fn form_text_field_pdf_a(document: &mut Document) {
+    form_text_field_pdf_a_impl(document);
+}
+
+#[test]
+fn form_text_field_pdf_a() {
+    let mut document = Document::new_with(settings_7());
+    form_text_field_pdf_a_impl(&mut document);
+
+    assert!(document.finish().is_ok());
+}
+
+fn rect(x: f32, y: f32) -> Rect {
+    Rect::from_xywh(x, y, 20.0, 20.0).unwrap()
+}
+
+fn red_square(surface: &mut Surface) -> Stream {
+    let mut stream_builder = surface.stream_builder();
+    let mut sub_surface = stream_builder.surface();
+    sub_surface.set_fill(Some(red_fill(1.0)));
+    sub_surface.draw_path(&rect_to_path(4.0, 4.0, 16.0, 16.0));
+    sub_surface.finish();
+
+    stream_builder.finish()
+}
+
+fn form_check_boxes_impl(document: &mut Document) {
+    let mut page = document.start_page_with(PageSettings::from_wh(200.0, 200.0).unwrap());
+    let mut surface = page.surface();
+    let on = red_square(&mut surface);
+    surface.finish();
+
+    page.add_annotation(
+        CheckBox::new("terms".to_string(), rect(20.0, 20.0))
+            .with_checked(true)
+            .with_required(true)
+            .into(),
+    );
+    page.add_annotation(
+        CheckBox::new("newsletter".to_string(), rect(20.0, 50.0))
+            .with_export_value("Subscribe".to_string())
+            .unwrap()
+            .with_symbol(ToggleSymbol::Cross)
+            .into(),
+    );
+    page.add_annotation(
+        CheckBox::new("custom".to_string(), rect(20.0, 80.0))
+            .with_appearance(on, None)
+            .with_checked(true)
+            .into(),
+    );
+    page.finish();
+}
+
+#[visreg(document)]
+fn form_check_boxes(document: &mut Document) {
+    form_check_boxes_impl(document);
+}
+
+#[snapshot(document)]
+fn form_check_boxes(document: &mut Document) {
+    form_check_boxes_impl(document);
+}
+
+#[test]
+fn form_check_boxes_contents() {
+    let mut document = Document::new_with(settings_1());
+    form_check_boxes_impl(&mut document);
+    let pdf = document.finish().unwrap();
+
+    assert!(contains(&pdf, b"/FT /Btn"));
+    assert!(contains(&pdf, b"/AS /Yes"));
+    assert!(contains(&pdf, b"/AS /Off"));
+    assert!(contains(&pdf, b"/Subscribe"));
+    assert!(contains(&pdf, b"/CA (8)"));
+}
+
+fn form_radio_group_across_pages_impl(document: &mut Document) {
+    let group = RadioGroup::new("size".to_string())
+        .with_value("M".to_string())
+        .with_no_toggle_to_off(true)
+        .with_radios_in_unison(true);
+
+    let mut page = document.start_page_with(PageSettings::from_wh(200.0, 200.0).unwrap());
+    page.add_annotation(
+        RadioButton::new(group.clone(), "S".to_string(), rect(20.0, 20.0))
+            .unwrap()
+            .into(),
+    );
+    page.add_annotation(
+        RadioButton::new(group.clone(), "M".to_string(), rect(50.0, 20.0))
+            .unwrap()
+            .into(),
+    );
+    page.finish();
+
+    let mut page = document.start_page_with(PageSettings::from_wh(200.0, 200.0).unwrap());
+    page.add_annotation(
+        RadioButton::new(group.clone(), "M".to_string(), rect(20.0, 20.0))
+            .unwrap()
+            .with_symbol(ToggleSymbol::Star)
+            .into(),
+    );
+    page.add_annotation(
+        RadioButton::new(group, "L".to_string(), rect(50.0, 20.0))
+            .unwrap()
+            .into(),
+    );
+    page.finish();
+}
+
+#[snapshot(document)]
+fn form_radio_group_across_pages(document: &mut Document) {
+    form_radio_group_across_pages_impl(document);
+}
+
+#[test]
+fn form_radio_group_across_pages() {
+    let mut document = Document::new_with(settings_1());
+    form_radio_group_across_pages_impl(&mut document);
+    let pdf = document.finish().unwrap();
+
+    assert!(contains(&pdf, b"/Kids"));
+    assert!(contains(&pdf, b"/Parent"));
+    // Radio, NoToggleToOff and RadiosInUnison.
+    assert!(contains(
+        &pdf,
+        format!("/Ff {}", (1 << 15) | (1 << 14) | (1 << 25)).as_bytes()
+    ));
+    assert!(contains(&pdf, b"/V /M"));
+    assert!(contains(&pdf, b"/AS /M"));
+    assert!(contains(&pdf, b"/AS /Off"));
+}
+
+#[test]
+fn form_button_export_value_off() {
+    assert!(RadioButton::new(
+        RadioGroup::new("choice".to_string()),
+        "Off".to_string(),
+        rect(20.0, 20.0),
+    )
+    .is_none());
+    assert!(CheckBox::new("terms".to_string(), rect(20.0, 20.0))
+        .with_export_value("Off".to_string())
+        .is_none());
+}
+
+fn form_buttons_pdf_a_impl(document: &mut Document) {
+    let mut page = document.start_page();
+    page.add_annotation(Annotation::new_widget(
+        CheckBox::new("terms".to_string(), rect(20.0, 20.0))
+            .with_checked(true)
+            .into(),
+        Some("Accept the terms".to_string()),
+    ));
+    page.add_annotation(Annotation::new_widget(
+        RadioButton::new(
+            RadioGroup::new("choice".to_string()),
+            "A".to_string(),
+            rect(20.0, 50.0),
+        )
+        .unwrap()
+        .into(),
+        Some("Choice A".to_string()),
+    ));
+    page.finish();
+}
+
+#[snapshot(document, settings_7)]
+fn form_buttons_pdf_a(document: &mut Document) {
+    form_buttons_pdf_a_impl(document);
+}
+
+#[test]
+fn form_buttons_pdf_a() {
+    let mut document = Document::new_with(settings_7());
+    form_buttons_pdf_a_impl(&mut document);
+
+    assert!(document.finish().is_ok());
+}
+
+fn options() -> Vec<ChoiceOption> {
+    vec![
+        ChoiceOption::new("de".to_string()).with_display_value("Germany".to_string()),
+        ChoiceOption::new("at".to_string()).with_display_value("Austria".to_string()),
+        ChoiceOption::new("Switzerland".to_string()),
+    ]
+}
+
+fn noto_sans() -> Font {
+    Font::new(NOTO_SANS.clone(), 0).unwrap()
+}
+
+fn form_choice_fields_impl(document: &mut Document) {
+    let mut page = document.start_page_with(PageSettings::from_wh(200.0, 300.0).unwrap());
+
+    page.add_annotation(
+        ComboBox::new(
+            "country".to_string(),
+            Rect::from_xywh(20.0, 20.0, 160.0, 20.0).unwrap(),
+            noto_sans(),
+            12.0,
+            options(),
+        )
+        .with_value("at".to_string())
+        .with_sort(true)
+        .with_commit_on_change(true)
+        .into(),
+    );
+    page.add_annotation(
+        ComboBox::new(
+            "custom_country".to_string(),
+            Rect::from_xywh(20.0, 50.0, 160.0, 20.0).unwrap(),
+            noto_sans(),
+            12.0,
+            options(),
+        )
+        .with_value("France".to_string())
+        .with_editable(true)
+        .into(),
+    );
+    page.add_annotation(
+        ListBox::new(
+            "countries".to_string(),
+            Rect::from_xywh(20.0, 80.0, 160.0, 60.0).unwrap(),
+            noto_sans(),
+            12.0,
+            options(),
+        )
+        .with_selected(vec!["de".to_string(), "Switzerland".to_string()])
+        .with_multi_select(true)
+        .into(),
+    );
+    page.finish();
+}
+
+#[visreg(document)]
+fn form_choice_fields(document: &mut Document) {
+    form_choice_fields_impl(document);
+}
+
+#[snapshot(document)]
+fn form_choice_fields(document: &mut Document) {
+    form_choice_fields_impl(document);
+}
+
+#[test]
+fn form_choice_fields_contents() {
+    let mut document = Document::new_with(settings_1());
+    form_choice_fields_impl(&mut document);
+    let pdf = document.finish().unwrap();
+
+    assert!(contains(&pdf, b"/FT /Ch"));
+    assert!(contains(&pdf, b"/Opt"));
+    assert!(contains(&pdf, b"(France)"));
+    assert!(contains(&pdf, b"/I [0 2]"));
+}
+
+fn form_push_buttons_impl(document: &mut Document) {
+    let mut page = document.start_page_with(PageSettings::from_wh(200.0, 200.0).unwrap());
+
+    page.add_annotation(
+        PushButton::new(
+            "submit".to_string(),
+            Rect::from_xywh(20.0, 20.0, 80.0, 20.0).unwrap(),
+            SubmitFormAction::new("https://example.com/submit".to_string())
+                .with_format(SubmitFormat::Html)
+                .with_get_method(true)
+                .with_fields(FieldSelection::Exclude(vec!["password".to_string()]))
+                .into(),
+        )
+        .with_caption("Submit".to_string(), noto_sans(), 12.0)
+        .into(),
+    );
+    page.add_annotation(
+        PushButton::new(
+            "reset".to_string(),
+            Rect::from_xywh(20.0, 50.0, 80.0, 20.0).unwrap(),
+            ResetFormAction::new().into(),
+        )
+        .with_caption("Reset".to_string(), noto_sans(), 12.0)
+        .into(),
+    );
+    page.add_annotation(
+        PushButton::new(
+            "website".to_string(),
+            Rect::from_xywh(20.0, 80.0, 80.0, 20.0).unwrap(),
+            LinkAction::new("https://example.com".to_string()).into(),
+        )
+        .into(),
+    );
+    page.finish();
+}
+
+#[visreg(document)]
+fn form_push_buttons(document: &mut Document) {
+    form_push_buttons_impl(document);
+}
+
+#[snapshot(document)]
+fn form_push_buttons(document: &mut Document) {
+    form_push_buttons_impl(document);
+}
+
+#[test]
+fn form_push_buttons_contents() {
+    let mut document = Document::new_with(settings_1());
+    form_push_buttons_impl(&mut document);
+    let pdf = document.finish().unwrap();
+
+    assert!(contains(&pdf, b"/S /SubmitForm"));
+    assert!(contains(&pdf, b"/S /ResetForm"));
+    assert!(contains(&pdf, b"/Flags 13"));
+    assert!(contains(&pdf, b"/CA (Submit)"));
+}
+
+#[test]
+fn form_reset_action_pdf_a() {
+    let mut d = Document::new_with(settings_7());
+    let mut page = d.start_page();
+    let annot_loc = loc(1);
+    page.add_annotation(
+        Annotation::new_widget(
+            PushButton::new(
+                "reset".to_string(),
+                Rect::from_xywh(20.0, 50.0, 80.0, 20.0).unwrap(),
+                ResetFormAction::new().into(),
+            )
+            .into(),
+            Some("Reset the form".to_string()),
+        )
+        .with_location(Some(annot_loc)),
+    );
+    page.finish();
+
+    assert_eq!(
+        validation_errors(d.finish()),
+        vec![ValidationError::ResetFormAction(Some(annot_loc))]
+    );
+}
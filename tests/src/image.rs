@@ -1,12 +1,14 @@
-use krilla::geom::Size;
-use krilla::image::Image;
+use krilla::color::RenderingIntent;
+use krilla::geom::{Size, Transform};
+use krilla::image::{BitsPerComponent, CustomImage, Image, ImageColorspace, StencilMask};
 use krilla::page::Page;
 use krilla::surface::Surface;
 use krilla::Document;
 use krilla_macros::{snapshot, visreg};
 
 use crate::{
-    load_custom_image, load_gif_image, load_jpg_image, load_png_image, load_webp_image, ASSETS_PATH,
+    contains, count, load_custom_image, load_gif_image, load_jpg_image, load_png_image,
+    load_webp_image, page_pdf, red_fill, settings_1, settings_7, ASSETS_PATH,
 };
 
 fn image_visreg_impl(surface: &mut Surface, name: &str, load_fn: fn(&str) -> Image) {
@@ -204,3 +206,209 @@ fn image_no_device_cs(page: &mut Page) {
     let size = Size::from_wh(image.size().0 as f32, image.size().1 as f32).unwrap();
     surface.draw_image(image, size);
 }
+
+fn image_stencil_mask_impl(surface: &mut Surface) {
+    let mask = StencilMask::new(vec![0b1010_1010, 0b0101_0101], 8, 2, false).unwrap();
+    surface.set_fill(Some(red_fill(1.0)));
+    surface.draw_stencil_mask(mask, Size::from_wh(80.0, 20.0).unwrap());
+}
+
+#[visreg]
+fn image_stencil_mask(surface: &mut Surface) {
+    image_stencil_mask_impl(surface);
+}
+
+#[snapshot]
+fn image_stencil_mask(page: &mut Page) {
+    let mut surface = page.surface();
+    image_stencil_mask_impl(&mut surface);
+}
+
+#[test]
+fn image_stencil_mask_contents() {
+    let pdf = page_pdf(settings_1(), image_stencil_mask_snapshot_impl);
+    assert!(contains(&pdf, b"/ImageMask true"));
+    assert!(contains(&pdf, b"/BitsPerComponent 1"));
+    assert!(contains(&pdf, b"/Decode [1 0]"));
+    assert!(contains(&pdf, b"1 0 0 rg"));
+    assert!(contains(&pdf, b"80 0 0 -20 0 20 cm"));
+}
+
+#[test]
+fn image_stencil_mask_invalid_length() {
+    assert!(StencilMask::new(vec![0; 3], 9, 2, false).is_err());
+    assert!(StencilMask::new(vec![0; 4], 9, 2, false).is_ok());
+}
+
+#[derive(Hash, Clone)]
+struct BilevelImage;
+
+impl CustomImage for BilevelImage {
+    fn color_channel(&self) -> &[u8] {
+        // Each row of 10 pixels is padded to two bytes.
+        &[0b1111_0000, 0b1100_0000, 0b0000_1111, 0b0000_0000]
+    }
+
+    fn alpha_channel(&self) -> Option<&[u8]> {
+        None
+    }
+
+    fn bits_per_component(&self) -> BitsPerComponent {
+        BitsPerComponent::One
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (10, 2)
+    }
+
+    fn icc_profile(&self) -> Option<&[u8]> {
+        None
+    }
+
+    fn color_space(&self) -> ImageColorspace {
+        ImageColorspace::Luma
+    }
+}
+
+fn image_one_bit_custom_impl(surface: &mut Surface) {
+    let image = Image::from_custom(BilevelImage, false).unwrap();
+    surface.draw_image(image, Size::from_wh(100.0, 20.0).unwrap());
+}
+
+#[visreg]
+fn image_one_bit_custom(surface: &mut Surface) {
+    image_one_bit_custom_impl(surface);
+}
+
+#[snapshot]
+fn image_one_bit_custom(page: &mut Page) {
+    let mut surface = page.surface();
+    image_one_bit_custom_impl(&mut surface);
+}
+
+#[test]
+fn image_one_bit_custom_contents() {
+    let pdf = page_pdf(settings_1(), image_one_bit_custom_snapshot_impl);
+    assert!(contains(&pdf, b"/BitsPerComponent 1"));
+    assert!(contains(&pdf, b"/ColorSpace /DeviceGray"));
+}
+
+fn image_snapshot_impl(page: &mut Page, image: Image) {
+    let mut surface = page.surface();
+    let size = Size::from_wh(image.size().0 as f32, image.size().1 as f32).unwrap();
+    surface.draw_image(image, size);
+}
+
+#[visreg]
+fn image_indexed2_png(surface: &mut Surface) {
+    image_visreg_impl(surface, "indexed2.png", load_png_image);
+}
+
+// The palette is kept with its original bit depth.
+#[snapshot]
+fn image_indexed2_png(page: &mut Page) {
+    image_snapshot_impl(page, load_png_image("indexed2.png"));
+}
+
+#[visreg]
+fn image_indexed2_trns_png(surface: &mut Surface) {
+    image_visreg_impl(surface, "indexed2_trns.png", load_png_image);
+}
+
+// The indices are expanded to 8 bits so that they can share the soft mask with the alpha channel.
+#[snapshot]
+fn image_indexed2_trns_png(page: &mut Page) {
+    image_snapshot_impl(page, load_png_image("indexed2_trns.png"));
+}
+
+#[snapshot]
+fn image_rgb8_gif(page: &mut Page) {
+    image_snapshot_impl(page, load_gif_image("rgb8.gif"));
+}
+
+#[snapshot]
+fn image_rgba8_gif(page: &mut Page) {
+    image_snapshot_impl(page, load_gif_image("rgba8.gif"));
+}
+
+#[test]
+fn image_indexed2_png_contents() {
+    let pdf = page_pdf(settings_1(), image_indexed2_png_snapshot_impl);
+    assert!(contains(&pdf, b"/Indexed /DeviceRGB 3"));
+    assert!(contains(&pdf, b"/BitsPerComponent 2"));
+    assert!(!contains(&pdf, b"/SMask"));
+}
+
+#[test]
+fn image_indexed2_trns_png_contents() {
+    let pdf = page_pdf(settings_1(), image_indexed2_trns_png_snapshot_impl);
+    assert!(contains(&pdf, b"/Indexed /DeviceRGB 3"));
+    assert!(contains(&pdf, b"/BitsPerComponent 8"));
+    assert!(contains(&pdf, b"/SMask"));
+}
+
+#[test]
+fn image_indexed_gif_contents() {
+    let pdf = page_pdf(settings_1(), image_rgb8_gif_snapshot_impl);
+    assert!(contains(&pdf, b"/Indexed /DeviceRGB"));
+    assert!(!contains(&pdf, b"/SMask"));
+
+    let pdf = page_pdf(settings_1(), image_rgba8_gif_snapshot_impl);
+    assert!(contains(&pdf, b"/Indexed /DeviceRGB"));
+    assert!(contains(&pdf, b"/SMask"));
+}
+
+#[visreg]
+fn image_rendering_intent(surface: &mut Surface) {
+    let image = load_png_image("rgb8.png").with_rendering_intent(RenderingIntent::Perceptual);
+    let size = image.size();
+    surface.draw_image(image, Size::from_wh(size.0 as f32, size.1 as f32).unwrap());
+}
+
+#[snapshot]
+fn image_rendering_intent(page: &mut Page) {
+    image_snapshot_impl(
+        page,
+        load_png_image("rgb8.png").with_rendering_intent(RenderingIntent::Perceptual),
+    );
+}
+
+#[snapshot(settings_7)]
+fn image_rendering_intent_pdf_a(page: &mut Page) {
+    let image = load_png_image("rgb8.png");
+    let size = Size::from_wh(50.0, 50.0).unwrap();
+    let mut surface = page.surface();
+
+    // Both draws should share the same image XObject.
+    surface.draw_image(
+        image
+            .clone()
+            .with_rendering_intent(RenderingIntent::Perceptual),
+        size,
+    );
+    surface.push_transform(&Transform::from_translate(100.0, 0.0));
+    surface.push_rendering_intent(RenderingIntent::Saturation);
+    surface.draw_image(image, size);
+    surface.pop();
+    surface.pop();
+}
+
+#[test]
+fn image_rendering_intent_contents() {
+    let pdf = page_pdf(settings_1(), image_rendering_intent_snapshot_impl);
+    assert!(contains(&pdf, b"/RI /Perceptual"));
+    assert!(!contains(&pdf, b"/Intent"));
+
+    let pdf = page_pdf(settings_1(), |page| {
+        image_snapshot_impl(page, load_png_image("rgb8.png"))
+    });
+    assert!(!contains(&pdf, b"/RI"));
+}
+
+#[test]
+fn image_rendering_intent_shared() {
+    let pdf = page_pdf(settings_7(), image_rendering_intent_pdf_a_snapshot_impl);
+    assert_eq!(count(&pdf, b"/Subtype /Image"), 1);
+    assert!(contains(&pdf, b"/RI /Perceptual"));
+    assert!(contains(&pdf, b"/RI /Saturation"));
+}
@@ -16,15 +16,17 @@ use difference::{Changeset, Difference};
 use krilla::action::LinkAction;
 use krilla::annotation::{Annotation, LinkAnnotation, Target};
 use krilla::color::{cmyk, luma, rgb};
-use krilla::configure::{Accessibility, Archival, Configuration, ConfigurationBuilder, PdfVersion};
+use krilla::configure::{
+    Accessibility, Archival, Configuration, ConfigurationBuilder, PdfVersion, PrintExchange,
+};
 use krilla::error::KrillaError;
 use krilla::geom::{Path, PathBuilder, Point, Transform};
-use krilla::icc::ICCProfile;
+use krilla::icc::{ICCProfile, OutputIntent, OutputIntentSubtype};
 use krilla::image::{BitsPerComponent, CustomImage, Image, ImageColorspace};
 use krilla::mask::{Mask, MaskType};
 use krilla::metadata::{DateTime, Metadata};
 use krilla::num::NormalizedF32;
-use krilla::page::PageSettings;
+use krilla::page::{Page, PageSettings};
 use krilla::paint::{Fill, Stop, Stroke};
 use krilla::pdf::{Pdf, PdfDocument};
 use krilla::stream::Stream;
@@ -44,9 +46,11 @@ use skrifa::raw::TableProvider;
 use skrifa::{FontRef, MetadataProvider};
 
 mod annotation;
+mod color;
 mod destination;
 mod embed;
 mod font;
+mod form;
 mod graphic;
 mod image;
 mod mask;
@@ -809,9 +813,18 @@ pub fn youtube_link(x: f32, y: f32, w: f32, h: f32) -> Annotation {
 }
 
 pub fn basic_pattern_stream(mut stream_builder: StreamBuilder) -> Stream {
+    basic_pattern_impl(stream_builder.surface());
+    stream_builder.finish()
+}
+
+pub fn basic_uncolored_pattern_stream(mut stream_builder: StreamBuilder) -> Stream {
+    basic_pattern_impl(stream_builder.uncolored_surface());
+    stream_builder.finish()
+}
+
+fn basic_pattern_impl(mut surface: Surface) {
     let path = rect_to_path(0.0, 0.0, 10.0, 10.0);
 
-    let mut surface = stream_builder.surface();
     surface.set_fill(Some(red_fill(1.0)));
     surface.draw_path(&path);
     surface.push_transform(&Transform::from_translate(10.0, 10.0));
@@ -819,8 +832,6 @@ pub fn basic_pattern_stream(mut stream_builder: StreamBuilder) -> Stream {
     surface.draw_path(&path);
     surface.pop();
     surface.finish();
-
-    stream_builder.finish()
 }
 
 pub static FONTDB: LazyLock<Arc<fontdb::Database>> = LazyLock::new(|| {
@@ -895,6 +906,8 @@ pub fn settings_1() -> SerializeSettings {
         enable_tagging: true,
         configuration: Configuration::default(),
         render_svg_glyph_fn: render_svg_glyph,
+        gradient_samples: None,
+        output_intents: vec![],
     }
 }
 
@@ -1181,6 +1194,78 @@ pub fn settings_33() -> SerializeSettings {
     }
 }
 
+pub fn settings_34() -> SerializeSettings {
+    SerializeSettings {
+        gradient_samples: Some(64),
+        ..settings_1()
+    }
+}
+
+pub fn settings_35() -> SerializeSettings {
+    SerializeSettings {
+        output_intents: vec![swop()],
+        ..settings_1()
+    }
+}
+
+pub fn settings_36() -> SerializeSettings {
+    SerializeSettings {
+        cmyk_profile: Some(swop_profile()),
+        output_intents: vec![swop()],
+        ..settings_7()
+    }
+}
+
+pub fn settings_37() -> SerializeSettings {
+    SerializeSettings {
+        output_intents: vec![OutputIntent::new("FOGRA39")
+            .with_registry_name("http://www.color.org")
+            .with_subtype(OutputIntentSubtype::PdfX)],
+        ..settings_7()
+    }
+}
+
+pub fn settings_38() -> SerializeSettings {
+    SerializeSettings {
+        output_intents: vec![OutputIntent::new("FOGRA39")],
+        ..settings_7()
+    }
+}
+
+pub fn settings_39() -> SerializeSettings {
+    SerializeSettings {
+        configuration: ConfigurationBuilder::new()
+            .with_print_exchange_validator(PrintExchange::X4)
+            .finish()
+            .unwrap(),
+        output_intents: vec![swop()],
+        ..settings_1()
+    }
+}
+
+pub fn settings_40() -> SerializeSettings {
+    SerializeSettings {
+        configuration: ConfigurationBuilder::new()
+            .with_print_exchange_validator(PrintExchange::X3)
+            .finish()
+            .unwrap(),
+        output_intents: vec![swop()],
+        ..settings_1()
+    }
+}
+
+pub fn swop_profile() -> ICCProfile<4> {
+    ICCProfile::new(&std::fs::read(ASSETS_PATH.join("icc/CGATS001Compat-v2-micro.icc")).unwrap())
+        .unwrap()
+}
+
+pub fn swop() -> OutputIntent {
+    OutputIntent::new("CGATS TR 001")
+        .with_output_condition("SWOP")
+        .with_registry_name("http://www.color.org")
+        .with_profile(swop_profile())
+}
+
 pub fn metadata_1() -> Metadata {
     Metadata::new()
         .language("en".to_string())
@@ -1196,6 +1281,26 @@ pub fn validation_errors(
     }
 }
 
+/// Create a document with a single page and return the finished PDF.
+pub fn page_pdf(settings: SerializeSettings, f: impl FnOnce(&mut Page)) -> Vec<u8> {
+    let mut document = Document::new_with(settings);
+    let mut page = document.start_page();
+    f(&mut page);
+    page.finish();
+
+    document.finish().unwrap()
+}
+
+/// Whether the bytes of a PDF contain the given byte sequence.
+pub fn contains(pdf: &[u8], needle: &[u8]) -> bool {
+    count(pdf, needle) > 0
+}
+
+/// How often the bytes of a PDF contain the given byte sequence.
+pub fn count(pdf: &[u8], needle: &[u8]) -> usize {
+    pdf.windows(needle.len()).filter(|w| *w == needle).count()
+}
+
 pub fn metadata_2() -> Metadata {
     Metadata::new()
         .language("en".to_string())
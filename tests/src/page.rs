@@ -1,12 +1,16 @@
 use std::num::NonZeroU32;
 
 use krilla::geom::Rect;
+use krilla::icc::{ICCProfile, OutputIntent};
 use krilla::page::{NumberingStyle, PageLabel, PageSettings};
 use krilla::Document;
 use krilla_macros::{snapshot, visreg};
 use tiny_skia_path::PathBuilder;
 
-use crate::{blue_fill, green_fill, load_pdf, purple_fill, rect_to_path, red_fill};
+use crate::{
+    blue_fill, contains, count, green_fill, load_pdf, purple_fill, rect_to_path, red_fill,
+    settings_1, settings_25,
+};
 
 fn media_box_impl(d: &mut Document, media_box: Rect) {
     let mut page = d.start_page_with(
@@ -95,3 +99,50 @@ fn page_media_box_bottom_right(d: &mut Document) {
 fn page_media_box_zoomed_out(d: &mut Document) {
     media_box_impl(d, Rect::from_xywh(-150.0, -200.0, 500.0, 500.0).unwrap())
 }
+
+fn page_output_intents_impl(document: &mut Document) {
+    let profile =
+        ICCProfile::<1>::new(include_bytes!("../../crates/krilla/icc/sGrey-v4.icc")).unwrap();
+    let output_intent = OutputIntent::new("Custom")
+        .with_info("sGrey")
+        .with_profile(profile);
+
+    for _ in 0..2 {
+        let settings = PageSettings::from_wh(200.0, 200.0)
+            .unwrap()
+            .with_output_intents(vec![output_intent.clone()]);
+        document.start_page_with(settings).finish();
+    }
+}
+
+#[snapshot(document, settings_25)]
+fn page_output_intents(document: &mut Document) {
+    page_output_intents_impl(document);
+}
+
+#[test]
+fn page_output_intents() {
+    let mut document = Document::new_with(settings_25());
+    page_output_intents_impl(&mut document);
+    let pdf = document.finish().unwrap();
+
+    // Both pages share the same output intent.
+    assert_eq!(count(&pdf, b"/Type /OutputIntent"), 1);
+    assert!(contains(&pdf, b"/OutputIntents ["));
+    assert!(contains(&pdf, b"/Info (sGrey)"));
+}
+
+#[snapshot(document)]
+fn page_output_intents_pdf_17(document: &mut Document) {
+    page_output_intents_impl(document);
+}
+
+#[test]
+fn page_output_intents_pdf_17() {
+    let mut document = Document::new_with(settings_1());
+    page_output_intents_impl(&mut document);
+    let pdf = document.finish().unwrap();
+
+    // Page-level output intents require PDF 2.0, so they are dropped.
+    assert!(!contains(&pdf, b"/OutputIntents"));
+}
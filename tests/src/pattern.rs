@@ -1,13 +1,19 @@
 mod shading {
+    use krilla::color::{luma, rgb};
+    use krilla::geom::Point;
     use krilla::num::NormalizedF32;
     use krilla::page::Page;
-    use krilla::paint::{Fill, LinearGradient, RadialGradient, SpreadMethod, SweepGradient};
+    use krilla::paint::{
+        CoonsPatch, Fill, LinearGradient, Mesh, MeshGradient, MeshVertex, RadialGradient,
+        SpreadMethod, SweepGradient, TensorPatch,
+    };
     use krilla::surface::Surface;
+    use krilla::Document;
     use krilla_macros::{snapshot, visreg};
 
     use crate::{
-        rect_to_path, stops_with_1_solid, stops_with_2_solid_1, stops_with_3_luma,
-        stops_with_3_solid_1,
+        contains, page_pdf, rect_to_path, settings_1, settings_34, settings_7, stops_with_1_solid,
+        stops_with_2_solid_1, stops_with_3_luma, stops_with_3_solid_1,
     };
 
     #[visreg(all)]
@@ -174,15 +180,391 @@ mod shading {
         }));
         surface.draw_path(&path);
     }
+
+    fn vertex(x: f32, y: f32, color: rgb::Color, opacity: f32) -> MeshVertex {
+        MeshVertex {
+            point: Point::from_xy(x, y),
+            color: color.into(),
+            opacity: NormalizedF32::new(opacity).unwrap(),
+        }
+    }
+
+    fn red() -> rgb::Color {
+        rgb::Color::new(255, 0, 0)
+    }
+
+    fn green() -> rgb::Color {
+        rgb::Color::new(0, 255, 0)
+    }
+
+    fn blue() -> rgb::Color {
+        rgb::Color::new(0, 0, 255)
+    }
+
+    fn corners() -> [krilla::color::Color; 4] {
+        [
+            red().into(),
+            green().into(),
+            blue().into(),
+            rgb::Color::new(255, 255, 0).into(),
+        ]
+    }
+
+    fn draw_mesh(surface: &mut Surface, mesh: Mesh) {
+        surface.set_fill(Some(Fill {
+            paint: MeshGradient::new(mesh, Default::default(), false)
+                .unwrap()
+                .into(),
+            opacity: NormalizedF32::ONE,
+            rule: Default::default(),
+        }));
+        surface.draw_path(&rect_to_path(20.0, 20.0, 180.0, 180.0));
+    }
+
+    fn triangles() -> Mesh {
+        Mesh::Triangles(vec![
+            [
+                vertex(20.0, 20.0, red(), 1.0),
+                vertex(180.0, 20.0, green(), 1.0),
+                vertex(20.0, 180.0, blue(), 1.0),
+            ],
+            [
+                vertex(180.0, 20.0, green(), 1.0),
+                vertex(180.0, 180.0, red(), 0.5),
+                vertex(20.0, 180.0, blue(), 1.0),
+            ],
+        ])
+    }
+
+    #[visreg]
+    fn pattern_mesh_gradient_triangles(surface: &mut Surface) {
+        draw_mesh(surface, triangles());
+    }
+
+    #[snapshot]
+    fn pattern_mesh_gradient_triangles(page: &mut Page) {
+        let mut surface = page.surface();
+        draw_mesh(&mut surface, triangles());
+    }
+
+    #[test]
+    fn pattern_mesh_gradient_triangles_contents() {
+        let pdf = page_pdf(settings_1(), pattern_mesh_gradient_triangles_snapshot_impl);
+
+        assert!(contains(&pdf, b"/ShadingType 4"));
+        // The opacities are encoded in a soft mask.
+        assert!(contains(&pdf, b"/Luminosity"));
+    }
+
+    fn lattice() -> Mesh {
+        Mesh::Lattice {
+            vertices: vec![
+                vertex(20.0, 20.0, red(), 1.0),
+                vertex(100.0, 20.0, green(), 1.0),
+                vertex(180.0, 20.0, blue(), 1.0),
+                vertex(20.0, 180.0, blue(), 1.0),
+                vertex(100.0, 180.0, red(), 1.0),
+                vertex(180.0, 180.0, green(), 1.0),
+                // Incomplete rows are ignored.
+                vertex(20.0, 200.0, green(), 1.0),
+            ],
+            vertices_per_row: 3,
+        }
+    }
+
+    #[visreg]
+    fn pattern_mesh_gradient_lattice(surface: &mut Surface) {
+        draw_mesh(surface, lattice());
+    }
+
+    #[snapshot]
+    fn pattern_mesh_gradient_lattice(page: &mut Page) {
+        let mut surface = page.surface();
+        draw_mesh(&mut surface, lattice());
+    }
+
+    #[test]
+    fn pattern_mesh_gradient_lattice_contents() {
+        let pdf = page_pdf(settings_1(), pattern_mesh_gradient_lattice_snapshot_impl);
+
+        assert!(contains(&pdf, b"/ShadingType 5"));
+        assert!(contains(&pdf, b"/VerticesPerRow 3"));
+    }
+
+    fn coons_patch() -> Mesh {
+        let p = Point::from_xy;
+        Mesh::CoonsPatches(vec![CoonsPatch {
+            points: [
+                p(20.0, 20.0),
+                p(70.0, 0.0),
+                p(130.0, 40.0),
+                p(180.0, 20.0),
+                p(200.0, 70.0),
+                p(160.0, 130.0),
+                p(180.0, 180.0),
+                p(130.0, 200.0),
+                p(70.0, 160.0),
+                p(20.0, 180.0),
+                p(0.0, 130.0),
+                p(40.0, 70.0),
+            ],
+            colors: corners(),
+            opacities: [NormalizedF32::ONE; 4],
+        }])
+    }
+
+    #[visreg]
+    fn pattern_mesh_gradient_coons_patch(surface: &mut Surface) {
+        draw_mesh(surface, coons_patch());
+    }
+
+    #[snapshot]
+    fn pattern_mesh_gradient_coons_patch(page: &mut Page) {
+        let mut surface = page.surface();
+        draw_mesh(&mut surface, coons_patch());
+    }
+
+    #[test]
+    fn pattern_mesh_gradient_coons_patch_contents() {
+        let pdf = page_pdf(
+            settings_1(),
+            pattern_mesh_gradient_coons_patch_snapshot_impl,
+        );
+
+        assert!(contains(&pdf, b"/ShadingType 6"));
+    }
+
+    fn tensor_patch() -> Mesh {
+        let mut points = [[Point::default(); 4]; 4];
+        for (row, points) in points.iter_mut().enumerate() {
+            for (col, point) in points.iter_mut().enumerate() {
+                *point = Point::from_xy(20.0 + col as f32 * 50.0, 20.0 + row as f32 * 50.0);
+            }
+        }
+
+        Mesh::TensorPatches(vec![TensorPatch {
+            points,
+            colors: corners(),
+            opacities: [NormalizedF32::ONE; 4],
+        }])
+    }
+
+    #[visreg]
+    fn pattern_mesh_gradient_tensor_patch(surface: &mut Surface) {
+        draw_mesh(surface, tensor_patch());
+    }
+
+    #[snapshot]
+    fn pattern_mesh_gradient_tensor_patch(page: &mut Page) {
+        let mut surface = page.surface();
+        draw_mesh(&mut surface, tensor_patch());
+    }
+
+    #[snapshot(settings_7)]
+    fn pattern_mesh_gradient_tensor_patch_pdf_a(page: &mut Page) {
+        let mut surface = page.surface();
+        draw_mesh(&mut surface, tensor_patch());
+    }
+
+    #[test]
+    fn pattern_mesh_gradient_tensor_patch_pdf_a() {
+        let mut document = Document::new_with(settings_7());
+        let mut page = document.start_page();
+        pattern_mesh_gradient_tensor_patch_pdf_a_snapshot_impl(&mut page);
+        page.finish();
+
+        assert!(document.finish().is_ok());
+    }
+
+    #[test]
+    fn pattern_mesh_gradient_invalid() {
+        let new = |mesh| MeshGradient::new(mesh, Default::default(), false);
+
+        // A single row.
+        assert!(new(Mesh::Lattice {
+            vertices: vec![
+                vertex(20.0, 20.0, red(), 1.0),
+                vertex(180.0, 20.0, green(), 1.0),
+            ],
+            vertices_per_row: 2,
+        })
+        .is_err());
+        // A single vertex per row.
+        assert!(new(Mesh::Lattice {
+            vertices: vec![
+                vertex(20.0, 20.0, red(), 1.0),
+                vertex(20.0, 180.0, green(), 1.0),
+            ],
+            vertices_per_row: 1,
+        })
+        .is_err());
+        // Colors in different color spaces.
+        assert!(new(Mesh::Triangles(vec![[
+            vertex(20.0, 20.0, red(), 1.0),
+            vertex(180.0, 20.0, green(), 1.0),
+            MeshVertex {
+                point: Point::from_xy(20.0, 180.0),
+                color: luma::Color::new(0).into(),
+                opacity: NormalizedF32::ONE,
+            },
+        ]]))
+        .is_err());
+    }
+
+    fn sweep_gradient(spread_method: SpreadMethod) -> Fill {
+        Fill {
+            paint: SweepGradient {
+                cx: 100.0,
+                cy: 100.0,
+                start_angle: 30.0,
+                end_angle: 120.0,
+                transform: Default::default(),
+                spread_method,
+                stops: stops_with_3_solid_1(),
+                anti_alias: false,
+            }
+            .into(),
+            opacity: NormalizedF32::ONE,
+            rule: Default::default(),
+        }
+    }
+
+    // PostScript is forbidden in PDF/A, so Coons patches are used instead.
+    #[snapshot(settings_7)]
+    fn pattern_sweep_gradient_pad_pdf_a(page: &mut Page) {
+        let mut surface = page.surface();
+        surface.set_fill(Some(sweep_gradient(SpreadMethod::Pad)));
+        surface.draw_path(&rect_to_path(20.0, 20.0, 180.0, 180.0));
+    }
+
+    #[snapshot(settings_7)]
+    fn pattern_sweep_gradient_repeat_pdf_a(page: &mut Page) {
+        let mut surface = page.surface();
+        surface.set_fill(Some(sweep_gradient(SpreadMethod::Repeat)));
+        surface.draw_path(&rect_to_path(20.0, 20.0, 180.0, 180.0));
+    }
+
+    #[snapshot(settings_7)]
+    fn pattern_sweep_gradient_reflect_pdf_a(page: &mut Page) {
+        let mut surface = page.surface();
+        surface.set_fill(Some(sweep_gradient(SpreadMethod::Reflect)));
+        surface.draw_path(&rect_to_path(20.0, 20.0, 180.0, 180.0));
+    }
+
+    #[test]
+    fn pattern_sweep_gradient_pdf_a() {
+        for pdf in [
+            page_pdf(settings_7(), pattern_sweep_gradient_pad_pdf_a_snapshot_impl),
+            page_pdf(
+                settings_7(),
+                pattern_sweep_gradient_repeat_pdf_a_snapshot_impl,
+            ),
+            page_pdf(
+                settings_7(),
+                pattern_sweep_gradient_reflect_pdf_a_snapshot_impl,
+            ),
+        ] {
+            assert!(contains(&pdf, b"/ShadingType 6"));
+            assert!(!contains(&pdf, b"/FunctionType 4"));
+        }
+    }
+
+    // A tiny range must not produce separate patches for each of its repetitions.
+    #[snapshot(settings_7)]
+    fn pattern_sweep_gradient_tiny_range_pdf_a(page: &mut Page) {
+        let mut surface = page.surface();
+        surface.set_fill(Some(Fill {
+            paint: SweepGradient {
+                cx: 100.0,
+                cy: 100.0,
+                start_angle: 0.0,
+                end_angle: 0.0001,
+                transform: Default::default(),
+                spread_method: SpreadMethod::Repeat,
+                stops: stops_with_3_solid_1(),
+                anti_alias: false,
+            }
+            .into(),
+            opacity: NormalizedF32::ONE,
+            rule: Default::default(),
+        }));
+        surface.draw_path(&rect_to_path(20.0, 20.0, 180.0, 180.0));
+    }
+
+    #[snapshot(settings_34)]
+    fn pattern_linear_gradient_sampled(page: &mut Page) {
+        let mut surface = page.surface();
+        surface.set_fill(Some(Fill {
+            paint: LinearGradient {
+                x1: 20.0,
+                y1: 0.0,
+                x2: 180.0,
+                y2: 0.0,
+                transform: Default::default(),
+                spread_method: SpreadMethod::Pad,
+                stops: stops_with_3_solid_1(),
+                anti_alias: false,
+            }
+            .into(),
+            opacity: NormalizedF32::ONE,
+            rule: Default::default(),
+        }));
+        surface.draw_path(&rect_to_path(20.0, 20.0, 180.0, 180.0));
+    }
+
+    #[test]
+    fn pattern_linear_gradient_sampled() {
+        let pdf = page_pdf(settings_34(), pattern_linear_gradient_sampled_snapshot_impl);
+
+        assert!(contains(&pdf, b"/FunctionType 0"));
+        assert!(contains(&pdf, b"/Size [64]"));
+        assert!(!contains(&pdf, b"/FunctionType 3"));
+    }
+
+    #[snapshot(settings_34)]
+    fn pattern_sweep_gradient_sampled(page: &mut Page) {
+        let mut surface = page.surface();
+        surface.set_fill(Some(Fill {
+            paint: SweepGradient {
+                cx: 100.0,
+                cy: 100.0,
+                start_angle: 0.0,
+                end_angle: 90.0,
+                transform: Default::default(),
+                spread_method: SpreadMethod::Reflect,
+                stops: stops_with_2_solid_1(),
+                anti_alias: false,
+            }
+            .into(),
+            opacity: NormalizedF32::ONE,
+            rule: Default::default(),
+        }));
+        surface.draw_path(&rect_to_path(20.0, 20.0, 180.0, 180.0));
+    }
+
+    #[test]
+    fn pattern_sweep_gradient_sampled() {
+        let pdf = page_pdf(settings_34(), pattern_sweep_gradient_sampled_snapshot_impl);
+
+        assert!(contains(&pdf, b"/FunctionType 0"));
+        assert!(contains(&pdf, b"/Size [64 64]"));
+        assert!(!contains(&pdf, b"/FunctionType 4"));
+    }
 }
 
 mod tiling {
+    use krilla::color::rgb;
     use krilla::num::NormalizedF32;
-    use krilla::paint::{Fill, Pattern};
+    use krilla::page::Page;
+    use krilla::paint::{Fill, Pattern, UncoloredPattern};
     use krilla::surface::Surface;
-    use krilla_macros::visreg;
+    use krilla::Document;
+    use krilla_macros::{snapshot, visreg};
 
-    use crate::{basic_pattern_stream, rect_to_path};
+    use crate::{
+        basic_pattern_stream, basic_uncolored_pattern_stream, contains, count, page_pdf,
+        rect_to_path, settings_1,
+    };
 
     #[visreg(all)]
     fn pattern_tiling_basic(surface: &mut Surface) {
@@ -204,4 +586,65 @@ mod tiling {
         }));
         surface.draw_path(&path)
     }
+
+    fn pattern_tiling_uncolored_impl(surface: &mut Surface) {
+        let pattern = Pattern {
+            stream: basic_uncolored_pattern_stream(surface.stream_builder()),
+            transform: Default::default(),
+            width: 20.0,
+            height: 20.0,
+        };
+
+        // Both colors share the same pattern object.
+        for (i, color) in [rgb::Color::new(255, 0, 0), rgb::Color::new(0, 0, 255)]
+            .into_iter()
+            .enumerate()
+        {
+            surface.set_fill(Some(Fill {
+                paint: UncoloredPattern::new(pattern.clone(), color.into())
+                    .unwrap()
+                    .into(),
+                opacity: NormalizedF32::ONE,
+                rule: Default::default(),
+            }));
+            let y = 20.0 + i as f32 * 90.0;
+            surface.draw_path(&rect_to_path(20.0, y, 180.0, y + 80.0));
+        }
+    }
+
+    #[visreg]
+    fn pattern_tiling_uncolored(surface: &mut Surface) {
+        pattern_tiling_uncolored_impl(surface);
+    }
+
+    #[snapshot]
+    fn pattern_tiling_uncolored(page: &mut Page) {
+        let mut surface = page.surface();
+        pattern_tiling_uncolored_impl(&mut surface);
+    }
+
+    #[test]
+    fn pattern_tiling_uncolored_shared() {
+        let pdf = page_pdf(settings_1(), pattern_tiling_uncolored_snapshot_impl);
+
+        assert_eq!(count(&pdf, b"/PaintType 2"), 1);
+        assert_eq!(count(&pdf, b"[/Pattern /DeviceRGB]"), 1);
+        assert!(contains(&pdf, b"1 0 0 /p0 scn"));
+        assert!(contains(&pdf, b"0 0 1 /p0 scn"));
+    }
+
+    #[test]
+    fn pattern_tiling_uncolored_with_colored_stream() {
+        let mut document = Document::new();
+        let mut page = document.start_page();
+        let mut surface = page.surface();
+        let pattern = Pattern {
+            stream: basic_pattern_stream(surface.stream_builder()),
+            transform: Default::default(),
+            width: 20.0,
+            height: 20.0,
+        };
+
+        assert!(UncoloredPattern::new(pattern, rgb::Color::new(255, 0, 0).into()).is_none());
+    }
 }
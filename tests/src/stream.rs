@@ -1,4 +1,5 @@
-use krilla::blend::BlendMode;
+use krilla::blend::{BlendMode, GroupColorSpace, OverprintMode, TransparencyGroup};
+use krilla::color::RenderingIntent;
 use krilla::geom::{Point, Size, Transform};
 use krilla::page::Page;
 use krilla::text::{Font, TextDirection};
@@ -6,7 +7,7 @@ use krilla_macros::snapshot;
 
 use crate::embed::file_1;
 use crate::{blue_fill, load_png_image, red_fill, NOTO_SANS};
-use crate::{green_fill, rect_to_path, Document};
+use crate::{contains, green_fill, page_pdf, rect_to_path, settings_1, Document};
 
 #[snapshot(settings_2)]
 fn stream_resource_cache(page: &mut Page) {
@@ -53,6 +54,76 @@ fn stream_reused_graphics_state(page: &mut Page) {
     surface.draw_path(&path1);
 }
 
+#[snapshot]
+fn stream_overprint(page: &mut Page) {
+    let mut surface = page.surface();
+    let path1 = rect_to_path(0.0, 0.0, 100.0, 100.0);
+    surface.push_overprint(true, false, OverprintMode::Nonzero);
+    surface.set_fill(Some(green_fill(1.0)));
+    surface.draw_path(&path1);
+    surface.pop();
+}
+
+#[test]
+fn stream_overprint() {
+    let pdf = page_pdf(settings_1(), stream_overprint_snapshot_impl);
+
+    assert!(contains(&pdf, b"/op true"));
+    assert!(contains(&pdf, b"/OP false"));
+    assert!(contains(&pdf, b"/OPM 1"));
+}
+
+#[snapshot]
+fn stream_rendering_intent(page: &mut Page) {
+    let mut surface = page.surface();
+    let path1 = rect_to_path(0.0, 0.0, 100.0, 100.0);
+    surface.push_rendering_intent(RenderingIntent::Saturation);
+    surface.set_fill(Some(green_fill(1.0)));
+    surface.draw_path(&path1);
+    surface.pop();
+}
+
+#[test]
+fn stream_rendering_intent_contents() {
+    let pdf = page_pdf(settings_1(), stream_rendering_intent_snapshot_impl);
+    assert!(contains(&pdf, b"/RI /Saturation"));
+}
+
+#[snapshot]
+fn stream_transparency_groups(page: &mut Page) {
+    let mut surface = page.surface();
+    let path1 = rect_to_path(0.0, 0.0, 100.0, 100.0);
+    let path2 = rect_to_path(50.0, 50.0, 150.0, 150.0);
+
+    surface.push_transparency_group(TransparencyGroup {
+        isolated: false,
+        knockout: true,
+        color_space: GroupColorSpace::Rgb,
+    });
+    surface.set_fill(Some(green_fill(0.5)));
+    surface.draw_path(&path1);
+    surface.set_fill(Some(red_fill(0.5)));
+    surface.draw_path(&path2);
+    surface.pop();
+
+    surface.push_transparency_group(TransparencyGroup {
+        color_space: GroupColorSpace::Cmyk,
+        ..Default::default()
+    });
+    surface.set_fill(Some(blue_fill(0.5)));
+    surface.draw_path(&path2);
+    surface.pop();
+}
+
+#[test]
+fn stream_transparency_groups() {
+    let pdf = page_pdf(settings_1(), stream_transparency_groups_snapshot_impl);
+
+    assert!(contains(&pdf, b"/K true"));
+    assert!(contains(&pdf, b"/I true"));
+    assert!(contains(&pdf, b"/CS /DeviceCMYK"));
+}
+
 // Make sure page streams, images, etc. are flate encoded with default settings.
 #[snapshot(document, settings_29)]
 fn stream_compress_by_default(document: &mut Document) {
@@ -1,15 +1,16 @@
-use krilla::geom::Point;
+use krilla::geom::{Point, Transform};
 use krilla::page::Page;
 use krilla::paint::{Fill, LinearGradient, Paint, SpreadMethod, Stroke};
 use krilla::surface::Surface;
-use krilla::text::{Font, GlyphId, KrillaGlyph, Tag, TextDirection};
+use krilla::text::{Font, GlyphId, KrillaGlyph, Tag, TextDirection, TextState, WritingMode};
 use krilla::{Data, Document};
 use krilla_macros::{snapshot, visreg};
 
 use crate::{
-    blue_fill, blue_stroke, red_fill, red_stroke, stops_with_3_solid_1, CANTARELL_VAR,
-    LATIN_MODERN_ROMAN, LIBERTINUS_SERIF, NOTO_COLOR_EMOJI_COLR, NOTO_SANS, NOTO_SANS_ARABIC,
-    NOTO_SANS_CJK, NOTO_SANS_DEVANAGARI, NOTO_SANS_VAR, TWITTER_COLOR_EMOJI,
+    blue_fill, blue_stroke, contains, page_pdf, rect_to_path, red_fill, red_stroke, settings_1,
+    stops_with_3_solid_1, CANTARELL_VAR, FONT_PATH, LATIN_MODERN_ROMAN, LIBERTINUS_SERIF,
+    NOTO_COLOR_EMOJI_COLR, NOTO_SANS, NOTO_SANS_ARABIC, NOTO_SANS_CJK, NOTO_SANS_DEVANAGARI,
+    NOTO_SANS_VAR, TWITTER_COLOR_EMOJI,
 };
 
 fn text_gradient(spread_method: SpreadMethod) -> LinearGradient {
@@ -202,6 +203,29 @@ fn text_direction_ttb(surface: &mut Surface) {
     );
 }
 
+fn text_vertical_impl(surface: &mut Surface, outlined: bool) {
+    let font = Font::new(NOTO_SANS_CJK.clone(), 0).unwrap();
+    surface.set_writing_mode(WritingMode::Vertical);
+    surface.draw_text(
+        Point::from_xy(100.0, 20.0),
+        font,
+        20.0,
+        "你好，这是一段文字。",
+        outlined,
+        TextDirection::TopToBottom,
+    );
+}
+
+#[visreg]
+fn text_vertical_writing_mode(surface: &mut Surface) {
+    text_vertical_impl(surface, false);
+}
+
+#[visreg]
+fn text_vertical_writing_mode_outlined(surface: &mut Surface) {
+    text_vertical_impl(surface, true);
+}
+
 #[visreg]
 fn text_direction_btt(surface: &mut Surface) {
     let font = Font::new(NOTO_SANS_CJK.clone(), 0).unwrap();
@@ -436,6 +460,206 @@ fn text_two_fonts_reproducibility() {
     }
 }
 
+fn text_invisible_impl(surface: &mut Surface) {
+    surface.set_fill(Some(red_fill(1.0)));
+    surface.set_invisible_text(true);
+    surface.draw_text(
+        Point::from_xy(0.0, 50.0),
+        Font::new(NOTO_SANS.clone(), 0).unwrap(),
+        16.0,
+        "hi there",
+        false,
+        TextDirection::Auto,
+    );
+    // Type3 fonts are usually always filled, but invisible text takes precedence.
+    surface.draw_text(
+        Point::from_xy(0.0, 80.0),
+        Font::new(NOTO_COLOR_EMOJI_COLR.clone(), 0).unwrap(),
+        16.0,
+        "😀",
+        true,
+        TextDirection::Auto,
+    );
+}
+
+#[visreg]
+fn text_invisible(surface: &mut Surface) {
+    text_invisible_impl(surface);
+}
+
+#[snapshot]
+fn text_invisible(page: &mut Page) {
+    let mut surface = page.surface();
+    text_invisible_impl(&mut surface);
+}
+
+#[test]
+fn text_invisible_contents() {
+    let pdf = page_pdf(settings_1(), text_invisible_snapshot_impl);
+
+    assert!(contains(&pdf, b"3 Tr"));
+    assert!(contains(&pdf, b"/ToUnicode"));
+    assert!(!contains(&pdf, b"0 Tr"));
+    assert!(!contains(&pdf, b"1 0 0 rg"));
+}
+
+fn text_clip_impl(surface: &mut Surface, fill: Option<Fill>, stroke: Option<Stroke>) {
+    let font = Font::new(NOTO_SANS.clone(), 0).unwrap();
+    let glyphs = [(38, 0..1), (79, 1..2), (76, 2..3), (83, 3..4)]
+        .map(|(id, range)| KrillaGlyph::new(GlyphId::new(id), 0.6, 0.0, 0.0, 0.0, range, None));
+
+    surface.set_fill(fill);
+    surface.set_stroke(stroke);
+    surface.push_transform(&Transform::from_scale(2.0, 2.0));
+    surface.push_text_clip(Point::from_xy(0.0, 40.0), &glyphs, font, "Clip", 32.0);
+    surface.set_fill(Some(blue_fill(1.0)));
+    surface.set_stroke(None);
+    surface.draw_path(&rect_to_path(0.0, 0.0, 100.0, 50.0));
+    surface.pop();
+    surface.pop();
+    // Nothing drawn after the clip should be clipped.
+    surface.draw_path(&rect_to_path(20.0, 120.0, 180.0, 180.0));
+}
+
+#[visreg]
+fn text_clip_only(surface: &mut Surface) {
+    text_clip_impl(surface, None, None);
+}
+
+#[snapshot]
+fn text_clip_only(page: &mut Page) {
+    let mut surface = page.surface();
+    text_clip_impl(&mut surface, None, None);
+}
+
+#[test]
+fn text_clip_only_contents() {
+    let pdf = page_pdf(settings_1(), text_clip_only_snapshot_impl);
+
+    assert!(contains(&pdf, b"7 Tr"));
+    assert!(contains(&pdf, b"/ToUnicode"));
+    // The transform is undone after the text object, since `Q` would remove the clip.
+    assert!(contains(&pdf, b"2 0 0 -2 0 842 cm"));
+    assert!(contains(&pdf, b"0.5 0 0 -0.5 0 421 cm"));
+}
+
+#[visreg]
+fn text_clip_fill_stroke(surface: &mut Surface) {
+    text_clip_impl(surface, Some(red_fill(0.5)), Some(red_stroke(1.0, 3.0)));
+}
+
+#[snapshot]
+fn text_clip_fill_stroke(page: &mut Page) {
+    let mut surface = page.surface();
+    text_clip_impl(
+        &mut surface,
+        Some(red_fill(0.5)),
+        Some(red_stroke(1.0, 3.0)),
+    );
+}
+
+#[test]
+fn text_clip_fill_stroke_contents() {
+    let pdf = page_pdf(settings_1(), text_clip_fill_stroke_snapshot_impl);
+
+    assert!(contains(&pdf, b"6 Tr"));
+    assert!(contains(&pdf, b"3 w"));
+    // All changes to the graphics state are reset after the text object.
+    assert!(contains(&pdf, b"1 w"));
+    assert!(contains(&pdf, b"/ca 1"));
+}
+
+fn text_state_impl(surface: &mut Surface) {
+    let font = Font::new(NOTO_SANS.clone(), 0).unwrap();
+    surface.draw_text(
+        Point::from_xy(0.0, 50.0),
+        font.clone(),
+        16.0,
+        "hi there",
+        false,
+        TextDirection::Auto,
+    );
+    surface.set_text_state(TextState {
+        character_spacing: 1.0,
+        word_spacing: 5.0,
+        horizontal_scaling: 0.5,
+        rise: 2.0,
+    });
+    surface.draw_text(
+        Point::from_xy(0.0, 80.0),
+        font,
+        16.0,
+        "hi there",
+        false,
+        TextDirection::Auto,
+    );
+}
+
+#[visreg]
+fn text_state(surface: &mut Surface) {
+    text_state_impl(surface);
+}
+
+#[snapshot]
+fn text_state(page: &mut Page) {
+    let mut surface = page.surface();
+    text_state_impl(&mut surface);
+}
+
+#[test]
+fn text_state_contents() {
+    let pdf = page_pdf(settings_1(), text_state_snapshot_impl);
+
+    assert!(contains(&pdf, b"1 Tc"));
+    assert!(contains(&pdf, b"50 Tz"));
+    assert!(contains(&pdf, b"2 Ts"));
+    // Word spacing only applies to single-byte codes, so it is written as an adjustment
+    // of 5 / 16 * 1000 instead.
+    assert!(!contains(&pdf, b"Tw"));
+    assert!(contains(&pdf, b"-312.5"));
+}
+
+// The vertical text uses its own font, while the horizontal text still uses
+// the horizontal one.
+#[snapshot]
+fn text_vertical(page: &mut Page) {
+    let mut surface = page.surface();
+    let font_data: Data = std::fs::read(FONT_PATH.join("NotoSerifSC_subset1.ttf"))
+        .unwrap()
+        .into();
+    let font = Font::new(font_data, 0).unwrap();
+
+    surface.set_writing_mode(WritingMode::Vertical);
+    surface.draw_text(
+        Point::from_xy(100.0, 20.0),
+        font.clone(),
+        16.0,
+        "智智智",
+        false,
+        TextDirection::TopToBottom,
+    );
+    surface.set_writing_mode(WritingMode::Horizontal);
+    surface.draw_text(
+        Point::from_xy(0.0, 50.0),
+        font,
+        16.0,
+        "智",
+        false,
+        TextDirection::Auto,
+    );
+}
+
+#[test]
+fn text_vertical_contents() {
+    let pdf = page_pdf(settings_1(), text_vertical_snapshot_impl);
+
+    assert!(contains(&pdf, b"/Identity-V"));
+    assert!(contains(&pdf, b"/Identity-H"));
+    assert!(contains(&pdf, b"/WMode 1"));
+    assert!(contains(&pdf, b"/DW2 ["));
+    assert!(contains(&pdf, b"/W2 ["));
+}
+
 fn variable_impl(surface: &mut Surface, coords: Vec<Vec<(Tag, f32)>>, font: Data, text: &str) {
     let mut cur_y = 20.0;
 
@@ -1,15 +1,23 @@
 use krilla::action::LinkAction;
-use krilla::annotation::{Annotation, LinkAnnotation, Target};
-use krilla::color::{rgb, separation};
+use krilla::annotation::{
+    Annotation, FileAttachmentAnnotation, LinkAnnotation, ScreenAnnotation, StampAnnotation,
+    StampName, Target,
+};
+use krilla::blend::{GroupColorSpace, OverprintMode, TransparencyGroup};
+use krilla::color::{devicen, rgb, separation};
 use krilla::configure::validate::VersionedFeature;
-use krilla::configure::{Accessibility, ConfigurationBuilder, PdfVersion, ValidationError};
+use krilla::configure::{
+    Accessibility, ConfigurationBuilder, PdfVersion, PrintExchange, ValidationError,
+};
 use krilla::embed::EmbedError;
 use krilla::error::KrillaError;
+use krilla::form::{RadioButton, RadioGroup, TextField};
 use krilla::geom::{Point, Rect, Size};
+use krilla::icc::OutputIntent;
 use krilla::metadata::{DateTime, Metadata};
 use krilla::num::NormalizedF32;
 use krilla::outline::Outline;
-use krilla::page::Page;
+use krilla::page::{Page, PageSettings};
 use krilla::paint::{Fill, FillRule, LinearGradient, SpreadMethod};
 use krilla::tagging::{Artifact, ArtifactType, ContentTag, SpanTag, TagGroup, TagKind, TagTree};
 use krilla::tagging::{ListNumbering, TableHeaderScope, Tag};
@@ -19,11 +27,11 @@ use krilla_macros::snapshot;
 
 use crate::embed::{embedded_file_impl, file_1};
 use crate::{
-    blue_fill, cmyk_fill, dummy_text_with_spans, green_fill, load_jpg_image, load_png_image, loc,
-    metadata_1, metadata_2, rect_to_path, red_fill, settings_1, settings_13, settings_15,
-    settings_17, settings_19, settings_20, settings_23, settings_24, settings_32, settings_33,
-    settings_7, settings_8, settings_9, stops_with_2_solid_1, validation_errors, youtube_link,
-    NOTO_SANS,
+    blue_fill, cmyk_fill, contains, dummy_text_with_spans, green_fill, load_jpg_image,
+    load_png_image, loc, metadata_1, metadata_2, rect_to_path, red_fill, settings_1, settings_13,
+    settings_15, settings_17, settings_19, settings_20, settings_23, settings_24, settings_32,
+    settings_33, settings_7, settings_8, settings_9, stops_with_2_solid_1, swop, validation_errors,
+    youtube_link, NOTO_SANS,
 };
 use crate::{Document, SerializeSettings};
 
@@ -143,6 +151,89 @@ fn validate_pdf_a_postscript() {
     )
 }
 
+#[test]
+fn validate_pdf_a_nonzero_overprint_mode() {
+    let mut document = pdfa_document();
+    let mut page = document.start_page();
+    let mut surface = page.surface();
+
+    surface.push_overprint(true, true, OverprintMode::Zero);
+    surface.set_fill(Some(green_fill(1.0)));
+    surface.draw_path(&rect_to_path(0.0, 0.0, 100.0, 100.0));
+    surface.pop();
+
+    surface.push_overprint(true, false, OverprintMode::Nonzero);
+    surface.set_fill(Some(green_fill(1.0)));
+    surface.draw_path(&rect_to_path(0.0, 0.0, 100.0, 100.0));
+    surface.pop();
+
+    surface.finish();
+    page.finish();
+
+    assert_eq!(
+        validation_errors(document.finish()),
+        vec![ValidationError::NonzeroOverprintMode(None)]
+    )
+}
+
+#[test]
+fn validate_pdf_a_cmyk_transparency_group_without_profile() {
+    let mut document = pdfa_document();
+    let mut page = document.start_page();
+    let mut surface = page.surface();
+
+    surface.push_transparency_group(TransparencyGroup {
+        color_space: GroupColorSpace::Cmyk,
+        ..Default::default()
+    });
+    surface.set_fill(Some(green_fill(1.0)));
+    surface.draw_path(&rect_to_path(0.0, 0.0, 100.0, 100.0));
+    surface.pop();
+
+    surface.finish();
+    page.finish();
+
+    assert_eq!(
+        validation_errors(document.finish()),
+        vec![ValidationError::MissingCMYKProfile]
+    )
+}
+
+#[test]
+fn validate_pdf_a_postscript_sampled() {
+    let mut document = Document::new_with(SerializeSettings {
+        gradient_samples: Some(128),
+        ..settings_7()
+    });
+    let mut page = document.start_page();
+
+    let gradient = LinearGradient {
+        x1: 50.0,
+        y1: 0.0,
+        x2: 150.0,
+        y2: 0.0,
+        transform: Default::default(),
+        spread_method: SpreadMethod::Repeat,
+        stops: stops_with_2_solid_1(),
+        anti_alias: false,
+    };
+
+    let fill = Fill {
+        paint: gradient.into(),
+        ..Default::default()
+    };
+
+    let mut surface = page.surface();
+
+    surface.set_fill(Some(fill));
+    surface.draw_path(&rect_to_path(0.0, 0.0, 100.0, 100.0));
+
+    surface.finish();
+    page.finish();
+
+    assert!(document.finish().is_ok());
+}
+
 #[test]
 pub fn validate_disabled_q_nesting_28() {
     let document = q_nesting_impl(SerializeSettings::default());
@@ -507,6 +598,33 @@ fn validate_pdf_a4e_full_example(document: &mut Document) {
     validate_pdf_full_example(document);
 }
 
+#[test]
+fn validate_pdf_a_stamp_annotation_without_appearance() {
+    let mut document = pdfa_document();
+    let mut page = document.start_page();
+
+    let annot_loc = loc(1);
+    page.add_annotation(
+        Annotation::new_stamp(
+            StampAnnotation::new(
+                Rect::from_xywh(50.0, 50.0, 100.0, 50.0).unwrap(),
+                StampName::Approved,
+            ),
+            Some("Approved".to_string()),
+        )
+        .with_location(Some(annot_loc)),
+    );
+
+    page.finish();
+
+    assert_eq!(
+        validation_errors(document.finish()),
+        vec![ValidationError::MissingAnnotationAppearance(Some(
+            annot_loc
+        ))]
+    );
+}
+
 #[test]
 fn validate_pdf_ua1_empty_annotation_alt() {
     let mut document = Document::new_with(settings_15());
@@ -538,6 +656,108 @@ fn validate_pdf_ua1_empty_annotation_alt() {
         .contains(&ValidationError::MissingAnnotationAltText(Some(annot_loc))));
 }
 
+fn text_field() -> TextField {
+    TextField::new(
+        "name".to_string(),
+        Rect::from_xywh(50.0, 50.0, 100.0, 20.0).unwrap(),
+        Font::new(NOTO_SANS.clone(), 0).unwrap(),
+        12.0,
+    )
+    .with_default_value("John Doe".to_string())
+}
+
+#[test]
+fn validate_pdf_ua1_untagged_form_field_without_tooltip() {
+    let mut document = Document::new_with(settings_15());
+    let mut page = document.start_page();
+
+    let annot_loc = loc(1);
+    page.add_annotation(
+        Annotation::new_widget(text_field().into(), None).with_location(Some(annot_loc)),
+    );
+    page.finish();
+
+    let errors = validation_errors(document.finish());
+    assert!(errors.contains(&ValidationError::MissingFieldTooltip(Some(annot_loc))));
+    assert!(errors.contains(&ValidationError::UntaggedFormField(Some(annot_loc))));
+    assert!(!errors.contains(&ValidationError::MissingAnnotationAltText(Some(annot_loc))));
+}
+
+#[test]
+fn validate_pdf_ua1_tagged_form_field() {
+    let mut document = Document::new_with(settings_15());
+    let mut page = document.start_page();
+
+    let annot_loc = loc(1);
+    let annot = page.add_tagged_annotation(
+        Annotation::new_widget(text_field().into(), Some("Your name".to_string()))
+            .with_location(Some(annot_loc)),
+    );
+    page.finish();
+
+    let mut form = TagGroup::new(Tag::Form);
+    form.push(annot);
+
+    let mut tag_tree = TagTree::new();
+    tag_tree.push(form);
+    document.set_tag_tree(tag_tree);
+
+    let errors = validation_errors(document.finish());
+    assert!(!errors.contains(&ValidationError::MissingFieldTooltip(Some(annot_loc))));
+    assert!(!errors.contains(&ValidationError::UntaggedFormField(Some(annot_loc))));
+}
+
+#[test]
+fn validate_pdf_ua1_form_field_outside_form_tag() {
+    let mut document = Document::new_with(settings_15());
+    let mut page = document.start_page();
+
+    let annot_loc = loc(1);
+    let annot = page.add_tagged_annotation(
+        Annotation::new_widget(text_field().into(), Some("Your name".to_string()))
+            .with_location(Some(annot_loc)),
+    );
+    page.finish();
+
+    let mut paragraph = TagGroup::new(Tag::P);
+    paragraph.push(annot);
+
+    let mut tag_tree = TagTree::new();
+    tag_tree.push(paragraph);
+    document.set_tag_tree(tag_tree);
+
+    assert!(validation_errors(document.finish())
+        .contains(&ValidationError::UntaggedFormField(Some(annot_loc))));
+}
+
+// The tooltip of the first button is also used for the field of the group.
+#[snapshot(document, settings_15)]
+fn validate_pdf_ua1_radio_group(document: &mut Document) {
+    document.set_metadata(Metadata::new().title("Form".into()).language("en".into()));
+    document.set_outline(Outline::new());
+
+    let mut page = document.start_page();
+    let group = RadioGroup::new("choice".to_string());
+
+    let mut form = TagGroup::new(Tag::Form);
+    for (i, value) in ["A", "B"].into_iter().enumerate() {
+        let rect = Rect::from_xywh(20.0 + i as f32 * 30.0, 20.0, 20.0, 20.0).unwrap();
+        form.push(
+            page.add_tagged_annotation(Annotation::new_widget(
+                RadioButton::new(group.clone(), value.to_string(), rect)
+                    .unwrap()
+                    .into(),
+                Some(format!("Choice {value}")),
+            )),
+        );
+    }
+    page.finish();
+
+    let mut tag_tree = TagTree::new();
+    tag_tree.push(form);
+    document.set_tag_tree(tag_tree);
+}
+
 #[test]
 fn validate_pdf_ua1_empty_alt() {
     let mut document = Document::new_with(settings_15());
@@ -821,6 +1041,56 @@ fn validate_pdf_a3_missing_fields() {
     )
 }
 
+#[test]
+fn validate_pdf_a2_file_attachment_annotation() {
+    let mut document = pdfa_document();
+    let mut page = document.start_page();
+
+    let file_loc = loc(1);
+    let annot_loc = loc(2);
+    let mut f1 = file_1();
+    f1.location = Some(file_loc);
+    page.add_annotation(
+        Annotation::new_file_attachment(
+            FileAttachmentAnnotation::new(Rect::from_xywh(50.0, 50.0, 20.0, 20.0).unwrap(), f1),
+            Some("Attached file".to_string()),
+        )
+        .with_location(Some(annot_loc)),
+    );
+
+    page.finish();
+
+    assert_eq!(
+        validation_errors(document.finish()),
+        vec![
+            ValidationError::EmbeddedFile(EmbedError::Existence, Some(file_loc)),
+            ValidationError::MissingAnnotationAppearance(Some(annot_loc))
+        ]
+    );
+}
+
+#[test]
+fn validate_pdf_a3_screen_annotation() {
+    let mut document = Document::new_with(settings_23());
+    let mut page = document.start_page();
+
+    let annot_loc = loc(1);
+    page.add_annotation(
+        Annotation::new_screen(
+            ScreenAnnotation::new(Rect::from_xywh(50.0, 50.0, 100.0, 100.0).unwrap(), file_1()),
+            Some("A video".to_string()),
+        )
+        .with_location(Some(annot_loc)),
+    );
+
+    page.finish();
+
+    assert_eq!(
+        validation_errors(document.finish()),
+        vec![ValidationError::Multimedia(Some(annot_loc))]
+    );
+}
+
 #[snapshot(document, settings_23)]
 fn validate_pdf_a3_with_embedded_file(d: &mut Document) {
     embedded_file_impl(d)
@@ -977,6 +1247,81 @@ fn validate_inconsistent_separation_fallback() {
     );
 }
 
+#[test]
+fn validate_inconsistent_devicen_fallback() {
+    let mut document = Document::new_with(settings_7());
+    let mut page = document.start_page();
+    let mut surface = page.surface();
+
+    let colorant = || separation::SeparationColorant::Custom("Orange".to_string());
+    let separation =
+        separation::SeparationSpace::new(colorant(), rgb::Color::new(255, 128, 0).into());
+    let color1: krilla::color::Color = separation::Color::new(255, separation).into();
+    surface.set_fill(Some(Fill {
+        paint: color1.into(),
+        opacity: NormalizedF32::ONE,
+        rule: Default::default(),
+    }));
+    surface.draw_path(&rect_to_path(0.0, 0.0, 20.0, 20.0));
+
+    // The Orange colorant of the DeviceN space uses a different fallback.
+    let devicen = devicen::DeviceNSpace::new(vec![
+        (colorant(), rgb::Color::new(255, 0, 0).into()),
+        (
+            separation::SeparationColorant::Custom("Green".to_string()),
+            rgb::Color::new(0, 255, 0).into(),
+        ),
+    ])
+    .unwrap();
+    let color2: krilla::color::Color = devicen::Color::new(vec![255, 255], devicen).into();
+    surface.set_fill(Some(Fill {
+        paint: color2.into(),
+        opacity: NormalizedF32::ONE,
+        rule: Default::default(),
+    }));
+    surface.draw_path(&rect_to_path(30.0, 0.0, 50.0, 20.0));
+
+    surface.finish();
+    page.finish();
+
+    assert_eq!(
+        validation_errors(document.finish()),
+        vec![ValidationError::InconsistentSeparationFallback(colorant())]
+    );
+}
+
+#[test]
+fn validate_pdf_a1_too_many_colorants() {
+    let mut document = Document::new_with(settings_19());
+    let mut page = document.start_page();
+    let mut surface = page.surface();
+
+    let colorants = (0..9)
+        .map(|i| {
+            (
+                separation::SeparationColorant::Custom(format!("Spot{i}")),
+                rgb::Color::new(0, 0, 255).into(),
+            )
+        })
+        .collect();
+    let space = devicen::DeviceNSpace::new(colorants).unwrap();
+    let color: krilla::color::Color = devicen::Color::new(vec![255; 9], space).into();
+    surface.set_fill(Some(Fill {
+        paint: color.into(),
+        opacity: NormalizedF32::ONE,
+        rule: Default::default(),
+    }));
+    surface.draw_path(&rect_to_path(0.0, 0.0, 20.0, 20.0));
+
+    surface.finish();
+    page.finish();
+
+    assert_eq!(
+        validation_errors(document.finish()),
+        vec![ValidationError::TooManyColorants]
+    );
+}
+
 fn header_footer_artifact_subtypes_impl(settings: SerializeSettings) -> Document {
     let mut document = Document::new_with(settings);
     let mut page = document.start_page();
@@ -1236,3 +1581,211 @@ fn validate_pdf14_tagged_annotation_no_ua() {
 
     assert!(document.finish().is_ok());
 }
+
+fn pdf_x_document(x: PrintExchange, output_intents: Vec<OutputIntent>) -> Document {
+    let mut document = Document::new_with(SerializeSettings {
+        configuration: ConfigurationBuilder::new()
+            .with_print_exchange_validator(x)
+            .finish()
+            .unwrap(),
+        output_intents,
+        ..settings_1()
+    });
+    document.set_metadata(pdf_x_metadata());
+
+    document
+}
+
+fn pdf_x_page(document: &mut Document, fill: Fill) {
+    let mut page = document.start_page_with(
+        PageSettings::from_wh(200.0, 200.0)
+            .unwrap()
+            .with_trim_box(Rect::from_xywh(10.0, 10.0, 180.0, 180.0)),
+    );
+    let mut surface = page.surface();
+    surface.set_fill(Some(fill));
+    surface.draw_path(&rect_to_path(20.0, 20.0, 180.0, 180.0));
+    surface.finish();
+    page.finish();
+}
+
+fn pdf_x_metadata() -> Metadata {
+    Metadata::new()
+        .title("Print job".to_string())
+        .creation_date(DateTime::new(2001))
+}
+
+#[snapshot(document, settings_39)]
+fn validate_pdf_x4_full_example(document: &mut Document) {
+    document.set_metadata(pdf_x_metadata());
+    pdf_x_page(document, cmyk_fill(0.5));
+}
+
+#[test]
+fn validate_pdf_x4_full_example() {
+    let mut document = pdf_x_document(PrintExchange::X4, vec![swop()]);
+    pdf_x_page(&mut document, cmyk_fill(0.5));
+    let pdf = document.finish().unwrap();
+
+    assert!(contains(&pdf, b"%PDF-1.6"));
+    assert!(contains(&pdf, b"/GTS_PDFXVersion (PDF/X-4)"));
+    assert!(contains(&pdf, b"/Trapped /False"));
+    assert!(contains(&pdf, b"/S /GTS_PDFX"));
+    assert!(contains(&pdf, b"pdfxid:GTS_PDFXVersion"));
+}
+
+#[test]
+fn validate_pdf_x1a_version_and_conformance() {
+    let mut document = Document::new_with(SerializeSettings {
+        configuration: ConfigurationBuilder::new()
+            .with_print_exchange_validator(PrintExchange::X1a)
+            .finish()
+            .unwrap(),
+        output_intents: vec![swop()],
+        xmp_metadata: true,
+        ..settings_1()
+    });
+    document.set_metadata(pdf_x_metadata());
+    pdf_x_page(&mut document, cmyk_fill(1.0));
+    let pdf = document.finish().unwrap();
+
+    assert!(contains(&pdf, b"/GTS_PDFXVersion (PDF/X-1:2003)"));
+    assert!(contains(&pdf, b"/GTS_PDFXConformance (PDF/X-1a:2003)"));
+    assert!(contains(
+        &pdf,
+        b"<pdfxid:GTS_PDFXVersion>PDF/X-1:2003</pdfxid:GTS_PDFXVersion>"
+    ));
+    assert!(contains(
+        &pdf,
+        b"<pdfxid:GTS_PDFXConformance>PDF/X-1a:2003</pdfxid:GTS_PDFXConformance>"
+    ));
+}
+
+#[test]
+fn validate_pdf_x_missing_output_intent_and_trim_box() {
+    let mut document = pdf_x_document(PrintExchange::X4, vec![]);
+    let mut page = document.start_page();
+    page.surface().finish();
+    page.finish();
+
+    let errors = validation_errors(document.finish());
+    assert!(errors.contains(&ValidationError::MissingOutputIntent));
+    assert!(errors.contains(&ValidationError::MissingTrimBox(0)));
+}
+
+#[test]
+fn validate_pdf_x1a_rgb() {
+    let mut document = pdf_x_document(PrintExchange::X1a, vec![swop()]);
+    pdf_x_page(&mut document, red_fill(1.0));
+
+    assert!(validation_errors(document.finish())
+        .iter()
+        .any(|e| matches!(e, ValidationError::NonCmykColorSpace(_))));
+}
+
+#[snapshot(document, settings_40)]
+fn validate_pdf_x3_rgb(document: &mut Document) {
+    document.set_metadata(pdf_x_metadata());
+    pdf_x_page(document, red_fill(1.0));
+}
+
+#[test]
+fn validate_pdf_x3_rgb() {
+    let mut document = pdf_x_document(PrintExchange::X3, vec![swop()]);
+    pdf_x_page(&mut document, red_fill(1.0));
+    let pdf = document.finish().unwrap();
+
+    // RGB colors are device-independent in PDF/X-3.
+    assert!(contains(&pdf, b"/ICCBased"));
+    assert!(!contains(&pdf, b"/DeviceRGB"));
+    assert!(contains(&pdf, b"/GTS_PDFXVersion (PDF/X-3:2003)"));
+}
+
+#[test]
+fn validate_pdf_x3_transparency() {
+    let mut document = pdf_x_document(PrintExchange::X3, vec![swop()]);
+    pdf_x_page(&mut document, cmyk_fill(0.5));
+
+    assert!(validation_errors(document.finish())
+        .iter()
+        .any(|e| matches!(e, ValidationError::Transparency(_))));
+}
+
+#[test]
+fn validate_pdf_x4_missing_output_intent_profile() {
+    let output_intent = OutputIntent::new("FOGRA39").with_registry_name("http://www.color.org");
+    let mut document = pdf_x_document(PrintExchange::X4, vec![output_intent]);
+    pdf_x_page(&mut document, cmyk_fill(1.0));
+
+    assert_eq!(
+        validation_errors(document.finish()),
+        vec![ValidationError::MissingOutputIntentProfile]
+    );
+}
+
+#[test]
+fn validate_pdf_x3_output_intent_without_profile() {
+    let output_intent = OutputIntent::new("FOGRA39").with_registry_name("http://www.color.org");
+    let mut document = pdf_x_document(PrintExchange::X3, vec![output_intent]);
+    pdf_x_page(&mut document, cmyk_fill(1.0));
+
+    // PDF/X-3 allows referencing a registered printing condition instead.
+    assert!(document.finish().is_ok());
+}
+
+fn pdf_x_annotation_document(annotation: Annotation) -> Document {
+    let mut document = pdf_x_document(PrintExchange::X4, vec![swop()]);
+    let mut page = document.start_page_with(
+        PageSettings::from_wh(200.0, 200.0)
+            .unwrap()
+            .with_trim_box(Rect::from_xywh(10.0, 10.0, 180.0, 180.0))
+            .with_bleed_box(Rect::from_xywh(5.0, 5.0, 190.0, 190.0)),
+    );
+    page.add_annotation(annotation.with_location(Some(loc(1))));
+    page.finish();
+
+    document
+}
+
+#[test]
+fn validate_pdf_x_annotation_in_bleed_box() {
+    let document = pdf_x_annotation_document(youtube_link(20.0, 20.0, 50.0, 50.0));
+
+    assert_eq!(
+        validation_errors(document.finish()),
+        vec![ValidationError::AnnotationInBleedBox(Some(loc(1)))]
+    );
+}
+
+#[test]
+fn validate_pdf_x_form_field_in_bleed_box() {
+    let document = pdf_x_annotation_document(text_field().into());
+
+    assert!(validation_errors(document.finish())
+        .contains(&ValidationError::AnnotationInBleedBox(Some(loc(1)))));
+}
+
+#[test]
+fn validate_pdf_x_annotation_outside_bleed_box() {
+    let document = pdf_x_annotation_document(youtube_link(195.0, 0.0, 5.0, 5.0));
+
+    assert!(document.finish().is_ok());
+}
+
+#[test]
+fn validate_pdf_x_annotation_in_media_box() {
+    let mut document = pdf_x_document(PrintExchange::X4, vec![swop()]);
+    let mut page = document.start_page_with(
+        PageSettings::from_wh(200.0, 200.0)
+            .unwrap()
+            .with_trim_box(Rect::from_xywh(10.0, 10.0, 180.0, 180.0)),
+    );
+    page.add_annotation(youtube_link(195.0, 0.0, 5.0, 5.0).with_location(Some(loc(1))));
+    page.finish();
+
+    // Without a bleed box or crop box, the whole media box is printed.
+    assert_eq!(
+        validation_errors(document.finish()),
+        vec![ValidationError::AnnotationInBleedBox(Some(loc(1)))]
+    );
+}